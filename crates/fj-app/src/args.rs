@@ -1,4 +1,4 @@
-use std::{path::PathBuf, str::FromStr as _};
+use std::{path::PathBuf, str::FromStr as _, time::Duration};
 
 use anyhow::anyhow;
 use fj_host::Parameters;
@@ -16,6 +16,13 @@ pub struct Args {
     #[clap(short, long)]
     pub export: Option<PathBuf>,
 
+    /// Export the model, even if it is not a closed 2-manifold
+    ///
+    /// By default, exporting such a model fails. With this flag, a warning is
+    /// logged instead.
+    #[clap(long)]
+    pub export_invalid: bool,
+
     /// Parameters for the model, each in the form `key=value`
     #[clap(short, long, parse(try_from_str = parse_parameters))]
     pub parameters: Option<Parameters>,
@@ -23,6 +30,64 @@ pub struct Args {
     /// Model deviation tolerance
     #[clap[short, long, parse(try_from_str = parse_tolerance)]]
     pub tolerance: Option<Tolerance>,
+
+    /// Print the mass properties of the model, instead of displaying it
+    ///
+    /// The properties are computed from the exact geometry of the model. Faces
+    /// without a closed form, like those bounded by NURBS curves, are
+    /// approximated by triangles, and are only as precise as the tolerance.
+    #[clap(long)]
+    pub mass_properties: bool,
+
+    /// Check whether the model is a closed, consistently oriented solid
+    #[clap(long)]
+    pub validate_solid: bool,
+
+    /// Density of the model's material, used for its mass properties
+    #[clap(long, parse(try_from_str = parse_density))]
+    pub density: Option<Scalar>,
+
+    /// Build the model with the release profile
+    #[clap(long)]
+    pub release: bool,
+
+    /// Build the model with the given Cargo profile
+    #[clap(long, conflicts_with = "release")]
+    pub profile: Option<String>,
+
+    /// Features of the model to enable, separated by commas
+    #[clap(long, use_value_delimiter = true)]
+    pub features: Option<Vec<String>>,
+
+    /// Build the model for the given target, instead of the host
+    #[clap(long)]
+    pub target: Option<String>,
+
+    /// Evaluate the model in a separate process
+    ///
+    /// A model that crashes or doesn't finish in time is killed, and reported
+    /// as an error, without affecting the application.
+    #[clap(long)]
+    pub sandbox: bool,
+
+    /// Compile the model to WebAssembly, and run it in an embedded runtime
+    ///
+    /// Requires the `wasm32-unknown-unknown` target to be installed. Combine
+    /// with `--sandbox`, to apply a timeout.
+    #[cfg(feature = "wasm")]
+    #[clap(long)]
+    pub wasm: bool,
+
+    /// How long a sandboxed model may take to evaluate, in seconds
+    #[clap(long, requires = "sandbox", parse(try_from_str = parse_timeout))]
+    pub timeout: Option<Duration>,
+
+    /// How much memory a sandboxed model may use, in MiB
+    ///
+    /// Only supported on Unix-like systems, where this limits the address
+    /// space of the process that evaluates the model.
+    #[clap(long, requires = "sandbox")]
+    pub memory_limit: Option<u64>,
 }
 
 impl Args {
@@ -63,3 +128,21 @@ fn parse_tolerance(input: &str) -> anyhow::Result<Tolerance> {
 
     Ok(tolerance)
 }
+
+fn parse_density(input: &str) -> anyhow::Result<Scalar> {
+    let density = f64::from_str(input)?;
+    if !(density > 0. && density.is_finite()) {
+        return Err(anyhow!("Density must be a positive number"));
+    }
+
+    Ok(Scalar::from_f64(density))
+}
+
+fn parse_timeout(input: &str) -> anyhow::Result<Duration> {
+    let timeout = f64::from_str(input)?;
+    if !(timeout > 0. && timeout.is_finite()) {
+        return Err(anyhow!("Timeout must be a positive number of seconds"));
+    }
+
+    Ok(Duration::from_secs_f64(timeout))
+}
@@ -12,6 +12,9 @@ pub struct Config {
     pub default_path: Option<PathBuf>,
     pub default_model: Option<PathBuf>,
     pub target_dir: Option<PathBuf>,
+    pub profile: Option<String>,
+    pub features: Option<Vec<String>>,
+    pub target: Option<String>,
 }
 
 impl Config {
@@ -19,15 +19,21 @@ use std::path::PathBuf;
 
 use anyhow::{anyhow, Context as _};
 use fj_export::export;
-use fj_host::{Model, Parameters};
+use fj_host::{BuildOptions, Diagnostic, Model, Parameters, Profile, Sandbox};
+use fj_math::Scalar;
 use fj_operations::shape_processor::ShapeProcessor;
 use fj_viewer::run::run;
+use tracing::warn;
 use tracing_subscriber::fmt::format;
 use tracing_subscriber::EnvFilter;
 
 use crate::{args::Args, config::Config};
 
 fn main() -> anyhow::Result<()> {
+    // If this process was started to evaluate a sandboxed model, do that
+    // instead of starting the application.
+    fj_host::run_evaluator_if_requested();
+
     // Respect `RUST_LOG`. If that's not defined or erroneous, log warnings and
     // above.
     //
@@ -53,19 +59,103 @@ fn main() -> anyhow::Result<()> {
     })?;
     path.push(model);
 
-    let model = Model::from_path(path.clone(), config.target_dir)
-        .with_context(|| format!("Failed to load model: {}", path.display()))?;
+    let profile = if args.release {
+        Some(Profile::Release)
+    } else {
+        // Can't fail. Any string is a valid profile.
+        args.profile
+            .or(config.profile)
+            .map(|profile| profile.parse().unwrap())
+    };
+    let build_options = BuildOptions {
+        profile: profile.unwrap_or_default(),
+        features: args.features.or(config.features).unwrap_or_default(),
+        target: args.target.or(config.target),
+    };
+
+    let mut model = Model::from_path(path.clone(), config.target_dir)
+        .with_context(|| format!("Failed to load model: {}", path.display()))?
+        .with_build_options(build_options);
+    if args.sandbox {
+        let mut sandbox = Sandbox::default();
+        if let Some(timeout) = args.timeout {
+            sandbox.timeout = timeout;
+        }
+        if let Some(memory_limit) = args.memory_limit {
+            sandbox.memory_limit =
+                Some(memory_limit.saturating_mul(1024 * 1024));
+        }
+
+        model = model.with_sandbox(sandbox);
+    }
+    #[cfg(feature = "wasm")]
+    if args.wasm {
+        model = model.with_backend(fj_host::Backend::Wasm);
+    }
+
     let parameters = args.parameters.unwrap_or_else(Parameters::empty);
 
     let shape_processor = ShapeProcessor {
         tolerance: args.tolerance,
     };
 
-    if let Some(path) = args.export {
-        let shape = model.load_once(&parameters)?;
-        let shape = shape_processor.process(&shape);
+    if args.mass_properties || args.validate_solid || args.export.is_some() {
+        let loaded = match model.load_once(&parameters) {
+            Ok(loaded) => loaded,
+            Err(fj_host::Error::Compile { diagnostics }) => {
+                // The error's own message only repeats the errors among these,
+                // in short form.
+                print_diagnostics(&diagnostics);
+                return Err(anyhow!("Error compiling model"));
+            }
+            Err(err) => return Err(err.into()),
+        };
+        print_diagnostics(&loaded.diagnostics);
+
+        if args.validate_solid {
+            let report = shape_processor
+                .validate_solid(&loaded.shape, &loaded.settings)?;
+            println!("{report}");
+        }
 
-        export(&shape.mesh, &path)?;
+        if args.mass_properties {
+            let density = args.density.unwrap_or(Scalar::ONE);
+            let properties = shape_processor.mass_properties(
+                &loaded.shape,
+                &loaded.settings,
+                density,
+            )?;
+
+            let [cx, cy, cz] = properties.center_of_mass.coords.components;
+            println!("Volume:         {}", properties.volume);
+            println!("Surface area:   {}", properties.area);
+            println!("Mass:           {}", properties.mass);
+            println!("Center of mass: [{cx}, {cy}, {cz}]");
+            println!("Inertia tensor:");
+            for [a, b, c] in properties.inertia {
+                println!("    [{a}, {b}, {c}]");
+            }
+        }
+
+        if let Some(path) = args.export {
+            let shape =
+                shape_processor.process(&loaded.shape, &loaded.settings)?;
+
+            let issues = shape.mesh.validate();
+            if !issues.is_closed_manifold() {
+                if !args.export_invalid {
+                    return Err(anyhow!(
+                        "{issues}\n\
+                        Refusing to export. Pass `--export-invalid` to export \
+                        anyway."
+                    ));
+                }
+
+                warn!("{issues}");
+            }
+
+            export(&shape.mesh, shape.units, &path)?;
+        }
 
         return Ok(());
     }
@@ -75,3 +165,10 @@ fn main() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// Print the compiler's messages, as Cargo would print them
+fn print_diagnostics(diagnostics: &[Diagnostic]) {
+    for diagnostic in diagnostics {
+        eprint!("{}", diagnostic.render());
+    }
+}
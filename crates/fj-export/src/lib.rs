@@ -14,38 +14,166 @@
 
 #![deny(missing_docs)]
 
-use std::path::Path;
+use std::{
+    fs::File,
+    io::{self, Write as _},
+    path::Path,
+};
 
 use fj_interop::mesh::Mesh;
 use fj_math::Point;
+use zip::{write::FileOptions, ZipWriter};
 
 /// Export the provided mesh to the file at the given path
 ///
 /// Currently only 3MF is supported as an export format. The file extension of
 /// the provided path is ignored.
-pub fn export(mesh: &Mesh<Point<3>>, path: &Path) -> Result<(), Error> {
-    let vertices = mesh.vertices().map(|vertex| vertex.into()).collect();
+///
+/// `units` are the units that the mesh's coordinates are in. They are written
+/// to the `unit` attribute of the 3MF model, so the coordinates are exported
+/// unchanged.
+pub fn export(
+    mesh: &Mesh<Point<3>>,
+    units: fj::Units,
+    path: &Path,
+) -> Result<(), Error> {
+    let file = File::create(path)?;
+    write_3mf(mesh, units, file)
+}
 
-    let indices: Vec<_> = mesh.indices().collect();
-    let triangles = indices
-        .chunks(3)
-        .map(|triangle| {
-            [
-                triangle[0] as usize,
-                triangle[1] as usize,
-                triangle[2] as usize,
-            ]
-        })
-        .collect();
-
-    let mesh = threemf::TriangleMesh {
-        vertices,
-        triangles,
+/// Write a mesh as a 3MF package
+///
+/// 3MF is a ZIP archive that contains the model as XML, plus some metadata
+/// that tells consumers where to find it. The `threemf` crate can't set the unit
+/// of the model, so the package is written here.
+fn write_3mf(
+    mesh: &Mesh<Point<3>>,
+    units: fj::Units,
+    writer: impl io::Write + io::Seek,
+) -> Result<(), Error> {
+    let mut archive = ZipWriter::new(writer);
+    let options = FileOptions::default();
+
+    archive.start_file("[Content_Types].xml", options)?;
+    archive.write_all(CONTENT_TYPES.as_bytes())?;
+
+    archive.start_file("_rels/.rels", options)?;
+    archive.write_all(RELATIONSHIPS.as_bytes())?;
+
+    archive.start_file("3D/model.model", options)?;
+    write_model(mesh, units, &mut archive)?;
+
+    archive.finish()?;
+
+    Ok(())
+}
+
+fn write_model(
+    mesh: &Mesh<Point<3>>,
+    units: fj::Units,
+    writer: &mut impl io::Write,
+) -> io::Result<()> {
+    let unit = match units {
+        fj::Units::Millimeters => "millimeter",
+        fj::Units::Inches => "inch",
+        fj::Units::Meters => "meter",
     };
 
-    threemf::write(path, &mesh)?;
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        writer,
+        r#"<model unit="{unit}" xml:lang="en-US" xmlns="{MODEL_NAMESPACE}">"#
+    )?;
+    writeln!(writer, "<resources>")?;
+    writeln!(writer, r#"<object id="1" type="model">"#)?;
+    writeln!(writer, "<mesh>")?;
+
+    writeln!(writer, "<vertices>")?;
+    for vertex in mesh.vertices() {
+        let [x, y, z] = vertex.coords.components;
+        writeln!(writer, r#"<vertex x="{x}" y="{y}" z="{z}"/>"#)?;
+    }
+    writeln!(writer, "</vertices>")?;
+
+    writeln!(writer, "<triangles>")?;
+    let indices: Vec<_> = mesh.indices().collect();
+    for triangle in indices.chunks(3) {
+        let [v1, v2, v3] = [triangle[0], triangle[1], triangle[2]];
+        writeln!(writer, r#"<triangle v1="{v1}" v2="{v2}" v3="{v3}"/>"#)?;
+    }
+    writeln!(writer, "</triangles>")?;
+
+    writeln!(writer, "</mesh>")?;
+    writeln!(writer, "</object>")?;
+    writeln!(writer, "</resources>")?;
+    writeln!(writer, "<build>")?;
+    writeln!(writer, r#"<item objectid="1"/>"#)?;
+    writeln!(writer, "</build>")?;
+    writeln!(writer, "</model>")?;
 
     Ok(())
 }
 
-pub use threemf::Error;
+const MODEL_NAMESPACE: &str =
+    "http://schemas.microsoft.com/3dmanufacturing/core/2015/02";
+
+const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
+<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
+<Default Extension="model" ContentType="application/vnd.ms-package.3dmanufacturing-3dmodel+xml"/>
+</Types>
+"#;
+
+const RELATIONSHIPS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+<Relationship Target="/3D/model.model" Id="rel0" Type="http://schemas.microsoft.com/3dmanufacturing/2013/01/3dmodel"/>
+</Relationships>
+"#;
+
+/// An error that can occur while exporting a mesh
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// Error writing the file
+    #[error("I/O error while exporting")]
+    Io(#[from] io::Error),
+
+    /// Error writing the ZIP archive that 3MF files are stored in
+    #[error("Error writing 3MF archive")]
+    Zip(#[from] zip::result::ZipError),
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Read as _};
+
+    use fj_interop::mesh::Mesh;
+    use fj_math::Point;
+    use zip::ZipArchive;
+
+    use super::write_3mf;
+
+    #[test]
+    fn unit() -> anyhow::Result<()> {
+        let mut mesh = Mesh::new();
+        mesh.push_triangle(
+            [[0., 0., 0.], [1.5, 0., 0.], [0., 2., 0.]].map(Point::from),
+            [255, 0, 0, 255],
+        );
+
+        let mut file = Cursor::new(Vec::new());
+        write_3mf(&mesh, fj::Units::Inches, &mut file)?;
+
+        let mut archive = ZipArchive::new(file)?;
+        let mut model = String::new();
+        archive
+            .by_name("3D/model.model")?
+            .read_to_string(&mut model)?;
+
+        // The coordinates are exported as they are, in the unit of the model.
+        assert!(model.contains(r#"unit="inch""#));
+        assert!(model.contains(r#"<vertex x="1.5" y="0" z="0"/>"#));
+        assert!(model.contains(r#"<triangle v1="0" v2="1" v3="2"/>"#));
+
+        Ok(())
+    }
+}
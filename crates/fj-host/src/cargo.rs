@@ -0,0 +1,522 @@
+use std::{
+    convert::Infallible,
+    fmt,
+    io::{self, BufReader},
+    path::PathBuf,
+    process::{Child, Command, Stdio},
+    str::FromStr,
+    sync::mpsc,
+    thread,
+};
+
+use cargo_metadata::{diagnostic, Message};
+
+use crate::Error;
+
+/// A running `cargo build` command that collects the compiler's diagnostics
+///
+/// The diagnostics are not printed. If the build succeeds, they are returned;
+/// if it fails, they are returned as part of the error. It's up to the caller
+/// to present them, for example using [`Diagnostic::render`].
+pub(crate) struct Build {
+    child: Child,
+    diagnostics: mpsc::Receiver<io::Result<Vec<Diagnostic>>>,
+}
+
+impl Build {
+    /// Start the build
+    pub(crate) fn start(mut command: Command) -> Result<Self, Error> {
+        command.arg("--message-format=json").stdout(Stdio::piped());
+        own_process_group(&mut command);
+
+        let mut child = command.spawn()?;
+
+        // Can't panic. We just configured stdout to be piped.
+        let stdout = child.stdout.take().unwrap();
+
+        // Cargo's output is read on a separate thread, so the build can be
+        // cancelled while we're waiting for it.
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let mut diagnostics = Vec::new();
+            for message in Message::parse_stream(BufReader::new(stdout)) {
+                let message = match message {
+                    Ok(message) => message,
+                    Err(err) => {
+                        let _ = tx.send(Err(err));
+                        return;
+                    }
+                };
+
+                if let Message::CompilerMessage(message) = message {
+                    diagnostics.push(Diagnostic::from_cargo(message.message));
+                }
+            }
+
+            // The receiver is gone, if the build was cancelled. Nothing left
+            // to do in that case.
+            let _ = tx.send(Ok(diagnostics));
+        });
+
+        Ok(Self {
+            child,
+            diagnostics: rx,
+        })
+    }
+
+    /// Wait for the build to finish
+    pub(crate) fn wait(mut self) -> Result<Vec<Diagnostic>, Error> {
+        // Can only fail, if the thread reading the output panicked.
+        let diagnostics = self
+            .diagnostics
+            .recv()
+            .unwrap_or_else(|_| Err(output_lost()));
+
+        self.finish(diagnostics)
+    }
+
+    /// Check whether the build has finished, without waiting for it
+    ///
+    /// Returns `None`, if the build is still running.
+    pub(crate) fn try_wait(
+        &mut self,
+    ) -> Option<Result<Vec<Diagnostic>, Error>> {
+        match self.diagnostics.try_recv() {
+            Ok(diagnostics) => Some(self.finish(diagnostics)),
+            Err(mpsc::TryRecvError::Empty) => None,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                Some(self.finish(Err(output_lost())))
+            }
+        }
+    }
+
+    /// Stop the build
+    ///
+    /// Cargo runs the compiler in separate processes, which would keep running
+    /// if only Cargo was killed. On Unix-like systems, Cargo is started in its
+    /// own process group, and the whole group is killed. On other systems,
+    /// only Cargo is.
+    pub(crate) fn cancel(mut self) {
+        self.kill();
+    }
+
+    fn kill(&mut self) {
+        // The build might have finished just now, in which case there's
+        // nothing to kill.
+        kill_process_group(&self.child);
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+
+    fn finish(
+        &mut self,
+        diagnostics: io::Result<Vec<Diagnostic>>,
+    ) -> Result<Vec<Diagnostic>, Error> {
+        let diagnostics = match diagnostics {
+            Ok(diagnostics) => diagnostics,
+            Err(err) => {
+                // Without its output, we can't tell how the build went.
+                self.kill();
+                return Err(err.into());
+            }
+        };
+
+        let status = self.child.wait()?;
+        if !status.success() {
+            return Err(Error::Compile { diagnostics });
+        }
+
+        Ok(diagnostics)
+    }
+}
+
+/// Start the command in its own process group
+///
+/// See [`Build::cancel`]. Cargo doesn't receive signals that are meant for the
+/// application's process group then, like the one that results from pressing
+/// Ctrl+C. If the application exits, Cargo stops once it can't write its output
+/// anymore.
+#[cfg(unix)]
+fn own_process_group(command: &mut Command) {
+    use std::os::unix::process::CommandExt as _;
+
+    // Sound, as `setpgid` is async-signal-safe, and can be called between
+    // `fork` and `exec`.
+    unsafe {
+        command.pre_exec(|| {
+            if libc::setpgid(0, 0) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            Ok(())
+        });
+    }
+}
+
+/// Process groups are not supported on this system
+#[cfg(not(unix))]
+fn own_process_group(_: &mut Command) {}
+
+/// Kill the process group that the child is the leader of
+#[cfg(unix)]
+fn kill_process_group(child: &Child) {
+    // The ID of the group is the process ID of its leader.
+    if let Ok(pid) = libc::pid_t::try_from(child.id()) {
+        // Sound, as sending a signal doesn't touch any memory of ours. The
+        // group might not exist anymore, which is fine.
+        unsafe {
+            libc::kill(-pid, libc::SIGKILL);
+        }
+    }
+}
+
+/// Process groups are not supported on this system
+#[cfg(not(unix))]
+fn kill_process_group(_: &Child) {}
+
+/// The error that results from the thread that reads Cargo's output panicking
+fn output_lost() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Other,
+        "Error reading output of `cargo build`",
+    )
+}
+
+/// Options for building a model
+///
+/// See [`crate::Model::with_build_options`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct BuildOptions {
+    /// The profile that the model is built with
+    pub profile: Profile,
+
+    /// Features of the model's crate to enable
+    pub features: Vec<String>,
+
+    /// The target to build the model for, if not the host
+    ///
+    /// Ignored for WebAssembly models, which are always built for
+    /// `wasm32-unknown-unknown`.
+    pub target: Option<String>,
+}
+
+impl BuildOptions {
+    /// Add the arguments for these options to a `cargo build` command
+    ///
+    /// The target is not added, as it depends on the backend.
+    pub(crate) fn add_args(&self, command: &mut Command) {
+        match &self.profile {
+            Profile::Debug => {}
+            Profile::Release => {
+                command.arg("--release");
+            }
+            Profile::Custom(name) => {
+                command.args(["--profile", name]);
+            }
+        }
+
+        if !self.features.is_empty() {
+            command.args(["--features", &self.features.join(",")]);
+        }
+    }
+}
+
+/// A Cargo profile
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Profile {
+    /// The default profile, as used by `cargo build`
+    Debug,
+
+    /// The profile used by `cargo build --release`
+    Release,
+
+    /// A custom profile, as used by `cargo build --profile <name>`
+    Custom(String),
+}
+
+impl Profile {
+    /// The name of the directory that Cargo builds this profile into
+    pub fn dir_name(&self) -> &str {
+        match self {
+            Self::Debug => "debug",
+            Self::Release => "release",
+
+            // Cargo's built-in profiles don't follow the convention that
+            // custom profiles do.
+            Self::Custom(name) => match name.as_str() {
+                "dev" | "test" => "debug",
+                "bench" => "release",
+                name => name,
+            },
+        }
+    }
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self::Debug
+    }
+}
+
+impl FromStr for Profile {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let profile = match s {
+            "debug" | "dev" => Self::Debug,
+            "release" => Self::Release,
+            name => Self::Custom(name.to_owned()),
+        };
+
+        Ok(profile)
+    }
+}
+
+/// A message from the compiler, like an error or a warning
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Diagnostic {
+    /// The severity of the message
+    pub level: DiagnosticLevel,
+
+    /// The message itself
+    pub message: String,
+
+    /// The location in the code that the message refers to, if any
+    pub location: Option<Location>,
+
+    /// The message, as the compiler would print it
+    ///
+    /// This includes the code that the message refers to, as well as any
+    /// notes and suggestions.
+    pub rendered: Option<String>,
+}
+
+impl Diagnostic {
+    fn from_cargo(diagnostic: diagnostic::Diagnostic) -> Self {
+        let level = match diagnostic.level {
+            diagnostic::DiagnosticLevel::Ice
+            | diagnostic::DiagnosticLevel::Error => DiagnosticLevel::Error,
+            diagnostic::DiagnosticLevel::Warning => DiagnosticLevel::Warning,
+            diagnostic::DiagnosticLevel::Help => DiagnosticLevel::Help,
+            _ => DiagnosticLevel::Note,
+        };
+
+        let location = diagnostic
+            .spans
+            .iter()
+            .find(|span| span.is_primary)
+            .map(|span| Location {
+                file: PathBuf::from(&span.file_name),
+                line: span.line_start,
+                column: span.column_start,
+            });
+
+        Self {
+            level,
+            message: diagnostic.message,
+            location,
+            rendered: diagnostic.rendered,
+        }
+    }
+
+    /// Render the message, as the compiler would print it
+    ///
+    /// Falls back to the short form that the `Display` implementation
+    /// produces, if the compiler didn't provide a rendered message. The result
+    /// always ends with a newline.
+    pub fn render(&self) -> String {
+        match &self.rendered {
+            Some(rendered) => rendered.clone(),
+            None => format!("{self}\n"),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(location) = &self.location {
+            write!(f, "{}: ", location)?;
+        }
+
+        write!(f, "{}: {}", self.level, self.message)
+    }
+}
+
+/// The severity of a [`Diagnostic`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DiagnosticLevel {
+    /// An error that prevents the model from being compiled
+    Error,
+
+    /// A warning about something that might be a problem
+    Warning,
+
+    /// Additional information
+    Note,
+
+    /// A suggestion for how to fix a problem
+    Help,
+}
+
+impl fmt::Display for DiagnosticLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Error => write!(f, "error"),
+            Self::Warning => write!(f, "warning"),
+            Self::Note => write!(f, "note"),
+            Self::Help => write!(f, "help"),
+        }
+    }
+}
+
+/// A location in the code of a model
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Location {
+    /// The file, as reported by the compiler
+    ///
+    /// This is usually relative to the root of the workspace that the model is
+    /// part of.
+    pub file: PathBuf,
+
+    /// The line, starting at 1
+    pub line: usize,
+
+    /// The column, starting at 1
+    pub column: usize,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file.display(), self.line, self.column)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use cargo_metadata::diagnostic;
+
+    use super::{Diagnostic, DiagnosticLevel, Location};
+
+    #[test]
+    fn from_cargo_maps_level() {
+        let levels = [
+            ("error: internal compiler error", DiagnosticLevel::Error),
+            ("error", DiagnosticLevel::Error),
+            ("warning", DiagnosticLevel::Warning),
+            ("note", DiagnosticLevel::Note),
+            ("help", DiagnosticLevel::Help),
+            ("failure-note", DiagnosticLevel::Note),
+        ];
+
+        for (level, expected) in levels {
+            let diagnostic =
+                Diagnostic::from_cargo(cargo_diagnostic(level, &[]));
+            assert_eq!(diagnostic.level, expected, "level `{level}`");
+        }
+    }
+
+    #[test]
+    fn from_cargo_uses_primary_span() {
+        let diagnostic = Diagnostic::from_cargo(cargo_diagnostic(
+            "error",
+            &[("src/other.rs", 1, 2, false), ("src/lib.rs", 3, 4, true)],
+        ));
+
+        assert_eq!(
+            diagnostic.location,
+            Some(Location {
+                file: PathBuf::from("src/lib.rs"),
+                line: 3,
+                column: 4,
+            })
+        );
+        assert_eq!(diagnostic.to_string(), "src/lib.rs:3:4: error: message");
+    }
+
+    #[test]
+    fn from_cargo_without_primary_span() {
+        let diagnostic = Diagnostic::from_cargo(cargo_diagnostic(
+            "warning",
+            &[("src/lib.rs", 1, 2, false)],
+        ));
+
+        assert_eq!(diagnostic.location, None);
+        assert_eq!(diagnostic.render(), "warning: message\n");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn cancel_kills_child_processes() {
+        use std::{env, fs, process::Command, thread, time::Duration};
+
+        use super::Build;
+
+        let pid_file = env::temp_dir()
+            .join(format!("fj-host-cancel-test-{}", std::process::id()));
+
+        // `sh` stands in for Cargo, `sleep` for the compiler. The argument
+        // that `Build` adds ends up as `$0`, which is ignored.
+        let mut command = Command::new("sh");
+        command
+            .args(["-c", "sleep 30 & echo $! > \"$PID_FILE\"; wait"])
+            .env("PID_FILE", &pid_file);
+        let build = Build::start(command).unwrap();
+
+        let pid = loop {
+            match fs::read_to_string(&pid_file) {
+                Ok(pid) if pid.ends_with('\n') => break pid.trim().to_owned(),
+                _ => thread::sleep(Duration::from_millis(10)),
+            }
+        };
+        let _ = fs::remove_file(&pid_file);
+
+        build.cancel();
+
+        // The process might linger as a zombie for a moment, until it's
+        // reaped.
+        let stat = format!("/proc/{pid}/stat");
+        let is_dead = || match fs::read_to_string(&stat) {
+            Ok(stat) => stat.contains(") Z "),
+            Err(_) => true,
+        };
+        for _ in 0..100 {
+            if is_dead() {
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert!(is_dead());
+    }
+
+    fn cargo_diagnostic(
+        level: &str,
+        spans: &[(&str, usize, usize, bool)],
+    ) -> diagnostic::Diagnostic {
+        let spans = spans
+            .iter()
+            .map(|&(file_name, line, column, is_primary)| {
+                serde_json::json!({
+                    "file_name": file_name,
+                    "byte_start": 0,
+                    "byte_end": 0,
+                    "line_start": line,
+                    "line_end": line,
+                    "column_start": column,
+                    "column_end": column,
+                    "is_primary": is_primary,
+                    "text": [],
+                })
+            })
+            .collect::<Vec<_>>();
+
+        serde_json::from_value(serde_json::json!({
+            "message": "message",
+            "level": level,
+            "spans": spans,
+            "children": [],
+        }))
+        .unwrap()
+    }
+}
@@ -15,28 +15,44 @@
 
 #![deny(missing_docs)]
 
+mod cargo;
 mod platform;
+mod sandbox;
+mod watch;
+
+#[cfg(feature = "wasm")]
+mod wasm;
 
 use std::{
-    collections::{HashMap, HashSet},
-    ffi::OsStr,
+    collections::HashMap,
     io,
-    path::PathBuf,
+    path::{Path, PathBuf},
     process::Command,
-    sync::mpsc,
+    sync::{mpsc, Arc, Mutex},
     thread,
 };
 
-use notify::Watcher as _;
 use thiserror::Error;
 
-use self::platform::HostPlatform;
+use self::{cargo::Build, platform::HostPlatform};
+
+pub use self::{
+    cargo::{BuildOptions, Diagnostic, DiagnosticLevel, Location, Profile},
+    sandbox::{run_evaluator_if_requested, Sandbox, SandboxError},
+};
+
+#[cfg(feature = "wasm")]
+pub use self::wasm::WasmError;
 
 /// Represents a Fornjot model
 pub struct Model {
+    name: String,
     src_path: PathBuf,
-    lib_path: PathBuf,
+    target_dir: PathBuf,
     manifest_path: PathBuf,
+    build_options: BuildOptions,
+    backend: Backend,
+    sandbox: Option<Sandbox>,
 }
 
 impl Model {
@@ -60,200 +76,382 @@ impl Model {
 
         let src_path = path.join("src");
 
-        let lib_path = {
-            let file = HostPlatform::lib_file_name(&name);
-            let target_dir = target_dir.unwrap_or_else(|| path.join("target"));
-            target_dir.join("debug").join(file)
-        };
-
+        let target_dir = target_dir.unwrap_or_else(|| path.join("target"));
         let manifest_path = path.join("Cargo.toml");
 
         Ok(Self {
+            name,
             src_path,
-            lib_path,
+            target_dir,
             manifest_path,
+            build_options: BuildOptions::default(),
+            backend: Backend::default(),
+            sandbox: None,
         })
     }
 
+    /// Set the options that the model is built with
+    ///
+    /// By default, the model is built with the debug profile, for the host.
+    pub fn with_build_options(mut self, build_options: BuildOptions) -> Self {
+        self.build_options = build_options;
+        self
+    }
+
+    /// Set how the model is compiled and run
+    pub fn with_backend(mut self, backend: Backend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Evaluate the model in a separate process
+    ///
+    /// By default, the model is loaded into the current process. With a
+    /// sandbox, a crash in the model, or a model that doesn't finish in time,
+    /// results in an error, instead of affecting the current process.
+    ///
+    /// Requires a call to [`run_evaluator_if_requested`] at the start of the
+    /// application's `main` function. WebAssembly models are always isolated
+    /// from the current process, so only the timeout applies to them.
+    pub fn with_sandbox(mut self, sandbox: Sandbox) -> Self {
+        self.sandbox = Some(sandbox);
+        self
+    }
+
     /// Load the model once
     ///
     /// The passed arguments are provided to the model. Returns the shape that
-    /// the model returns.
+    /// the model returns, along with the model's settings.
+    ///
+    /// If the model declares its parameters, the arguments are validated
+    /// against those declarations first. See [`fj::ParameterSchema`].
     ///
     /// Please refer to [`Model::load_and_watch`], if you want to watch the
     /// model for changes, reloading it continually.
     pub fn load_once(
         &self,
         arguments: &Parameters,
-    ) -> Result<fj::Shape, Error> {
+    ) -> Result<LoadedModel, Error> {
+        let diagnostics = self.start_build()?.wait()?;
+
+        let mut loaded = self.evaluate(arguments)?;
+        loaded.diagnostics = diagnostics;
+
+        Ok(loaded)
+    }
+
+    /// Start building the model
+    fn start_build(&self) -> Result<Build, Error> {
         let manifest_path = self.manifest_path.display().to_string();
 
-        let status = Command::new("cargo")
+        let mut command = Command::new("cargo");
+        command
             .arg("build")
-            .args(["--manifest-path", &manifest_path])
-            .status()?;
+            .args(["--manifest-path", &manifest_path]);
+        self.build_options.add_args(&mut command);
+        if let Some(target) = self.target() {
+            command.args(["--target", target]);
+        }
 
-        if !status.success() {
-            return Err(Error::Compile);
+        Build::start(command)
+    }
+
+    /// Load the model after it has been built, and call it
+    fn evaluate(&self, arguments: &Parameters) -> Result<LoadedModel, Error> {
+        let lib_path = self.lib_path();
+
+        match self.backend {
+            Backend::Native => match &self.sandbox {
+                Some(sandbox) => sandbox.evaluate(&lib_path, arguments),
+                None => load_library(&lib_path, arguments),
+            },
+            #[cfg(feature = "wasm")]
+            Backend::Wasm => wasm::load(
+                &lib_path,
+                arguments,
+                self.sandbox.map(|sandbox| sandbox.timeout),
+                self.sandbox
+                    .and_then(|sandbox| sandbox.memory_limit)
+                    .unwrap_or(wasm::DEFAULT_MEMORY_LIMIT),
+            ),
         }
+    }
 
-        // So, strictly speaking this is all unsound:
-        // - `Library::new` requires us to abide by the arbitrary requirements
-        //   of any library initialization or termination routines.
-        // - `Library::get` requires us to specify the correct type for the
-        //   model function.
-        // - The model function itself is `unsafe`, because it is a function
-        //   from across an FFI interface.
-        //
-        // Typical models won't have initialization or termination routines (I
-        // think), should abide by the `ModelFn` signature, and might not do
-        // anything unsafe. But we have no way to know that the library the user
-        // told us to load actually does (I think).
-        //
-        // I don't know of a way to fix this. We should take this as motivation
-        // to switch to a better technique:
-        // https://github.com/hannobraun/Fornjot/issues/71
-        let shape = unsafe {
-            let lib = libloading::Library::new(&self.lib_path)?;
-            let model: libloading::Symbol<ModelFn> = lib.get(b"model")?;
-            model(arguments)
-        };
+    /// The target that the model is built for, if not the host
+    fn target(&self) -> Option<&str> {
+        self.backend
+            .target()
+            .or(self.build_options.target.as_deref())
+    }
 
-        Ok(shape)
+    /// The path of the file that the model is compiled to
+    fn lib_path(&self) -> PathBuf {
+        let mut lib_path = self.target_dir.clone();
+        if let Some(target) = self.target() {
+            lib_path.push(target);
+        }
+        lib_path.push(self.build_options.profile.dir_name());
+
+        match self.backend {
+            Backend::Native => {
+                lib_path.push(HostPlatform::lib_file_name(&self.name))
+            }
+            #[cfg(feature = "wasm")]
+            Backend::Wasm => lib_path.push(format!("{}.wasm", self.name)),
+        }
+
+        lib_path
     }
 
     /// Load the model, then watch it for changes
     ///
-    /// Whenever a change is detected, the model is being reloaded.
+    /// Whenever a change is detected, the model is being reloaded. Besides the
+    /// model's own code, this includes its manifest and any local crates it
+    /// depends on. Changes that happen in quick succession result in a single
+    /// reload. If a manifest changes, the local crates that the model depends
+    /// on are determined again.
+    ///
+    /// The model is built and evaluated on a separate thread. If another
+    /// change is detected while the model is being built, that build is
+    /// cancelled, and a new one is started.
     ///
     /// Consumes this instance of `Model` and returns a [`Watcher`], which can
-    /// be queried for changes to the model.
+    /// be queried for the progress of reloading the model.
     pub fn load_and_watch(
         self,
         parameters: Parameters,
     ) -> Result<Watcher, Error> {
-        let (tx, rx) = mpsc::sync_channel(0);
-        let tx2 = tx.clone();
-
-        let watch_path = self.src_path.clone();
-
-        let mut watcher = notify::recommended_watcher(
-            move |event: notify::Result<notify::Event>| {
-                // Unfortunately the `notify` documentation doesn't say when
-                // this might happen, so no idea if it needs to be handled.
-                let event = event.expect("Error handling watch event");
-
-                // Various acceptable ModifyKind kinds. Varies across platforms
-                // (e.g. MacOs vs. Windows10)
-                if let notify::EventKind::Modify(
-                    notify::event::ModifyKind::Any,
-                )
-                | notify::EventKind::Modify(
-                    notify::event::ModifyKind::Data(
-                        notify::event::DataChange::Any,
-                    ),
-                )
-                | notify::EventKind::Modify(
-                    notify::event::ModifyKind::Data(
-                        notify::event::DataChange::Content,
-                    ),
-                ) = event.kind
-                {
-                    let file_ext = event
-                        .paths
-                        .get(0)
-                        .expect("File path missing in watch event")
-                        .extension();
-
-                    let black_list = HashSet::from([
-                        OsStr::new("swp"),
-                        OsStr::new("tmp"),
-                        OsStr::new("swx"),
-                    ]);
-
-                    if let Some(ext) = file_ext {
-                        if black_list.contains(ext) {
-                            return;
-                        }
-                    }
-
-                    // This will panic, if the other end is disconnected, which
-                    // is probably the result of a panic on that thread, or the
-                    // application is being shut down.
-                    //
-                    // Either way, not much we can do about it here, except
-                    // maybe to provide a better error message in the future.
-                    tx.send(()).unwrap();
-                }
-            },
+        let (tx, rx) = mpsc::sync_channel(1);
+        let watcher = watch::PathWatcher::start(
+            &self.manifest_path,
+            &self.src_path,
+            watch::debounce(tx.clone()),
         )?;
-
-        watcher.watch(&watch_path, notify::RecursiveMode::Recursive)?;
+        let watcher = Arc::new(Mutex::new(watcher));
 
         // To prevent a race condition between the initial load and the start of
         // watching, we'll trigger the initial load here, after having started
         // watching.
         //
-        // Will panic, if the receiving end has panicked. Not much we can do
-        // about that, if it happened.
-        thread::spawn(move || tx2.send(()).unwrap());
+        // If a change has already been detected, the initial load is covered.
+        let _ = tx.try_send(());
+
+        let (events_tx, events_rx) = mpsc::channel();
+        let weak = Arc::downgrade(&watcher);
+        thread::spawn(move || {
+            watch::rebuild(self, parameters, rx, events_tx, weak)
+        });
 
         Ok(Watcher {
-            _watcher: Box::new(watcher),
-            channel: rx,
-            model: self,
-            parameters,
+            _watcher: watcher,
+            events: events_rx,
         })
     }
 }
 
+/// Load the model from its dynamic library and call it
+///
+/// This is where the model's code runs, either in the host process, or in a
+/// child process (see [`Sandbox`]).
+fn load_library(
+    lib_path: &Path,
+    arguments: &Parameters,
+) -> Result<LoadedModel, Error> {
+    // So, strictly speaking this is all unsound:
+    // - `Library::new` requires us to abide by the arbitrary requirements
+    //   of any library initialization or termination routines.
+    // - `Library::get` requires us to specify the correct type for the
+    //   model function.
+    // - The model function itself is `unsafe`, because it is a function
+    //   from across an FFI interface.
+    //
+    // Typical models won't have initialization or termination routines (I
+    // think), should abide by the `ModelFn` signature, and might not do
+    // anything unsafe. But we have no way to know that the library the user
+    // told us to load actually does (I think).
+    //
+    // I don't know of a way to fix this. We should take this as motivation
+    // to switch to a better technique:
+    // https://github.com/hannobraun/Fornjot/issues/71
+    let loaded = unsafe {
+        let lib = libloading::Library::new(lib_path)?;
+
+        // Models defined using `fj::model` tell us which version of the
+        // interface they were built for. Models that export `model`
+        // directly can't, and we have to trust them.
+        let generated = lib.get::<GeneratedModelFn>(b"fj_model").ok();
+        if generated.is_some() {
+            let version = lib
+                .get::<AbiVersionFn>(b"fj_abi_version")
+                .ok()
+                .map(|version| version());
+
+            if version != Some(fj::abi::VERSION) {
+                return Err(Error::AbiVersion {
+                    host: fj::abi::VERSION,
+                    model: version,
+                });
+            }
+        }
+
+        // Providing settings is optional for models.
+        let settings = match lib.get::<SettingsFn>(b"settings") {
+            Ok(settings) => settings(),
+            Err(_) => fj::Settings::default(),
+        };
+
+        // So is declaring parameters. Without declarations, there's
+        // nothing to validate against.
+        let arguments = match lib.get::<ParametersFn>(b"parameters") {
+            Ok(parameters) => arguments.validate(&parameters())?,
+            Err(_) => arguments.clone(),
+        };
+
+        // Models defined using `fj::model` export a function that catches
+        // panics. Models that export `model` directly don't, and a panic
+        // in them will unwind into the host.
+        let shape = match generated {
+            Some(model) => match model(&arguments) {
+                fj::abi::ModelResult::Ok(shape) => shape,
+                fj::abi::ModelResult::Panic(message) => {
+                    return Err(Error::Panic(message.to_string()));
+                }
+            },
+            None => {
+                let model: libloading::Symbol<ModelFn> = lib.get(b"model")?;
+                model(&arguments)
+            }
+        };
+
+        LoadedModel {
+            shape,
+            settings,
+            diagnostics: Vec::new(),
+        }
+    };
+
+    Ok(loaded)
+}
+
+/// How a model is compiled and run
+///
+/// See [`Model::with_backend`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Backend {
+    /// Compile the model to a dynamic library, and load that
+    ///
+    /// This is the default. The library is loaded into the current process,
+    /// unless a [`Sandbox`] is used.
+    Native,
+
+    /// Compile the model to WebAssembly, and run it in an embedded runtime
+    ///
+    /// Requires the `wasm32-unknown-unknown` target to be installed. Models
+    /// need to mark their settings function with `fj::settings`, for their
+    /// settings to be used.
+    ///
+    /// The model's memory is limited to the memory limit of the [`Sandbox`],
+    /// if any, or to 1 GiB otherwise. The sandbox's timeout applies too, but
+    /// no separate process is started.
+    #[cfg(feature = "wasm")]
+    Wasm,
+}
+
+impl Backend {
+    /// The target that the model is compiled for, if not the host's
+    fn target(&self) -> Option<&'static str> {
+        match self {
+            Self::Native => None,
+            #[cfg(feature = "wasm")]
+            Self::Wasm => Some("wasm32-unknown-unknown"),
+        }
+    }
+}
+
+impl Default for Backend {
+    fn default() -> Self {
+        Self::Native
+    }
+}
+
 /// Watches a model for changes, reloading it continually
+///
+/// The model is reloaded in the background. Use [`Watcher::receive`] to keep
+/// track of its progress.
 pub struct Watcher {
-    _watcher: Box<dyn notify::Watcher>,
-    channel: mpsc::Receiver<()>,
-    model: Model,
-    parameters: Parameters,
+    _watcher: Arc<Mutex<watch::PathWatcher>>,
+    events: mpsc::Receiver<WatcherEvent>,
 }
 
 impl Watcher {
-    /// Receive an updated shape that the reloaded model created
+    /// Receive the next event about reloading the model
     ///
-    /// Returns `None`, if the model has not changed since the last time this
-    /// method was called.
-    pub fn receive(&self) -> Option<fj::Shape> {
-        match self.channel.try_recv() {
-            Ok(()) => {
-                let shape = match self.model.load_once(&self.parameters) {
-                    Ok(shape) => shape,
-                    Err(Error::Compile) => {
-                        // It would be better to display an error in the UI,
-                        // where the user can actually see it. Issue:
-                        // https://github.com/hannobraun/fornjot/issues/30
-                        println!("Error compiling model");
-                        return None;
-                    }
-                    Err(err) => {
-                        panic!("Error reloading model: {:?}", err);
-                    }
-                };
-
-                Some(shape)
-            }
+    /// Returns `None`, if nothing has happened since the last time this method
+    /// was called. Never blocks, so it can be called from an event loop.
+    pub fn receive(&self) -> Option<WatcherEvent> {
+        match self.events.try_recv() {
+            Ok(event) => Some(event),
             Err(mpsc::TryRecvError::Empty) => {
                 // Nothing to receive from the channel.
                 None
             }
             Err(mpsc::TryRecvError::Disconnected) => {
                 // The other end has disconnected. This is probably the result
-                // of a panic on the other thread, or a program shutdown in
-                // progress. In any case, not much we can do here.
-                panic!();
+                // of a panic on the other thread, whose message has already
+                // been printed, or a program shutdown in progress. In any case,
+                // no more events are coming.
+                None
             }
         }
     }
 }
 
+/// An event about reloading a model
+///
+/// See [`Watcher::receive`].
+#[derive(Debug)]
+pub enum WatcherEvent {
+    /// A change was detected, and the model is being built
+    Building,
+
+    /// The model was built, and is being evaluated
+    Evaluating,
+
+    /// The model was reloaded
+    Ready(LoadedModel),
+
+    /// Reloading the model failed
+    ///
+    /// If the model failed to compile, this is an [`Error::Compile`], which
+    /// carries the compiler's diagnostics. The caller can keep using the last
+    /// shape it received.
+    Failed(Error),
+}
+
+/// The result of loading a model
+#[derive(Debug)]
+pub struct LoadedModel {
+    /// The shape that the model returned
+    pub shape: fj::Shape,
+
+    /// The settings that the model provided
+    ///
+    /// These are the default settings, if the model didn't provide any.
+    pub settings: fj::Settings,
+
+    /// The messages that the compiler emitted while building the model
+    ///
+    /// As the build succeeded, these are warnings and other messages that
+    /// don't prevent the model from being loaded.
+    pub diagnostics: Vec<Diagnostic>,
+}
+
 /// Parameters that are passed to a model
+///
+/// Models receive these as a `&HashMap<String, String>`, which is why this
+/// type needs to have the same layout.
+#[derive(Clone, Debug)]
+#[repr(transparent)]
 pub struct Parameters(pub HashMap<String, String>);
 
 impl Parameters {
@@ -261,14 +459,195 @@ impl Parameters {
     pub fn empty() -> Self {
         Self(HashMap::new())
     }
+
+    /// Validate the parameters against the model's declarations
+    ///
+    /// Returns the validated parameters, with the defaults filled in for any
+    /// declared parameters that are missing.
+    pub fn validate(
+        &self,
+        schema: &fj::ParameterSchema,
+    ) -> Result<Self, ParameterError> {
+        let declarations = schema.parameters();
+
+        for name in self.0.keys() {
+            if !declarations
+                .iter()
+                .any(|parameter| &parameter.name() == name)
+            {
+                return Err(ParameterError::Unknown {
+                    name: name.clone(),
+                    known: declarations
+                        .iter()
+                        .map(|parameter| parameter.name())
+                        .collect(),
+                });
+            }
+        }
+
+        let mut validated = HashMap::new();
+
+        for parameter in declarations {
+            let name = parameter.name();
+
+            // An integer parameter with a fractional default is a mistake in
+            // the model. Rounding the default would hide that.
+            if let Some(default) = parameter.default() {
+                if parameter.kind() == fj::ParameterKind::Integer
+                    && default.fract() != 0.
+                {
+                    return Err(ParameterError::InvalidDefault {
+                        name,
+                        default,
+                    });
+                }
+            }
+
+            let value = match self.0.get(&name) {
+                Some(value) => value.trim().to_owned(),
+                None => match parameter.default() {
+                    Some(default) => default.to_string(),
+                    None => return Err(ParameterError::Missing { name }),
+                },
+            };
+
+            let number = match parameter.kind() {
+                fj::ParameterKind::Integer => {
+                    value.parse::<i64>().ok().map(|n| n as f64)
+                }
+                fj::ParameterKind::Float => value.parse::<f64>().ok(),
+            };
+            let number = match number {
+                Some(number) if number.is_finite() => number,
+                _ => {
+                    return Err(ParameterError::Invalid {
+                        name,
+                        value,
+                        kind: parameter.kind(),
+                    })
+                }
+            };
+
+            let too_small =
+                matches!(parameter.min(), Some(min) if number < min);
+            let too_large =
+                matches!(parameter.max(), Some(max) if number > max);
+            if too_small || too_large {
+                return Err(ParameterError::OutOfRange {
+                    name,
+                    value: number,
+                    min: parameter.min(),
+                    max: parameter.max(),
+                });
+            }
+
+            validated.insert(name, value);
+        }
+
+        Ok(Self(validated))
+    }
+}
+
+/// An error that can occur when validating model parameters
+///
+/// See [`Parameters::validate`].
+#[derive(Debug, Error, serde::Deserialize, serde::Serialize)]
+pub enum ParameterError {
+    /// A parameter was passed that the model doesn't declare
+    #[error(
+        "Unknown parameter `{name}` (the model accepts: {})",
+        .known.join(", ")
+    )]
+    Unknown {
+        /// The name of the unknown parameter
+        name: String,
+
+        /// The names of the parameters that the model declares
+        known: Vec<String>,
+    },
+
+    /// A parameter without a default value wasn't passed
+    #[error("Missing value for parameter `{name}`")]
+    Missing {
+        /// The name of the missing parameter
+        name: String,
+    },
+
+    /// A parameter's value could not be parsed
+    #[error("Invalid value `{value}` for parameter `{name}`: expected {kind}")]
+    Invalid {
+        /// The name of the parameter
+        name: String,
+
+        /// The value that could not be parsed
+        value: String,
+
+        /// The kind of value that the parameter expects
+        kind: fj::ParameterKind,
+    },
+
+    /// An integer parameter was declared with a fractional default value
+    #[error(
+        "Model declares default {default} for integer parameter `{name}`; \
+        expected an integer"
+    )]
+    InvalidDefault {
+        /// The name of the parameter
+        name: String,
+
+        /// The default value that was declared
+        default: f64,
+    },
+
+    /// A parameter's value is outside of the declared range
+    #[error(
+        "Value {value} for parameter `{name}` is out of range ({})",
+        format_range(*.min, *.max)
+    )]
+    OutOfRange {
+        /// The name of the parameter
+        name: String,
+
+        /// The value that was passed
+        value: f64,
+
+        /// The smallest value that the parameter accepts, if limited
+        min: Option<f64>,
+
+        /// The largest value that the parameter accepts, if limited
+        max: Option<f64>,
+    },
+}
+
+fn format_range(min: Option<f64>, max: Option<f64>) -> String {
+    match (min, max) {
+        (Some(min), Some(max)) => format!("expected {min} to {max}"),
+        (Some(min), None) => format!("expected at least {min}"),
+        (None, Some(max)) => format!("expected at most {max}"),
+        (None, None) => String::new(),
+    }
+}
+
+fn format_errors(diagnostics: &[Diagnostic]) -> String {
+    diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.level == DiagnosticLevel::Error)
+        .map(|diagnostic| format!("\n{diagnostic}"))
+        .collect()
 }
 
 /// An error that can occur when loading or reloading a model
 #[derive(Debug, Error)]
 pub enum Error {
     /// Model failed to compile
-    #[error("Error compiling model")]
-    Compile,
+    #[error("Error compiling model{}", format_errors(.diagnostics))]
+    Compile {
+        /// The messages that the compiler emitted
+        ///
+        /// These include warnings and other messages, not just the errors
+        /// that caused the compilation to fail.
+        diagnostics: Vec<Diagnostic>,
+    },
 
     /// I/O error while loading the model
     #[error("I/O error while loading model")]
@@ -278,9 +657,162 @@ pub enum Error {
     #[error("Error loading model from dynamic library")]
     LibLoading(#[from] libloading::Error),
 
+    /// The parameters passed to the model are not valid
+    #[error(transparent)]
+    Parameter(#[from] ParameterError),
+
+    /// The model was built for a different version of the model interface
+    #[error(
+        "Model was built for interface version {}, but host expects version \
+        {host}; rebuild the model against a matching version of `fj`",
+        .model.map_or_else(|| "unknown".to_owned(), |v| v.to_string())
+    )]
+    AbiVersion {
+        /// The version that the host expects
+        host: u32,
+
+        /// The version that the model was built for, if it reported one
+        model: Option<u32>,
+    },
+
+    /// The model panicked
+    ///
+    /// Carries the panic message.
+    #[error("Model panicked: {0}")]
+    Panic(String),
+
+    /// Evaluating the model in a separate process failed
+    #[error(transparent)]
+    Sandbox(#[from] SandboxError),
+
+    /// Running a WebAssembly model failed
+    #[cfg(feature = "wasm")]
+    #[error(transparent)]
+    Wasm(#[from] WasmError),
+
     /// Error while watching the model code for changes
     #[error("Error watching model for changes")]
     Notify(#[from] notify::Error),
 }
 
 type ModelFn = unsafe extern "C" fn(args: &Parameters) -> fj::Shape;
+type GeneratedModelFn =
+    unsafe extern "C" fn(args: &Parameters) -> fj::abi::ModelResult;
+type AbiVersionFn = unsafe extern "C" fn() -> u32;
+type SettingsFn = unsafe extern "C" fn() -> fj::Settings;
+type ParametersFn = unsafe extern "C" fn() -> fj::ParameterSchema;
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{ParameterError, Parameters};
+
+    #[test]
+    fn validate_fills_in_defaults() {
+        let schema = fj::ParameterSchema::new()
+            .with(fj::Parameter::integer("count").with_default(3.))
+            .with(fj::Parameter::float("radius").with_default(1.5));
+
+        let validated = parameters(&[]).validate(&schema).unwrap();
+        assert_eq!(validated.0["count"], "3");
+        assert_eq!(validated.0["radius"], "1.5");
+
+        let validated =
+            parameters(&[("count", " 5 ")]).validate(&schema).unwrap();
+        assert_eq!(validated.0["count"], "5");
+    }
+
+    #[test]
+    fn validate_rejects_unknown_and_missing() {
+        let schema =
+            fj::ParameterSchema::new().with(fj::Parameter::float("radius"));
+
+        let result =
+            parameters(&[("radius", "1"), ("height", "2")]).validate(&schema);
+        assert!(matches!(
+            result,
+            Err(ParameterError::Unknown { name, known })
+                if name == "height" && known == ["radius"]
+        ));
+
+        let result = parameters(&[]).validate(&schema);
+        assert!(matches!(
+            result,
+            Err(ParameterError::Missing { name }) if name == "radius"
+        ));
+    }
+
+    #[test]
+    fn validate_rejects_invalid_values() {
+        let schema = fj::ParameterSchema::new()
+            .with(fj::Parameter::integer("count"))
+            .with(fj::Parameter::float("radius").with_default(1.));
+
+        for value in ["1.5", "many", ""] {
+            let result = parameters(&[("count", value)]).validate(&schema);
+            assert!(
+                matches!(result, Err(ParameterError::Invalid { .. })),
+                "{value:?} is not an integer"
+            );
+        }
+
+        for value in ["inf", "NaN"] {
+            let result = parameters(&[("count", "1"), ("radius", value)])
+                .validate(&schema);
+            assert!(matches!(result, Err(ParameterError::Invalid { .. })));
+        }
+    }
+
+    #[test]
+    fn validate_checks_range() {
+        let schema = fj::ParameterSchema::new().with(
+            fj::Parameter::float("radius")
+                .with_default(5.)
+                .with_min(1.)
+                .with_max(10.),
+        );
+
+        for value in ["1", "10", "5.5"] {
+            let result = parameters(&[("radius", value)]).validate(&schema);
+            assert!(result.is_ok(), "{value} is in range");
+        }
+
+        for value in ["0.5", "10.5"] {
+            let result = parameters(&[("radius", value)]).validate(&schema);
+            assert!(matches!(
+                result,
+                Err(ParameterError::OutOfRange {
+                    min: Some(_),
+                    max: Some(_),
+                    ..
+                })
+            ));
+        }
+    }
+
+    #[test]
+    fn validate_rejects_fractional_integer_default() {
+        let schema = fj::ParameterSchema::new()
+            .with(fj::Parameter::integer("count").with_default(5.5));
+
+        // The default is wrong, even if it isn't used.
+        for parameters in [parameters(&[]), parameters(&[("count", "5")])] {
+            let result = parameters.validate(&schema);
+            assert!(matches!(
+                result,
+                Err(ParameterError::InvalidDefault { name, default })
+                    if name == "count" && default == 5.5
+            ));
+        }
+    }
+
+    fn parameters(values: &[(&str, &str)]) -> Parameters {
+        Parameters(
+            values
+                .iter()
+                .map(|&(name, value)| (name.to_owned(), value.to_owned()))
+                .collect::<HashMap<_, _>>(),
+        )
+    }
+}
@@ -0,0 +1,450 @@
+//! Evaluation of models in a separate process
+//!
+//! Loading a model into the host process means that anything that goes wrong
+//! in the model goes wrong in the host too. A crash takes down the whole
+//! application, and an infinite loop or runaway memory use freezes it.
+//!
+//! As an alternative, the host can start a child process that loads the model,
+//! calls it, and sends the resulting shape back over a pipe. If the child
+//! crashes or doesn't finish in time, it is killed, and the host reports an
+//! error. On Unix-like systems, the memory that the child can use can be
+//! limited too.
+//!
+//! The child process is a copy of the current executable. Applications that
+//! use sandboxed evaluation need to call [`run_evaluator_if_requested`] at the
+//! start of their `main` function, to make that work.
+
+use std::{
+    env,
+    io::{self, Read as _, Write as _},
+    path::Path,
+    process::{self, Command, ExitStatus, Stdio},
+    ptr,
+    sync::mpsc,
+    thread,
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{LoadedModel, ParameterError, Parameters};
+
+/// The environment variable that tells the child process which model to load
+const LIB_PATH_VAR: &str = "FJ_HOST_EVALUATE_MODEL";
+
+/// The environment variable that tells the child process its memory limit
+const MEMORY_LIMIT_VAR: &str = "FJ_HOST_MEMORY_LIMIT";
+
+/// Configuration for evaluating models in a separate process
+///
+/// See [`crate::Model::with_sandbox`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Sandbox {
+    /// How long the model may take to evaluate, before it is killed
+    pub timeout: Duration,
+
+    /// How much memory the model may use, in bytes, if limited
+    ///
+    /// On Unix-like systems, this limits the address space of the child
+    /// process, which includes the executable and the libraries it loads, not
+    /// just the memory that the model allocates. A model that exceeds the limit
+    /// fails to allocate memory, which usually crashes the child process.
+    ///
+    /// The limit is not enforced on other systems.
+    ///
+    /// WebAssembly models are not run in a child process. For them, this
+    /// limits the size of the model's memory, on all systems.
+    pub memory_limit: Option<u64>,
+}
+
+impl Sandbox {
+    /// Evaluate the model from the given library in a child process
+    pub(crate) fn evaluate(
+        &self,
+        lib_path: &Path,
+        arguments: &Parameters,
+    ) -> Result<LoadedModel, crate::Error> {
+        let mut command = Command::new(env::current_exe()?);
+        command
+            .env(LIB_PATH_VAR, lib_path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped());
+        if let Some(memory_limit) = self.memory_limit {
+            command.env(MEMORY_LIMIT_VAR, memory_limit.to_string());
+        }
+
+        let mut child = command.spawn()?;
+
+        // Can't panic. We just configured both to be piped.
+        let mut stdin = child.stdin.take().unwrap();
+        let mut stdout = child.stdout.take().unwrap();
+
+        bincode::serialize_into(&mut stdin, &arguments.0)
+            .map_err(SandboxError::Encoding)?;
+        drop(stdin);
+
+        // The output is read on a separate thread, so we can stop waiting for
+        // it, if the timeout expires. Reading it concurrently also makes sure
+        // that the child can't block on a full pipe.
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let mut output = Vec::new();
+            let result = stdout.read_to_end(&mut output).map(|_| output);
+
+            // The receiver is gone, if the timeout expired. Nothing left to
+            // do in that case.
+            let _ = tx.send(result);
+        });
+
+        let output = match rx.recv_timeout(self.timeout) {
+            Ok(output) => output?,
+            Err(_) => {
+                // The child might have exited just now, in which case there's
+                // nothing to kill.
+                let _ = child.kill();
+                child.wait()?;
+
+                return Err(SandboxError::Timeout(self.timeout).into());
+            }
+        };
+
+        let status = child.wait()?;
+        if !status.success() {
+            return Err(SandboxError::Crashed(status).into());
+        }
+
+        let (model_output, response) = decode_response(&output)?;
+
+        // The model's output is only of interest to the user. If it can't be
+        // written, there's no reason to fail.
+        let _ = io::stdout().write_all(model_output);
+
+        match response {
+            Response::Loaded { shape, settings } => Ok(LoadedModel {
+                shape,
+                settings,
+                diagnostics: Vec::new(),
+            }),
+            Response::Failed(failure) => Err(failure.into()),
+        }
+    }
+}
+
+impl Default for Sandbox {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(30),
+            memory_limit: None,
+        }
+    }
+}
+
+/// Evaluate a model and exit, if this process was started to do that
+///
+/// Does nothing, if this process is not a child process that was started by
+/// [`Sandbox`]. Applications that evaluate models in a separate process must
+/// call this function at the very start of `main`, before doing anything that
+/// the child process shouldn't do.
+pub fn run_evaluator_if_requested() {
+    let lib_path = match env::var_os(LIB_PATH_VAR) {
+        Some(lib_path) => lib_path,
+        None => return,
+    };
+
+    let response = evaluate(Path::new(&lib_path));
+
+    // The model might have written output through C's stdio, which is
+    // buffered separately from Rust's. Unless it's flushed now, it would only
+    // be written when the process exits, after the response.
+    //
+    // Sound, as flushing all streams doesn't involve any pointers of ours.
+    unsafe {
+        libc::fflush(ptr::null_mut());
+    }
+
+    // If the response can't be sent, there's nobody to tell about it. Exiting
+    // with an error lets the host know that something went wrong.
+    let stdout = io::stdout();
+    let exit_code = match encode_response(&response, &mut stdout.lock()) {
+        Ok(()) => 0,
+        Err(_) => 1,
+    };
+
+    process::exit(exit_code);
+}
+
+/// Load and evaluate the model in the child process
+fn evaluate(lib_path: &Path) -> Response {
+    if let Some(memory_limit) = env::var_os(MEMORY_LIMIT_VAR) {
+        let memory_limit =
+            memory_limit.to_str().and_then(|limit| limit.parse().ok());
+        let result = match memory_limit {
+            Some(memory_limit) => limit_memory(memory_limit),
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid value",
+            )),
+        };
+
+        if let Err(err) = result {
+            return Response::Failed(Failure::Other(format!(
+                "Error limiting memory of model process: {err}"
+            )));
+        }
+    }
+
+    let arguments = match bincode::deserialize_from(io::stdin()) {
+        Ok(arguments) => Parameters(arguments),
+        Err(err) => {
+            return Response::Failed(Failure::Other(format!(
+                "Error reading arguments: {err}"
+            )))
+        }
+    };
+
+    match crate::load_library(lib_path, &arguments) {
+        Ok(LoadedModel {
+            shape, settings, ..
+        }) => Response::Loaded { shape, settings },
+        Err(err) => Response::Failed(err.into()),
+    }
+}
+
+/// Limit the address space of the current process
+#[cfg(unix)]
+fn limit_memory(bytes: u64) -> io::Result<()> {
+    let limit = libc::rlimit {
+        rlim_cur: bytes as libc::rlim_t,
+        rlim_max: bytes as libc::rlim_t,
+    };
+
+    // Sound, as `limit` is a valid `rlimit` that outlives the call.
+    let result = unsafe { libc::setrlimit(libc::RLIMIT_AS, &limit) };
+    if result != 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    Ok(())
+}
+
+/// Limiting memory is not supported on this system
+#[cfg(not(unix))]
+fn limit_memory(_: u64) -> io::Result<()> {
+    Ok(())
+}
+
+/// Write the response
+///
+/// The model might have written its own output to stdout, before the response
+/// is written. That's why the response is followed by its length, so the host
+/// can find it at the end of the output.
+fn encode_response(
+    response: &Response,
+    output: &mut impl io::Write,
+) -> Result<(), bincode::Error> {
+    let response = bincode::serialize(response)?;
+
+    output.write_all(&response)?;
+    output.write_all(&(response.len() as u64).to_le_bytes())?;
+    output.flush()?;
+
+    Ok(())
+}
+
+/// Find the response at the end of the child's output
+///
+/// Returns the output that precedes the response, which came from the model,
+/// together with the response.
+fn decode_response(output: &[u8]) -> Result<(&[u8], Response), SandboxError> {
+    let (rest, length) = output
+        .len()
+        .checked_sub(8)
+        .map(|split| output.split_at(split))
+        .ok_or(SandboxError::MissingResponse)?;
+
+    // Can't panic. `length` has exactly 8 bytes.
+    let length = u64::from_le_bytes(length.try_into().unwrap());
+    let (model_output, response) = usize::try_from(length)
+        .ok()
+        .and_then(|length| rest.len().checked_sub(length))
+        .map(|split| rest.split_at(split))
+        .ok_or(SandboxError::MissingResponse)?;
+
+    let response =
+        bincode::deserialize(response).map_err(SandboxError::Encoding)?;
+
+    Ok((model_output, response))
+}
+
+/// The response that the child process sends to the host
+#[derive(Debug, Deserialize, Serialize)]
+enum Response {
+    Loaded {
+        shape: fj::Shape,
+        settings: fj::Settings,
+    },
+    Failed(Failure),
+}
+
+/// The reason that loading or evaluating the model failed in the child process
+///
+/// Errors that the host handles specifically are sent as they are. All others
+/// are sent as their message.
+#[derive(Debug, Deserialize, Serialize)]
+enum Failure {
+    Parameter(ParameterError),
+    AbiVersion { host: u32, model: Option<u32> },
+    Panic(String),
+    Other(String),
+}
+
+impl From<crate::Error> for Failure {
+    fn from(err: crate::Error) -> Self {
+        match err {
+            crate::Error::Parameter(err) => Self::Parameter(err),
+            crate::Error::AbiVersion { host, model } => {
+                Self::AbiVersion { host, model }
+            }
+            crate::Error::Panic(message) => Self::Panic(message),
+            err => Self::Other(err.to_string()),
+        }
+    }
+}
+
+impl From<Failure> for crate::Error {
+    fn from(failure: Failure) -> Self {
+        match failure {
+            Failure::Parameter(err) => Self::Parameter(err),
+            Failure::AbiVersion { host, model } => {
+                Self::AbiVersion { host, model }
+            }
+            Failure::Panic(message) => Self::Panic(message),
+            Failure::Other(message) => SandboxError::Model(message).into(),
+        }
+    }
+}
+
+/// An error that can occur when evaluating a model in a separate process
+#[derive(Debug, Error)]
+pub enum SandboxError {
+    /// The model did not finish evaluating in time, and was killed
+    #[error("Model did not finish within {0:?} and was killed")]
+    Timeout(Duration),
+
+    /// The process evaluating the model exited unexpectedly
+    ///
+    /// This is probably the result of a crash in the model.
+    #[error("Model process exited unexpectedly ({0})")]
+    Crashed(ExitStatus),
+
+    /// Loading or evaluating the model failed in the child process
+    ///
+    /// Carries the error message reported by the child process. Errors that
+    /// callers are likely to handle specifically, like [`ParameterError`] or a
+    /// panic in the model, are reported as the respective variant of
+    /// [`crate::Error`] instead.
+    #[error("{0}")]
+    Model(String),
+
+    /// The child process exited without responding
+    #[error("Model process exited without sending a shape")]
+    MissingResponse,
+
+    /// Data sent between the host and the child process could not be encoded
+    /// or decoded
+    #[error("Error communicating with model process")]
+    Encoding(#[source] bincode::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Error, ParameterError};
+
+    use super::{decode_response, encode_response, Failure, Response};
+
+    #[test]
+    fn response_after_model_output() {
+        let mut output = b"model output".to_vec();
+        encode_response(&loaded(), &mut output).unwrap();
+
+        let (model_output, response) = decode_response(&output).unwrap();
+        assert_eq!(model_output, b"model output");
+        assert!(matches!(response, Response::Loaded { .. }));
+
+        // Without any output from the model.
+        let mut output = Vec::new();
+        encode_response(&loaded(), &mut output).unwrap();
+
+        let (model_output, _) = decode_response(&output).unwrap();
+        assert!(model_output.is_empty());
+    }
+
+    #[test]
+    fn missing_response() {
+        // Output that is too short for the length of the response
+        let result = decode_response(b"short");
+        assert!(matches!(result, Err(super::SandboxError::MissingResponse)));
+
+        // A length that is longer than the output
+        let mut output = b"model output".to_vec();
+        output.extend(1000u64.to_le_bytes());
+        let result = decode_response(&output);
+        assert!(matches!(result, Err(super::SandboxError::MissingResponse)));
+
+        // A length that doesn't fit into memory
+        let output = u64::MAX.to_le_bytes();
+        let result = decode_response(&output);
+        assert!(matches!(result, Err(super::SandboxError::MissingResponse)));
+    }
+
+    #[test]
+    fn invalid_response() {
+        // The length fits, but what precedes it is not a valid response.
+        let mut output = b"garbage".to_vec();
+        output.extend(7u64.to_le_bytes());
+
+        let result = decode_response(&output);
+        assert!(matches!(result, Err(super::SandboxError::Encoding(_))));
+    }
+
+    #[test]
+    fn typed_failures() {
+        let failures = [
+            Error::Parameter(ParameterError::Missing {
+                name: "radius".to_owned(),
+            }),
+            Error::AbiVersion {
+                host: 2,
+                model: Some(1),
+            },
+            Error::Panic("boom".to_owned()),
+        ];
+
+        for err in failures {
+            let expected = err.to_string();
+
+            let mut output = Vec::new();
+            let response = Response::Failed(err.into());
+            encode_response(&response, &mut output).unwrap();
+
+            let failure = match decode_response(&output).unwrap() {
+                (_, Response::Failed(failure)) => failure,
+                (_, response) => panic!("Unexpected response: {response:?}"),
+            };
+            assert!(!matches!(failure, Failure::Other(_)));
+
+            let err = Error::from(failure);
+            assert!(!matches!(err, Error::Sandbox(_)));
+            assert_eq!(err.to_string(), expected);
+        }
+    }
+
+    fn loaded() -> Response {
+        Response::Loaded {
+            shape: fj::Sketch::from_points(vec![[0., 0.], [1., 0.], [0., 1.]])
+                .into(),
+            settings: fj::Settings::default(),
+        }
+    }
+}
@@ -0,0 +1,382 @@
+//! Running models that are compiled to WebAssembly
+//!
+//! Unlike native models, WebAssembly models are portable, and can't affect the
+//! host, except through the functions it calls. They are run in an embedded
+//! runtime, and exchange data with the host in serialized form. See
+//! `fj::abi::wasm` for the model side of that.
+
+use std::{
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use serde::de::DeserializeOwned;
+use thiserror::Error;
+use wasmtime::{
+    Config, Engine, Instance, Memory, Module, ResourceLimiter, Store, Trap,
+    TypedFunc,
+};
+
+use crate::{LoadedModel, Parameters};
+
+/// The memory limit that applies, if none is given explicitly
+pub(crate) const DEFAULT_MEMORY_LIMIT: u64 = 1024 * 1024 * 1024;
+
+/// Load the model from a WebAssembly module and call it
+///
+/// If a timeout is given, the model is stopped, if it runs for longer than
+/// that. The model's memory can't grow beyond `memory_limit` bytes.
+pub(crate) fn load(
+    lib_path: &Path,
+    arguments: &Parameters,
+    timeout: Option<Duration>,
+    memory_limit: u64,
+) -> Result<LoadedModel, crate::Error> {
+    let mut config = Config::new();
+    config.epoch_interruption(true);
+
+    let engine = Engine::new(&config).map_err(WasmError::Runtime)?;
+    let module =
+        Module::from_file(&engine, lib_path).map_err(WasmError::Runtime)?;
+
+    // The model traps, once the epoch is incremented. That only happens, if
+    // the timeout expires.
+    let mut store = Store::new(&engine, Limits::new(memory_limit));
+    store.set_epoch_deadline(1);
+    store.limiter(|limits| limits);
+    let timeout = timeout.map(|duration| Timeout::start(duration, &engine));
+
+    // Models don't get to import anything. Whatever they need to do, they
+    // need to do without the help of the host.
+    let instance =
+        Instance::new(&mut store, &module, &[]).map_err(WasmError::Runtime)?;
+
+    let version = match instance.get_func(&mut store, "fj_abi_version") {
+        Some(_) => Some(
+            function::<(), u32>(&instance, &mut store, "fj_abi_version")?
+                .call(&mut store, ())
+                .map_err(|trap| {
+                    WasmError::from_trap(trap, &timeout, store.data())
+                })?,
+        ),
+        None => None,
+    };
+    if version != Some(fj::abi::VERSION) {
+        return Err(crate::Error::AbiVersion {
+            host: fj::abi::VERSION,
+            model: version,
+        });
+    }
+
+    let mut model = Instantiated {
+        memory: instance
+            .get_memory(&mut store, "memory")
+            .ok_or(WasmError::MissingExport("memory"))?,
+        alloc: function(&instance, &mut store, "fj_wasm_alloc")?,
+        free: function(&instance, &mut store, "fj_wasm_free")?,
+        store,
+        timeout,
+    };
+
+    // Declaring parameters is optional for models.
+    let arguments =
+        match instance.get_func(&mut model.store, "fj_wasm_parameters") {
+            Some(_) => {
+                let parameters = function::<(), u64>(
+                    &instance,
+                    &mut model.store,
+                    "fj_wasm_parameters",
+                )?;
+                let schema: fj::ParameterSchema = model.call(parameters, ())?;
+
+                arguments.validate(&schema)?
+            }
+            None => arguments.clone(),
+        };
+
+    let call = function(&instance, &mut model.store, "fj_wasm_model")?;
+    let arguments = model.write(&arguments.0)?;
+    let shape = match model.call(call, arguments)? {
+        fj::abi::ModelResult::Ok(shape) => shape,
+        fj::abi::ModelResult::Panic(message) => {
+            return Err(crate::Error::Panic(message.to_string()));
+        }
+    };
+
+    // Providing settings is optional for models.
+    let settings = match instance.get_func(&mut model.store, "fj_wasm_settings")
+    {
+        Some(_) => {
+            let settings = function::<(), u64>(
+                &instance,
+                &mut model.store,
+                "fj_wasm_settings",
+            )?;
+            model.call(settings, ())?
+        }
+        None => fj::Settings::default(),
+    };
+
+    Ok(LoadedModel {
+        shape,
+        settings,
+        diagnostics: Vec::new(),
+    })
+}
+
+/// A model that has been instantiated, and can be called
+struct Instantiated {
+    store: Store<Limits>,
+    memory: Memory,
+    alloc: TypedFunc<u32, u32>,
+    free: TypedFunc<(u32, u32), ()>,
+    timeout: Option<Timeout>,
+}
+
+impl Instantiated {
+    /// Serialize a value into a new buffer in the model's memory
+    ///
+    /// Returns the pointer to and length of the buffer. Ownership of the buffer
+    /// needs to be passed to the model.
+    fn write(
+        &mut self,
+        value: &impl serde::Serialize,
+    ) -> Result<(u32, u32), WasmError> {
+        let bytes = bincode::serialize(value).map_err(WasmError::Encoding)?;
+        let len =
+            u32::try_from(bytes.len()).map_err(|_| WasmError::InvalidMemory)?;
+
+        let ptr = self
+            .alloc
+            .call(&mut self.store, len)
+            .map_err(|trap| self.error(trap))?;
+        self.memory
+            .write(&mut self.store, ptr as usize, &bytes)
+            .map_err(|_| WasmError::InvalidMemory)?;
+
+        Ok((ptr, len))
+    }
+
+    /// Call a function that returns a serialized value, and deserialize it
+    fn call<Params, T>(
+        &mut self,
+        function: TypedFunc<Params, u64>,
+        params: Params,
+    ) -> Result<T, WasmError>
+    where
+        Params: wasmtime::WasmParams,
+        T: DeserializeOwned,
+    {
+        let buffer = function
+            .call(&mut self.store, params)
+            .map_err(|trap| self.error(trap))?;
+
+        // See `fj::abi::wasm` for how buffers are represented.
+        let ptr = (buffer >> 32) as u32;
+        let len = buffer as u32;
+
+        let mut bytes = vec![0; len as usize];
+        self.memory
+            .read(&self.store, ptr as usize, &mut bytes)
+            .map_err(|_| WasmError::InvalidMemory)?;
+        self.free
+            .call(&mut self.store, (ptr, len))
+            .map_err(|trap| self.error(trap))?;
+
+        bincode::deserialize(&bytes).map_err(WasmError::Encoding)
+    }
+
+    fn error(&self, trap: Trap) -> WasmError {
+        WasmError::from_trap(trap, &self.timeout, self.store.data())
+    }
+}
+
+/// Limits the growth of the model's memory
+///
+/// Like [`Timeout`], this remembers why the model was stopped, as the trap
+/// that results from a failed allocation doesn't tell us.
+struct Limits {
+    memory: u64,
+    exceeded: bool,
+}
+
+impl Limits {
+    fn new(memory: u64) -> Self {
+        Self {
+            memory,
+            exceeded: false,
+        }
+    }
+}
+
+impl ResourceLimiter for Limits {
+    fn memory_growing(
+        &mut self,
+        _: usize,
+        desired: usize,
+        _: Option<usize>,
+    ) -> bool {
+        if desired as u64 > self.memory {
+            self.exceeded = true;
+            return false;
+        }
+
+        true
+    }
+
+    fn table_growing(&mut self, _: u32, _: u32, _: Option<u32>) -> bool {
+        true
+    }
+}
+
+/// Stops the model, once a given duration has passed
+///
+/// The timeout is cancelled, when this is dropped.
+struct Timeout {
+    duration: Duration,
+    expired: Arc<AtomicBool>,
+    _cancel: mpsc::Sender<()>,
+}
+
+impl Timeout {
+    fn start(duration: Duration, engine: &Engine) -> Self {
+        let expired = Arc::new(AtomicBool::new(false));
+        let (cancel, cancelled) = mpsc::channel::<()>();
+
+        // The trap that results from this doesn't tell us why it happened.
+        // That's why we need to remember it ourselves.
+        let engine = engine.clone();
+        let expired_2 = expired.clone();
+        thread::spawn(move || {
+            // Nothing is ever sent. We're only waiting for the sender to be
+            // dropped, which means the model has finished.
+            if let Err(mpsc::RecvTimeoutError::Timeout) =
+                cancelled.recv_timeout(duration)
+            {
+                expired_2.store(true, Ordering::SeqCst);
+                engine.increment_epoch();
+            }
+        });
+
+        Self {
+            duration,
+            expired,
+            _cancel: cancel,
+        }
+    }
+}
+
+fn function<Params, Results>(
+    instance: &Instance,
+    store: &mut Store<Limits>,
+    name: &'static str,
+) -> Result<TypedFunc<Params, Results>, WasmError>
+where
+    Params: wasmtime::WasmParams,
+    Results: wasmtime::WasmResults,
+{
+    instance
+        .get_typed_func(store, name)
+        .map_err(|_| WasmError::MissingExport(name))
+}
+
+/// An error that can occur when running a WebAssembly model
+#[derive(Debug, Error)]
+pub enum WasmError {
+    /// The WebAssembly module could not be compiled or instantiated
+    #[error("Error loading WebAssembly model")]
+    Runtime(#[source] anyhow::Error),
+
+    /// The model doesn't export a function that the host expects
+    ///
+    /// This is probably the result of the model not using `fj::model`.
+    #[error(
+        "WebAssembly model doesn't export `{0}`; is it defined using \
+        `fj::model`?"
+    )]
+    MissingExport(&'static str),
+
+    /// The model trapped
+    ///
+    /// This is probably the result of a panic, which aborts WebAssembly
+    /// models.
+    #[error("WebAssembly model crashed")]
+    Trap(#[source] Trap),
+
+    /// The model did not finish in time, and was stopped
+    #[error("Model did not finish within {0:?} and was stopped")]
+    Timeout(Duration),
+
+    /// The model tried to use more memory than it is allowed to
+    #[error("Model exceeded its memory limit of {0} bytes")]
+    OutOfMemory(u64),
+
+    /// The model passed a buffer that is not within its memory
+    #[error("WebAssembly model passed an invalid buffer")]
+    InvalidMemory,
+
+    /// Data exchanged with the model could not be encoded or decoded
+    #[error("Error communicating with WebAssembly model")]
+    Encoding(#[source] bincode::Error),
+}
+
+impl WasmError {
+    fn from_trap(
+        trap: Trap,
+        timeout: &Option<Timeout>,
+        limits: &Limits,
+    ) -> Self {
+        match timeout {
+            Some(timeout) if timeout.expired.load(Ordering::SeqCst) => {
+                Self::Timeout(timeout.duration)
+            }
+            _ if limits.exceeded => Self::OutOfMemory(limits.memory),
+            _ => Self::Trap(trap),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::atomic::Ordering, thread, time::Duration};
+
+    use wasmtime::{Engine, ResourceLimiter};
+
+    use super::{Limits, Timeout};
+
+    #[test]
+    fn timeout_expires() {
+        let timeout = Timeout::start(Duration::ZERO, &Engine::default());
+        thread::sleep(Duration::from_millis(100));
+
+        assert!(timeout.expired.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn timeout_is_cancelled_when_dropped() {
+        let timeout =
+            Timeout::start(Duration::from_millis(50), &Engine::default());
+        let expired = timeout.expired.clone();
+
+        drop(timeout);
+        thread::sleep(Duration::from_millis(150));
+
+        assert!(!expired.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn limits_deny_memory_growth_beyond_limit() {
+        let mut limits = Limits::new(1024);
+
+        assert!(limits.memory_growing(0, 1024, None));
+        assert!(!limits.exceeded);
+
+        assert!(!limits.memory_growing(1024, 1025, None));
+        assert!(limits.exceeded);
+    }
+}
@@ -0,0 +1,542 @@
+//! Support for watching a model for changes
+//!
+//! A model depends on more than the code in its `src/` directory. Its manifest
+//! and any local crates it depends on (like a shared library of parts) affect
+//! it too, so all of those are watched.
+//!
+//! Saving a file usually results in several events in quick succession. These
+//! are combined into a single request to rebuild the model. The model is then
+//! rebuilt on a separate thread, so whoever is waiting for it can keep doing
+//! other things.
+//!
+//! Whenever a manifest changes, the dependencies of the model might have
+//! changed too. The paths that are watched are updated before the next build.
+
+use std::{
+    collections::{BTreeSet, HashSet},
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc, Mutex, PoisonError, Weak,
+    },
+    thread,
+    time::Duration,
+};
+
+use cargo_metadata::{DependencyKind, MetadataCommand, Package};
+use notify::{
+    event::{EventKind, ModifyKind},
+    RecursiveMode,
+};
+
+use crate::{cargo::Build, Diagnostic, Error, Model, Parameters, WatcherEvent};
+
+/// How long to wait for more events, before requesting a rebuild
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// How often to check whether a build has finished
+const POLL: Duration = Duration::from_millis(20);
+
+/// Files that editors create temporarily, and that don't affect the model
+const IGNORED_EXTENSIONS: &[&str] = &["swp", "swx", "tmp"];
+
+/// Watches the paths that affect a model, and requests rebuilds
+///
+/// Keeps track of whether a manifest has changed. If so, the watched paths
+/// need to be updated, using [`PathWatcher::update`].
+pub(crate) struct PathWatcher {
+    watcher: Box<dyn notify::Watcher + Send>,
+    paths: Arc<Mutex<Paths>>,
+    watched: Vec<(PathBuf, RecursiveMode)>,
+    manifest_changed: Arc<AtomicBool>,
+}
+
+impl PathWatcher {
+    /// Start watching the paths that affect the model
+    ///
+    /// Every relevant change is sent to `changes`.
+    pub(crate) fn start(
+        manifest_path: &Path,
+        src_path: &Path,
+        changes: mpsc::Sender<()>,
+    ) -> Result<Self, Error> {
+        let paths = Arc::new(Mutex::new(Paths::default()));
+        let manifest_changed = Arc::new(AtomicBool::new(false));
+
+        let watcher = {
+            let paths = paths.clone();
+            let manifest_changed = manifest_changed.clone();
+
+            notify::recommended_watcher(
+                move |event: notify::Result<notify::Event>| {
+                    // Unfortunately the `notify` documentation doesn't say when
+                    // this might happen, so no idea if it needs to be handled.
+                    let event = event.expect("Error handling watch event");
+
+                    let paths =
+                        paths.lock().unwrap_or_else(PoisonError::into_inner);
+                    if !paths.is_relevant(&event) {
+                        return;
+                    }
+                    if paths.changes_manifest(&event) {
+                        manifest_changed.store(true, Ordering::SeqCst);
+                    }
+
+                    // This fails, if the other end is disconnected, which
+                    // means the watcher is being dropped. Nobody is interested
+                    // in changes anymore.
+                    let _ = changes.send(());
+                },
+            )?
+        };
+
+        let mut path_watcher = Self {
+            watcher: Box::new(watcher),
+            paths,
+            watched: Vec::new(),
+            manifest_changed,
+        };
+        path_watcher.update(manifest_path, src_path)?;
+
+        Ok(path_watcher)
+    }
+
+    /// Update the watched paths, if a manifest has changed since the last time
+    pub(crate) fn update_if_manifest_changed(
+        &mut self,
+        manifest_path: &Path,
+        src_path: &Path,
+    ) -> Result<(), Error> {
+        if self.manifest_changed.swap(false, Ordering::SeqCst) {
+            self.update(manifest_path, src_path)?;
+        }
+
+        Ok(())
+    }
+
+    /// Determine the paths that affect the model, and watch those
+    fn update(
+        &mut self,
+        manifest_path: &Path,
+        src_path: &Path,
+    ) -> Result<(), Error> {
+        let paths = Paths::for_model(manifest_path, src_path);
+        let watched = paths.watched();
+
+        for (path, mode) in &self.watched {
+            if !watched.contains(&(path.clone(), *mode)) {
+                // The path might not exist anymore, in which case it's not
+                // watched anymore either.
+                let _ = self.watcher.unwatch(path);
+            }
+        }
+        for (path, mode) in &watched {
+            if !self.watched.contains(&(path.clone(), *mode)) {
+                self.watcher.watch(path, *mode)?;
+            }
+        }
+
+        self.watched = watched;
+        *self.paths.lock().unwrap_or_else(PoisonError::into_inner) = paths;
+
+        Ok(())
+    }
+}
+
+/// The paths that need to be watched for changes to the model
+///
+/// All paths are canonicalized, to match the paths in watch events.
+#[derive(Debug, Default)]
+pub(crate) struct Paths {
+    /// Directories that are watched, including their subdirectories
+    pub directories: BTreeSet<PathBuf>,
+
+    /// Individual files that are watched
+    pub files: BTreeSet<PathBuf>,
+}
+
+impl Paths {
+    /// Determine the paths that need to be watched for changes to the model
+    ///
+    /// Falls back to watching just the model's manifest and source directory,
+    /// if the dependencies of the model can't be determined. This happens, for
+    /// example, if the manifest is invalid, in which case it's all the more
+    /// important that changes to it are noticed.
+    pub(crate) fn for_model(manifest_path: &Path, src_path: &Path) -> Self {
+        let mut paths = Self::default();
+        paths.add_file(manifest_path);
+        paths.add_directory(src_path);
+
+        let metadata =
+            match MetadataCommand::new().manifest_path(manifest_path).exec() {
+                Ok(metadata) => metadata,
+                Err(_) => return paths,
+            };
+        let resolve = match &metadata.resolve {
+            Some(resolve) => resolve,
+            None => return paths,
+        };
+
+        // Walk the dependency graph, starting at the model, to find all local
+        // packages that go into it. Packages from a registry or a git
+        // repository can't change while we're watching.
+        let mut pending: Vec<_> = resolve.root.iter().collect();
+        let mut visited = HashSet::new();
+
+        while let Some(id) = pending.pop() {
+            if !visited.insert(id) {
+                continue;
+            }
+
+            let package = &metadata[id];
+            if package.source.is_some() {
+                continue;
+            }
+
+            paths.add_package(package);
+
+            if let Some(node) = resolve.nodes.iter().find(|node| &node.id == id)
+            {
+                // Dev-dependencies are not part of the model.
+                let dependencies = node.deps.iter().filter(|dep| {
+                    dep.dep_kinds
+                        .iter()
+                        .any(|info| info.kind != DependencyKind::Development)
+                });
+                pending.extend(dependencies.map(|dep| &dep.pkg));
+            }
+        }
+
+        paths
+    }
+
+    /// Add the paths of a local package that affect the model
+    fn add_package(&mut self, package: &Package) {
+        let manifest_path = PathBuf::from(&package.manifest_path);
+        let root = manifest_path.parent().map(Path::to_path_buf);
+        self.add_file(&manifest_path);
+
+        for target in &package.targets {
+            let irrelevant = target.kind.iter().any(|kind| {
+                matches!(kind.as_str(), "bin" | "example" | "test" | "bench")
+            });
+            if irrelevant {
+                continue;
+            }
+
+            let src_path = PathBuf::from(&target.src_path);
+
+            // Watching the directory that contains the target's root file is
+            // usually what we want. Unless that's the package root (as is the
+            // case for build scripts), which contains the `target/` directory.
+            // Every build would trigger another one.
+            match src_path.parent() {
+                Some(dir) if Some(dir) != root.as_deref() => {
+                    self.add_directory(dir);
+                }
+                _ => {
+                    self.add_file(&src_path);
+                }
+            }
+        }
+    }
+
+    fn add_directory(&mut self, path: &Path) {
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        self.directories.insert(path);
+    }
+
+    fn add_file(&mut self, path: &Path) {
+        // Editors often replace a file when saving it, so it might not exist
+        // right now. Its directory does.
+        let parent = match path.parent() {
+            Some(parent) if parent != Path::new("") => parent,
+            _ => Path::new("."),
+        };
+        let path = match (parent.canonicalize(), path.file_name()) {
+            (Ok(parent), Some(file_name)) => parent.join(file_name),
+            _ => path.to_path_buf(),
+        };
+
+        self.files.insert(path);
+    }
+
+    /// The paths to register with the watcher
+    ///
+    /// Individual files are not watched directly. If a file is replaced, as
+    /// many editors do when saving, the watch would be lost. Their directories
+    /// are watched instead, unless they are covered by a watched directory
+    /// already.
+    fn watched(&self) -> Vec<(PathBuf, RecursiveMode)> {
+        let mut watched: Vec<_> = self
+            .directories
+            .iter()
+            .map(|directory| (directory.clone(), RecursiveMode::Recursive))
+            .collect();
+
+        let parents: BTreeSet<_> = self
+            .files
+            .iter()
+            .filter_map(|file| file.parent())
+            .filter(|parent| !self.in_directory(parent))
+            .collect();
+        watched.extend(
+            parents.into_iter().map(|parent| {
+                (parent.to_path_buf(), RecursiveMode::NonRecursive)
+            }),
+        );
+
+        watched
+    }
+
+    /// Indicate whether a watch event might affect the model
+    fn is_relevant(&self, event: &notify::Event) -> bool {
+        // Access doesn't change anything, and neither do changes to metadata,
+        // like permissions or timestamps.
+        let relevant_kind = match event.kind {
+            EventKind::Create(_) | EventKind::Remove(_) | EventKind::Any => {
+                true
+            }
+            EventKind::Modify(ModifyKind::Metadata(_)) => false,
+            EventKind::Modify(_) => true,
+            EventKind::Access(_) | EventKind::Other => false,
+        };
+
+        relevant_kind
+            && event.paths.iter().any(|path| {
+                let ignored = IGNORED_EXTENSIONS.iter().any(|ignored| {
+                    path.extension() == Some(OsStr::new(ignored))
+                });
+
+                !ignored
+                    && (self.files.contains(path) || self.in_directory(path))
+            })
+    }
+
+    /// Indicate whether a watch event concerns a manifest
+    fn changes_manifest(&self, event: &notify::Event) -> bool {
+        event.paths.iter().any(|path| {
+            path.file_name() == Some(OsStr::new("Cargo.toml"))
+                && self.files.contains(path)
+        })
+    }
+
+    fn in_directory(&self, path: &Path) -> bool {
+        self.directories
+            .iter()
+            .any(|directory| path.starts_with(directory))
+    }
+}
+
+/// Combine bursts of events into single rebuild requests
+///
+/// Returns the sender that events are to be sent to. Once no more events have
+/// arrived for a short while, a rebuild is requested through `rebuild`. If a
+/// rebuild has already been requested and not been handled yet, there's no
+/// need to request another one.
+pub(crate) fn debounce(rebuild: mpsc::SyncSender<()>) -> mpsc::Sender<()> {
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        while rx.recv().is_ok() {
+            while rx.recv_timeout(DEBOUNCE).is_ok() {}
+
+            match rebuild.try_send(()) {
+                Ok(()) | Err(mpsc::TrySendError::Full(())) => {}
+                Err(mpsc::TrySendError::Disconnected(())) => break,
+            }
+        }
+    });
+
+    tx
+}
+
+/// Rebuild and reload the model, whenever that is requested
+///
+/// Runs until the watcher is dropped. If another rebuild is requested while the
+/// model is being built, the build is cancelled and started over. Evaluating
+/// the model is not cancelled, as a model that runs in this process can't be
+/// interrupted.
+///
+/// Only holds a weak reference to the watcher, which is owned by whoever is
+/// waiting for the model. Otherwise the watcher would never be dropped.
+pub(crate) fn rebuild(
+    model: Model,
+    parameters: Parameters,
+    requests: mpsc::Receiver<()>,
+    events: mpsc::Sender<WatcherEvent>,
+    watcher: Weak<Mutex<PathWatcher>>,
+) {
+    while requests.recv().is_ok() {
+        let event = loop {
+            if events.send(WatcherEvent::Building).is_err() {
+                return;
+            }
+
+            let updated = match watcher.upgrade() {
+                Some(watcher) => watcher
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .update_if_manifest_changed(
+                        &model.manifest_path,
+                        &model.src_path,
+                    ),
+                None => return,
+            };
+            if let Err(err) = updated {
+                break WatcherEvent::Failed(err);
+            }
+
+            let build = match model.start_build() {
+                Ok(build) => build,
+                Err(err) => break WatcherEvent::Failed(err),
+            };
+
+            let diagnostics = match wait_for_build(build, &requests) {
+                BuildOutcome::Finished(Ok(diagnostics)) => diagnostics,
+                BuildOutcome::Finished(Err(err)) => {
+                    break WatcherEvent::Failed(err)
+                }
+                BuildOutcome::Superseded => continue,
+                BuildOutcome::Stopped => return,
+            };
+
+            if events.send(WatcherEvent::Evaluating).is_err() {
+                return;
+            }
+
+            break match model.evaluate(&parameters) {
+                Ok(mut loaded) => {
+                    loaded.diagnostics = diagnostics;
+                    WatcherEvent::Ready(loaded)
+                }
+                Err(err) => WatcherEvent::Failed(err),
+            };
+        };
+
+        if events.send(event).is_err() {
+            return;
+        }
+    }
+}
+
+/// Wait for a build to finish, unless another rebuild is requested first
+fn wait_for_build(
+    mut build: Build,
+    requests: &mpsc::Receiver<()>,
+) -> BuildOutcome {
+    loop {
+        match requests.recv_timeout(POLL) {
+            Ok(()) => {
+                build.cancel();
+                return BuildOutcome::Superseded;
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                build.cancel();
+                return BuildOutcome::Stopped;
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                if let Some(result) = build.try_wait() {
+                    return BuildOutcome::Finished(result);
+                }
+            }
+        }
+    }
+}
+
+enum BuildOutcome {
+    /// The build finished, successfully or not
+    Finished(Result<Vec<Diagnostic>, Error>),
+
+    /// Another rebuild was requested, and the build was cancelled
+    Superseded,
+
+    /// The watcher was dropped, and the build was cancelled
+    Stopped,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use notify::{
+        event::{
+            AccessKind, CreateKind, DataChange, MetadataKind, ModifyKind,
+            RenameMode,
+        },
+        Event, EventKind, RecursiveMode,
+    };
+
+    use super::Paths;
+
+    #[test]
+    fn watched_covers_files_through_their_directories() {
+        let paths = paths();
+
+        assert_eq!(
+            paths.watched(),
+            [
+                (PathBuf::from("/model/src"), RecursiveMode::Recursive),
+                (PathBuf::from("/model"), RecursiveMode::NonRecursive),
+            ]
+        );
+    }
+
+    #[test]
+    fn is_relevant_filters_by_path() {
+        let paths = paths();
+        let modify = EventKind::Modify(ModifyKind::Data(DataChange::Content));
+
+        assert!(paths.is_relevant(&event(modify, &["/model/src/lib.rs"])));
+        assert!(paths.is_relevant(&event(modify, &["/model/Cargo.toml"])));
+
+        assert!(!paths.is_relevant(&event(modify, &["/model/Cargo.lock"])));
+        assert!(!paths.is_relevant(&event(modify, &["/model/target/x.so"])));
+        assert!(!paths.is_relevant(&event(modify, &["/model/src/.a.swp"])));
+    }
+
+    #[test]
+    fn is_relevant_filters_by_kind() {
+        let paths = paths();
+        let path = &["/model/src/lib.rs"];
+
+        let create = EventKind::Create(CreateKind::File);
+        let rename = EventKind::Modify(ModifyKind::Name(RenameMode::To));
+        assert!(paths.is_relevant(&event(create, path)));
+        assert!(paths.is_relevant(&event(rename, path)));
+
+        let access = EventKind::Access(AccessKind::Any);
+        let metadata =
+            EventKind::Modify(ModifyKind::Metadata(MetadataKind::Any));
+        assert!(!paths.is_relevant(&event(access, path)));
+        assert!(!paths.is_relevant(&event(metadata, path)));
+    }
+
+    #[test]
+    fn changes_manifest() {
+        let paths = paths();
+        let create = EventKind::Create(CreateKind::File);
+
+        let manifest = event(create, &["/model/Cargo.toml"]);
+        let code = event(create, &["/model/src/lib.rs"]);
+
+        assert!(paths.changes_manifest(&manifest));
+        assert!(!paths.changes_manifest(&code));
+    }
+
+    fn paths() -> Paths {
+        let mut paths = Paths::default();
+        paths.directories.insert(PathBuf::from("/model/src"));
+        paths.files.insert(PathBuf::from("/model/Cargo.toml"));
+        paths.files.insert(PathBuf::from("/model/build.rs"));
+        paths.files.insert(PathBuf::from("/model/src/main.rs"));
+        paths
+    }
+
+    fn event(kind: EventKind, paths: &[&str]) -> Event {
+        paths
+            .iter()
+            .fold(Event::new(kind), |event, path| event.add_path(path.into()))
+    }
+}
@@ -16,3 +16,4 @@
 
 pub mod debug;
 pub mod mesh;
+pub mod validate;
@@ -4,6 +4,8 @@ use std::{collections::HashMap, hash::Hash};
 
 use fj_math::Point;
 
+use crate::validate::MeshIssues;
+
 /// A triangle mesh
 pub struct Mesh<V> {
     vertices: Vec<V>,
@@ -76,6 +78,16 @@ impl Mesh<Point<3>> {
 
         self.triangles.push(Triangle { points, color });
     }
+
+    /// Check whether the mesh is a closed 2-manifold
+    ///
+    /// Checks the mesh for boundary edges, non-manifold edges, inconsistent
+    /// winding, degenerate triangles, and self-intersections. Points are
+    /// compared exactly, so triangles only count as connected, if they share
+    /// identical points.
+    pub fn validate(&self) -> MeshIssues {
+        crate::validate::validate(self)
+    }
 }
 
 // This needs to be a manual implementation. Deriving `Default` would require
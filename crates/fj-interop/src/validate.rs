@@ -0,0 +1,385 @@
+//! Validation of triangle meshes
+//!
+//! See [`Mesh::validate`].
+
+use std::{collections::HashMap, fmt};
+
+use fj_math::{Point, Scalar, Segment, Triangle};
+
+use crate::mesh::Mesh;
+
+/// The problems that were found while validating a mesh
+///
+/// Returned by [`Mesh::validate`]. A mesh without any problems is a closed
+/// 2-manifold, which means it is the boundary of a solid.
+///
+/// Vertices that are closer to each other than a small tolerance, relative to
+/// the size of the mesh, are considered to be the same vertex. Edges are
+/// reported using one of the merged vertices.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MeshIssues {
+    /// Edges that are only part of a single triangle
+    ///
+    /// Meshes that have any of those are not closed.
+    pub boundary_edges: Vec<[Point<3>; 2]>,
+
+    /// Edges that are part of more than two triangles
+    pub non_manifold_edges: Vec<[Point<3>; 2]>,
+
+    /// Edges whose two triangles are wound inconsistently
+    ///
+    /// The two triangles that share an edge must traverse it in opposite
+    /// directions. Otherwise, one of them is facing the wrong way.
+    pub inconsistent_winding: Vec<[Point<3>; 2]>,
+
+    /// Triangles that don't span any area
+    pub degenerate_triangles: Vec<[Point<3>; 3]>,
+
+    /// Pairs of triangles that intersect each other
+    ///
+    /// Triangles that share a single vertex necessarily touch there. They are
+    /// only reported, if they intersect anywhere else. Triangles that share an
+    /// edge are not checked against each other.
+    pub self_intersections: Vec<[[Point<3>; 3]; 2]>,
+}
+
+impl MeshIssues {
+    /// Indicate whether no problems were found
+    ///
+    /// If this returns `true`, the mesh is a closed 2-manifold.
+    pub fn is_closed_manifold(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+impl fmt::Display for MeshIssues {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_closed_manifold() {
+            return write!(f, "Mesh is a closed 2-manifold");
+        }
+
+        let issues = [
+            (self.boundary_edges.len(), "boundary edges"),
+            (self.non_manifold_edges.len(), "non-manifold edges"),
+            (
+                self.inconsistent_winding.len(),
+                "inconsistently wound edges",
+            ),
+            (self.degenerate_triangles.len(), "degenerate triangles"),
+            (self.self_intersections.len(), "self-intersecting triangles"),
+        ];
+
+        write!(f, "Mesh is not a closed 2-manifold:")?;
+        for (num, description) in issues {
+            if num > 0 {
+                write!(f, "\n- {num} {description}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The tolerance for merging vertices, relative to the size of the mesh
+const MERGE_TOLERANCE: f64 = 1e-9;
+
+pub(crate) fn validate(mesh: &Mesh<Point<3>>) -> MeshIssues {
+    let mut issues = MeshIssues::default();
+
+    let (vertices, indices) = merge_vertices(mesh.vertices());
+
+    // Every edge, along with the directions in which the triangles that share
+    // it traverse it. `true` means the edge is traversed from the smaller to
+    // the larger vertex index.
+    let mut edges: HashMap<[usize; 2], Vec<bool>> = HashMap::new();
+    let mut triangles = Vec::new();
+
+    for triangle in mesh.triangles() {
+        let triangle = triangle.points.map(|point| indices[&point]);
+        let points = triangle.map(|index| vertices[index]);
+
+        if is_degenerate(points) {
+            issues.degenerate_triangles.push(points);
+            continue;
+        }
+
+        let [a, b, c] = triangle;
+        for [start, end] in [[a, b], [b, c], [c, a]] {
+            let (edge, forward) = if start < end {
+                ([start, end], true)
+            } else {
+                ([end, start], false)
+            };
+            edges.entry(edge).or_default().push(forward);
+        }
+
+        triangles.push((triangle, points));
+    }
+
+    for (edge, directions) in edges {
+        let edge = edge.map(|index| vertices[index]);
+        match directions.as_slice() {
+            [_] => issues.boundary_edges.push(edge),
+            [a, b] => {
+                if a == b {
+                    issues.inconsistent_winding.push(edge);
+                }
+            }
+            _ => issues.non_manifold_edges.push(edge),
+        }
+    }
+
+    issues.self_intersections = self_intersections(triangles);
+
+    // The order of the issues depends on the iteration order of the hash map.
+    // Sort them, to make the result deterministic.
+    issues.boundary_edges.sort();
+    issues.non_manifold_edges.sort();
+    issues.inconsistent_winding.sort();
+
+    issues
+}
+
+/// Merge vertices that are closer to each other than the tolerance
+///
+/// Returns the merged vertices, and a map from each of the original vertices
+/// to the index of the merged vertex it was merged into.
+fn merge_vertices(
+    vertices: impl Iterator<Item = Point<3>>,
+) -> (Vec<Point<3>>, HashMap<Point<3>, usize>) {
+    let mut vertices: Vec<_> = vertices.collect();
+    vertices.sort();
+
+    let size = vertices
+        .iter()
+        .map(|vertex| vertex.coords.magnitude())
+        .max()
+        .unwrap_or(Scalar::ZERO);
+    let tolerance = size * Scalar::from(MERGE_TOLERANCE);
+
+    let mut merged: Vec<Point<3>> = Vec::new();
+    let mut indices = HashMap::new();
+
+    for vertex in vertices {
+        // Merged vertices are added in order, so they are sorted along the x
+        // axis too. Only the last few can be close enough.
+        let existing = merged
+            .iter()
+            .enumerate()
+            .rev()
+            .take_while(|(_, other)| vertex.x - other.x <= tolerance)
+            .find(|(_, &other)| (vertex - other).magnitude() <= tolerance)
+            .map(|(index, _)| index);
+
+        let index = existing.unwrap_or_else(|| {
+            merged.push(vertex);
+            merged.len() - 1
+        });
+        indices.insert(vertex, index);
+    }
+
+    (merged, indices)
+}
+
+fn is_degenerate([a, b, c]: [Point<3>; 3]) -> bool {
+    let longest = [b - a, c - b, a - c]
+        .into_iter()
+        .map(|edge| edge.magnitude())
+        .max()
+        // Can't panic. The array has three elements.
+        .unwrap();
+
+    let double_area = (b - a).cross(&(c - a)).magnitude();
+    double_area <= longest * longest * Scalar::from(f64::EPSILON)
+}
+
+/// Find all pairs of triangles that intersect each other
+///
+/// Sorts the triangles along the x axis, so only triangles whose extents on
+/// that axis overlap need to be checked against each other.
+fn self_intersections(
+    triangles: Vec<([usize; 3], [Point<3>; 3])>,
+) -> Vec<[[Point<3>; 3]; 2]> {
+    let mut triangles: Vec<_> = triangles
+        .into_iter()
+        .map(|(indices, points)| {
+            let min = points.map(|point| point.x).into_iter().min();
+            let max = points.map(|point| point.x).into_iter().max();
+
+            // Can't panic. The array has three elements.
+            ([min.unwrap(), max.unwrap()], indices, points)
+        })
+        .collect();
+    triangles.sort();
+
+    let mut intersections = Vec::new();
+
+    for (i, ([_, max_a], a_indices, a)) in triangles.iter().enumerate() {
+        for ([min_b, _], b_indices, b) in &triangles[i + 1..] {
+            if min_b > max_a {
+                break;
+            }
+
+            let mut shared =
+                a_indices.iter().filter(|index| b_indices.contains(index));
+
+            let intersects = match (shared.next(), shared.next()) {
+                (None, _) => Triangle::from_points(*a)
+                    .intersects(&Triangle::from_points(*b)),
+                (Some(&shared), None) => {
+                    // The triangles touch at the shared vertex. If they
+                    // intersect anywhere else, one of the edges opposite that
+                    // vertex must intersect the other triangle.
+                    let a_edge = opposite_edge(a_indices, a, shared);
+                    let b_edge = opposite_edge(b_indices, b, shared);
+
+                    Triangle::from_points(*a).intersects_segment(&b_edge)
+                        || Triangle::from_points(*b).intersects_segment(&a_edge)
+                }
+                (Some(_), Some(_)) => false,
+            };
+
+            if intersects {
+                intersections.push([*a, *b]);
+            }
+        }
+    }
+
+    intersections
+}
+
+/// Return the edge of a triangle that is opposite the given vertex
+fn opposite_edge(
+    indices: &[usize; 3],
+    points: &[Point<3>; 3],
+    vertex: usize,
+) -> Segment<3> {
+    // Can't panic. The vertex is always one of the triangle's.
+    let i = indices.iter().position(|&index| index == vertex).unwrap();
+    Segment::from_points([points[(i + 1) % 3], points[(i + 2) % 3]])
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Point, Vector};
+
+    use crate::mesh::Mesh;
+
+    #[test]
+    fn tetrahedron() {
+        let mesh = tetrahedron_mesh(false);
+        assert!(mesh.validate().is_closed_manifold());
+    }
+
+    #[test]
+    fn inconsistent_winding() {
+        let mesh = tetrahedron_mesh(true);
+        let issues = mesh.validate();
+
+        assert_eq!(issues.inconsistent_winding.len(), 3);
+        assert!(issues.boundary_edges.is_empty());
+    }
+
+    #[test]
+    fn open_mesh() {
+        let [a, b, c, d] = tetrahedron_points();
+
+        let mut mesh = Mesh::new();
+        mesh.push_triangle([a, c, b], [0, 0, 0, 0]);
+        mesh.push_triangle([a, b, d], [0, 0, 0, 0]);
+
+        let issues = mesh.validate();
+        assert_eq!(issues.boundary_edges.len(), 4);
+        assert!(issues.inconsistent_winding.is_empty());
+    }
+
+    #[test]
+    fn non_manifold_and_degenerate() {
+        let mut mesh = tetrahedron_mesh(false);
+        let [a, b, _, _] = tetrahedron_points();
+
+        mesh.push_triangle([a, b, Point::from([0., 0., -1.])], [0, 0, 0, 0]);
+        mesh.push_triangle([a, b, Point::from([0.5, 0., 0.])], [0, 0, 0, 0]);
+
+        let issues = mesh.validate();
+        assert_eq!(issues.non_manifold_edges, vec![[a, b]]);
+        assert_eq!(issues.degenerate_triangles.len(), 1);
+    }
+
+    #[test]
+    fn self_intersection() {
+        let mut mesh = tetrahedron_mesh(false);
+        mesh.push_triangle(
+            [[0.2, 0.2, -1.], [0.2, 0.2, 1.], [3., 3., 0.]].map(Point::from),
+            [0, 0, 0, 0],
+        );
+
+        let issues = mesh.validate();
+        assert!(!issues.self_intersections.is_empty());
+    }
+
+    #[test]
+    fn self_intersection_with_shared_vertex() {
+        let mut mesh = Mesh::new();
+        let [a, b, c, _] = tetrahedron_points();
+
+        // Shares only `a` with the first triangle, but pierces it.
+        mesh.push_triangle([a, b, c], [0, 0, 0, 0]);
+        mesh.push_triangle(
+            [a, Point::from([0.5, 0.1, -1.]), Point::from([0.1, 0.5, 1.])],
+            [0, 0, 0, 0],
+        );
+
+        let issues = mesh.validate();
+        assert!(!issues.self_intersections.is_empty());
+    }
+
+    #[test]
+    fn touching_at_shared_vertex() {
+        let mut mesh = Mesh::new();
+        let [a, b, c, _] = tetrahedron_points();
+
+        mesh.push_triangle([a, b, c], [0, 0, 0, 0]);
+        mesh.push_triangle(
+            [a, Point::from([-1., 0., 0.]), Point::from([0., -1., 0.])],
+            [0, 0, 0, 0],
+        );
+
+        let issues = mesh.validate();
+        assert!(issues.self_intersections.is_empty());
+    }
+
+    #[test]
+    fn nearly_coincident_vertices() {
+        let mut mesh = Mesh::new();
+
+        let [a, b, c, d] = tetrahedron_points();
+        let b_moved = b + Vector::from([0., 1e-12, 0.]);
+
+        mesh.push_triangle([a, c, b], [0, 0, 0, 0]);
+        mesh.push_triangle([a, b_moved, d], [0, 0, 0, 0]);
+        mesh.push_triangle([b, c, d], [0, 0, 0, 0]);
+        mesh.push_triangle([a, d, c], [0, 0, 0, 0]);
+
+        assert!(mesh.validate().is_closed_manifold());
+    }
+
+    fn tetrahedron_mesh(flip_one: bool) -> Mesh<Point<3>> {
+        let [a, b, c, d] = tetrahedron_points();
+
+        let mut mesh = Mesh::new();
+        mesh.push_triangle([a, c, b], [0, 0, 0, 0]);
+        mesh.push_triangle([a, b, d], [0, 0, 0, 0]);
+        mesh.push_triangle([b, c, d], [0, 0, 0, 0]);
+
+        let last = if flip_one { [a, c, d] } else { [a, d, c] };
+        mesh.push_triangle(last, [0, 0, 0, 0]);
+
+        mesh
+    }
+
+    fn tetrahedron_points() -> [Point<3>; 4] {
+        [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.], [0., 0., 1.]]
+            .map(Point::from)
+    }
+}
@@ -0,0 +1,128 @@
+//! Benchmarks for the `Shape` API
+//!
+//! Builds patterns of adjacent squares, which share their vertices with their
+//! neighbors, and measures how long that, and looking up all objects of the
+//! resulting shape, takes. The time per square stays roughly constant, as the
+//! pattern grows.
+//!
+//! For comparison, the lookup is also done with a linear scan over all objects
+//! of the same type, which is how `Shape::get_handle` used to work. This grows
+//! quadratically, so it's only done for the smaller patterns.
+//!
+//! Run with `cargo bench -p fj-kernel`. This doesn't use the standard benchmark
+//! harness, as that requires a nightly compiler.
+
+use std::time::{Duration, Instant};
+
+use fj_kernel::{
+    shape::{Iter, Object, Shape},
+    topology::Cycle,
+};
+
+/// The largest pattern, for which the linear scan is measured
+const MAX_SCAN: usize = 32;
+
+fn main() {
+    println!(
+        "{:>8} {:>10} {:>12} {:>12} {:>12} {:>12} {:>12}",
+        "squares",
+        "vertices",
+        "build",
+        "per square",
+        "lookup",
+        "scan",
+        "iterate"
+    );
+
+    for n in [8, 16, 32, 64, 128, 256] {
+        let (shape, build) = measure(|| pattern(n));
+        let (_, lookup) = measure(|| lookup_all(&shape));
+        let (_, iterate) = measure(|| iterate_all(&shape));
+
+        let scan = if n <= MAX_SCAN {
+            let (_, scan) = measure(|| scan_all(&shape));
+            format!("{:?}", scan)
+        } else {
+            String::from("-")
+        };
+
+        let squares = n * n;
+        println!(
+            "{:>8} {:>10} {:>12?} {:>12?} {:>12?} {:>12} {:>12?}",
+            squares,
+            shape.vertices().count(),
+            build,
+            build / squares as u32,
+            lookup,
+            scan,
+            iterate,
+        );
+    }
+}
+
+/// Build a pattern of `n` by `n` squares
+fn pattern(n: usize) -> Shape {
+    let mut shape = Shape::new();
+
+    for x in 0..n {
+        for y in 0..n {
+            let [x, y] = [x, y].map(|coord| coord as f64);
+
+            Cycle::builder(&mut shape)
+                .build_polygon([
+                    [x, y, 0.],
+                    [x + 1., y, 0.],
+                    [x + 1., y + 1., 0.],
+                    [x, y + 1., 0.],
+                ])
+                .unwrap();
+        }
+    }
+
+    shape
+}
+
+/// Look up the handle of every point, vertex, edge and cycle of the shape
+fn lookup_all(shape: &Shape) {
+    for point in shape.points().values() {
+        assert!(shape.get_handle(&point).is_some());
+    }
+    for vertex in shape.vertices().values() {
+        assert!(shape.get_handle(&vertex).is_some());
+    }
+    for edge in shape.edges().values() {
+        assert!(shape.get_handle(&edge).is_some());
+    }
+    for cycle in shape.cycles().values() {
+        assert!(shape.get_handle(&cycle).is_some());
+    }
+}
+
+/// Look up the same objects as [`lookup_all`], using a linear scan
+fn scan_all(shape: &Shape) {
+    scan(shape.points());
+    scan(shape.vertices());
+    scan(shape.edges());
+    scan(shape.cycles());
+
+    fn scan<T: Object>(objects: Iter<T>) {
+        let handles: Vec<_> = objects.collect();
+
+        for object in handles.iter().map(|handle| handle.get()) {
+            assert!(handles.iter().any(|handle| handle.get() == object));
+        }
+    }
+}
+
+fn iterate_all(shape: &Shape) -> usize {
+    shape.points().count()
+        + shape.vertices().count()
+        + shape.edges().count()
+        + shape.cycles().count()
+}
+
+fn measure<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
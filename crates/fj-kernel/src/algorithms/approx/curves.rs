@@ -2,7 +2,7 @@ use std::cmp::max;
 
 use fj_math::{Point, Scalar};
 
-use crate::geometry::{Circle, Curve};
+use crate::geometry::{Circle, Curve, Ellipse, NurbsCurve};
 
 use super::Tolerance;
 
@@ -11,32 +11,47 @@ use super::Tolerance;
 /// `tolerance` defines how far the approximation is allowed to deviate from the
 /// actual edge.
 ///
-/// # Implementation Note
+/// `range` defines the section of the curve that is approximated, in curve
+/// coordinates. Only points in between the boundaries of the range are
+/// returned, not the boundaries themselves. Those are provided by the vertices
+/// that bound the edge.
 ///
-/// This only works as it is, because edges are severely limited and don't
-/// define which section of the curve they inhabit. Once they do that, we need
-/// an `approximate_between(a, b)` method instead, where `a` and `b` are the
-/// vertices that bound the edge on the curve.
-///
-/// The `approximate_between` methods of the curves then need to make sure to
-/// only return points in between those vertices, not the vertices themselves.
+/// If `range` is `None`, the whole curve is approximated. This is only possible
+/// for curves that connect to themselves. In that case, the approximation
+/// starts with the point at the start of the curve.
 pub fn approx_curve(
     curve: &Curve,
+    range: Option<[Point<1>; 2]>,
     tolerance: Tolerance,
     out: &mut Vec<Point<3>>,
 ) {
+    let range = match range {
+        Some(range) => range,
+        None => match curve.closed_range() {
+            Some(range) => {
+                out.push(curve.point_curve_to_model(&range[0]));
+                range
+            }
+            None => return,
+        },
+    };
+
     match curve {
-        Curve::Circle(curve) => approx_circle(curve, tolerance, out),
+        Curve::Circle(curve) => approx_circle(curve, range, tolerance, out),
+        Curve::Ellipse(curve) => approx_ellipse(curve, range, tolerance, out),
         Curve::Line(_) => {}
+        Curve::Nurbs(curve) => approx_nurbs(curve, range, tolerance, out),
     }
 }
 
 /// Approximate the circle
 ///
 /// `tolerance` specifies how much the approximation is allowed to deviate
-/// from the circle.
+/// from the circle. Only points within `range`, excluding its boundaries, are
+/// returned.
 pub fn approx_circle(
     circle: &Circle,
+    range: [Point<1>; 2],
     tolerance: Tolerance,
     out: &mut Vec<Point<3>>,
 ) {
@@ -50,14 +65,138 @@ pub fn approx_circle(
 
     let n = number_of_vertices_for_circle(tolerance, radius);
 
-    for i in 0..n {
-        let angle = Scalar::PI * 2. / n as f64 * i as f64;
-        let point = circle.point_curve_to_model(&Point::from([angle]));
-        out.push(point);
+    for t in subdivide_angle(range, n) {
+        out.push(circle.point_curve_to_model(&t));
+    }
+}
+
+/// Approximate the ellipse
+///
+/// `tolerance` specifies how much the approximation is allowed to deviate
+/// from the ellipse. Only points within `range`, excluding its boundaries, are
+/// returned.
+pub fn approx_ellipse(
+    ellipse: &Ellipse,
+    range: [Point<1>; 2],
+    tolerance: Tolerance,
+    out: &mut Vec<Point<3>>,
+) {
+    // An ellipse is an affine transformation of a circle. Approximating it
+    // like a circle whose radius is the major radius of the ellipse, won't
+    // deviate more than that circle's approximation.
+    let n = number_of_vertices_for_circle(tolerance, ellipse.major_radius());
+
+    for t in subdivide_angle(range, n) {
+        out.push(ellipse.point_curve_to_model(&t));
+    }
+}
+
+/// Subdivide a range of angles
+///
+/// `n` is the number of segments that a full circle would be subdivided into.
+/// Returns the points between the segments, which means the boundaries of the
+/// range are not included.
+fn subdivide_angle([start, end]: [Point<1>; 2], n: u64) -> Vec<Point<1>> {
+    let step = Scalar::PI * 2. / n as f64;
+    let num_segments = ((end.t - start.t).abs() / step)
+        .ceil()
+        .max(Scalar::ONE)
+        .into_u64();
+
+    (1..num_segments)
+        .map(|i| {
+            let t =
+                start.t + (end.t - start.t) * (i as f64 / num_segments as f64);
+            Point::from([t])
+        })
+        .collect()
+}
+
+/// Approximate the NURBS curve
+///
+/// `tolerance` specifies how much the approximation is allowed to deviate
+/// from the curve. Only points within `range`, excluding its boundaries, are
+/// returned. The range can go in either direction.
+pub fn approx_nurbs(
+    curve: &NurbsCurve,
+    [start, end]: [Point<1>; 2],
+    tolerance: Tolerance,
+    out: &mut Vec<Point<3>>,
+) {
+    let n = number_of_segments_for_spline(
+        curve.degree,
+        &curve.control_points,
+        tolerance,
+    );
+
+    let (min, max) = if start.t <= end.t {
+        (start.t, end.t)
+    } else {
+        (end.t, start.t)
+    };
+
+    let mut points = Vec::new();
+
+    for span in curve.knots.windows(2) {
+        // Can't panic, as we passed `2` to `windows`.
+        let (a, b) = (span[0].max(min), span[1].min(max));
+        if a >= b {
+            continue;
+        }
+
+        for i in 0..n {
+            let t = a + (b - a) * (i as f64 / n as f64);
+            if t == min {
+                continue;
+            }
+
+            points.push(curve.point_curve_to_model(&Point::from([t])));
+        }
+    }
+
+    if start.t > end.t {
+        points.reverse();
     }
+
+    out.extend(points);
 }
 
-fn number_of_vertices_for_circle(tolerance: Tolerance, radius: Scalar) -> u64 {
+/// Compute the number of segments per knot span of a spline
+///
+/// This is an estimate that is based on the control polygon of the spline. For
+/// a polynomial curve of degree `p` that is split into `n` segments, the
+/// distance between the curve and its segments is at most
+/// `p * (p - 1) * max(|P[i-1] - 2 * P[i] + P[i+1]|) / (8 * n^2)`. The weights
+/// of rational splines are not taken into account.
+pub fn number_of_segments_for_spline(
+    degree: usize,
+    control_points: &[Point<3>],
+    tolerance: Tolerance,
+) -> u64 {
+    let max_second_difference = control_points
+        .windows(3)
+        .map(|points| {
+            // Can't panic, as we passed `3` to `windows`.
+            (points[0].coords - points[1].coords * 2. + points[2].coords)
+                .magnitude()
+        })
+        .max()
+        .unwrap_or(Scalar::ZERO);
+
+    let p = Scalar::from_u64(degree as u64);
+    let n = (p * (p - Scalar::ONE) * max_second_difference
+        / (tolerance.inner() * 8.))
+        .sqrt()
+        .ceil()
+        .into_u64();
+
+    max(n, 1)
+}
+
+pub fn number_of_vertices_for_circle(
+    tolerance: Tolerance,
+    radius: Scalar,
+) -> u64 {
     let n = (Scalar::PI / (Scalar::ONE - (tolerance.inner() / radius)).acos())
         .ceil()
         .into_u64();
@@ -67,9 +206,81 @@ fn number_of_vertices_for_circle(tolerance: Tolerance, radius: Scalar) -> u64 {
 
 #[cfg(test)]
 mod tests {
-    use fj_math::Scalar;
+    use std::f64::consts::{FRAC_1_SQRT_2, PI};
+
+    use fj_math::{Point, Scalar, Vector};
+
+    use crate::{
+        algorithms::Tolerance,
+        geometry::{Circle, NurbsCurve},
+    };
+
+    #[test]
+    fn approx_circle_arc() {
+        let tolerance = Tolerance::from_scalar(0.01).unwrap();
 
-    use crate::algorithms::Tolerance;
+        let circle = Circle {
+            center: Point::origin(),
+            a: Vector::from([1., 0., 0.]),
+            b: Vector::from([0., 1., 0.]),
+        };
+
+        let mut full = Vec::new();
+        let range = [Point::from([0.]), Point::from([PI * 2.])];
+        super::approx_circle(&circle, range, tolerance, &mut full);
+
+        // The arc goes across the starting point of the circle.
+        let mut arc = Vec::new();
+        let range = [Point::from([PI * 1.75]), Point::from([PI * 2.25])];
+        super::approx_circle(&circle, range, tolerance, &mut arc);
+
+        // The arc covers a quarter of the circle, and should be approximated
+        // with the same density.
+        assert!(!arc.is_empty());
+        assert!(arc.len() <= full.len() / 4 + 1);
+
+        for point in arc {
+            assert!(point.x > Scalar::from(FRAC_1_SQRT_2));
+        }
+    }
+
+    #[test]
+    fn approx_nurbs() {
+        let tolerance = Tolerance::from_scalar(0.02).unwrap();
+
+        let curve = NurbsCurve::clamped(
+            2,
+            vec![
+                Point::from([0., 0., 0.]),
+                Point::from([1., 2., 0.]),
+                Point::from([2., 0., 0.]),
+            ],
+        );
+
+        let mut points = Vec::new();
+        let range = [Point::from([0.]), Point::from([1.])];
+        super::approx_nurbs(&curve, range, tolerance, &mut points);
+
+        // The start and end are provided by the vertices of the edge.
+        assert!(!points.contains(&Point::from([0., 0., 0.])));
+        assert!(!points.contains(&Point::from([2., 0., 0.])));
+
+        // Check that the approximation doesn't deviate from the curve by more
+        // than the tolerance. The curve is a parabola, which is symmetric.
+        let mut all_points = vec![Point::from([0., 0., 0.])];
+        all_points.extend(points);
+        all_points.push(Point::from([2., 0., 0.]));
+
+        for segment in all_points.windows(2) {
+            let [a, b] = [segment[0], segment[1]]
+                .map(|point| curve.point_model_to_curve(&point).t);
+            let center =
+                curve.point_curve_to_model(&Point::from([(a + b) / 2.]));
+            let chord_center = segment[0] + (segment[1] - segment[0]) * 0.5;
+
+            assert!((center - chord_center).magnitude() <= tolerance.inner());
+        }
+    }
 
     #[test]
     fn number_of_vertices_for_circle() {
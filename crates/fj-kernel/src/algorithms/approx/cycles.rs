@@ -2,7 +2,7 @@ use fj_math::{Point, Segment};
 
 use crate::topology::Cycle;
 
-use super::{curves::approx_curve, edges::approximate_edge, Tolerance};
+use super::{edges::approx_edge, Tolerance};
 
 /// An approximation of a [`Cycle`]
 #[derive(Debug, Eq, PartialEq, Hash)]
@@ -19,11 +19,15 @@ impl CycleApprox {
     pub fn new(cycle: &Cycle, tolerance: Tolerance) -> Self {
         let mut points = Vec::new();
 
-        for edge in cycle.edges() {
-            let mut edge_points = Vec::new();
-            approx_curve(&edge.curve(), tolerance, &mut edge_points);
+        // The approximation follows the cycle, so every edge needs to be
+        // approximated in the direction the cycle runs through it.
+        for (edge, &reversed) in cycle.edges().zip(&cycle.reversed) {
+            let mut edge_points = approx_edge(&edge, tolerance);
+            if reversed {
+                edge_points.reverse();
+            }
 
-            points.extend(approximate_edge(edge_points, edge.vertices()));
+            points.extend(edge_points);
         }
 
         points.dedup();
@@ -58,3 +62,62 @@ where
         Self { points }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Point, Scalar};
+
+    use crate::{
+        algorithms::Tolerance,
+        shape::Shape,
+        topology::{Cycle, Edge},
+    };
+
+    use super::CycleApprox;
+
+    #[test]
+    fn arc() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+
+        let tolerance = Tolerance::from_scalar(0.01).unwrap();
+
+        // A half circle, closed by a line segment.
+        let arc = Edge::builder(&mut shape)
+            .build_arc(Scalar::ONE, [Scalar::ZERO, Scalar::PI])?;
+        let [a, b] = arc.get().vertices.clone().unwrap();
+        let line = Edge::builder(&mut shape)
+            .build_line_segment_from_vertices([b, a])?;
+
+        let cycle = Cycle::from_edges(vec![arc, line]);
+        let approx = CycleApprox::new(&cycle, tolerance);
+
+        let a = Point::from([1., 0., 0.]);
+        let b = Point::from([-1., 0., 0.]);
+
+        assert_eq!(approx.points.first(), Some(&a));
+        assert_eq!(approx.points.last(), Some(&a));
+        assert!(approx
+            .points
+            .iter()
+            .any(|point| (point - b).magnitude() < Scalar::from(1e-12)));
+
+        // All points of the approximation must be on the half of the circle
+        // that the arc covers.
+        for point in &approx.points {
+            assert!(point.y > Scalar::from(-1e-12));
+        }
+
+        // The arc should be approximated with the same density as a full
+        // circle would be.
+        let full_circle = {
+            let edge = Edge::builder(&mut shape).build_circle(Scalar::ONE)?;
+            let cycle = Cycle::from_edges(vec![edge]);
+            CycleApprox::new(&cycle, tolerance)
+        };
+        let expected = (full_circle.points.len() - 1) as f64 / 2.;
+        let actual = (approx.points.len() - 1) as f64;
+        assert!((actual - expected).abs() <= 2.);
+
+        Ok(())
+    }
+}
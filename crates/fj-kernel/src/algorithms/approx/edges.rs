@@ -1,6 +1,24 @@
 use fj_math::Point;
 
-use crate::topology::Vertex;
+use crate::topology::{Edge, Vertex};
+
+use super::{curves::approx_curve, Tolerance};
+
+/// Compute an approximation of an edge
+///
+/// The approximation starts at the first vertex of the edge, and ends at the
+/// second one. Edges without vertices start and end at the same point.
+pub fn approx_edge(edge: &Edge, tolerance: Tolerance) -> Vec<Point<3>> {
+    let curve = edge.curve();
+    let range = edge.vertices().map(|vertices| {
+        curve.range_between(vertices.map(|vertex| vertex.point()))
+    });
+
+    let mut points = Vec::new();
+    approx_curve(&curve, range, tolerance, &mut points);
+
+    approximate_edge(points, edge.vertices())
+}
 
 pub fn approximate_edge(
     mut points: Vec<Point<3>>,
@@ -28,18 +28,13 @@ impl FaceApprox {
     /// `tolerance` defines how far the approximation is allowed to deviate from
     /// the actual face.
     pub fn new(face: &Face, tolerance: Tolerance) -> Self {
-        // Curved faces whose curvature is not fully defined by their edges
-        // are not supported yet. For that reason, we can fully ignore `face`'s
-        // `surface` field and just pass the edges to `Self::for_edges`.
+        // This only approximates the edges that bound the face. For faces
+        // whose curvature is fully defined by their edges, like planes or
+        // cylinders, that is sufficient.
         //
-        // An example of a curved face that is supported, is the cylinder. Its
-        // curvature is fully defined be the edges (circles) that border it. The
-        // circle approximations are sufficient to triangulate the surface.
-        //
-        // An example of a curved face that is currently not supported, and thus
-        // doesn't need to be handled here, is a sphere. A spherical face would
-        // would need to provide its own approximation, as the edges that bound
-        // it have nothing to do with its curvature.
+        // Faces whose curvature is not fully defined by their edges, like
+        // spheres, need additional points from within the surface. Those are
+        // added during triangulation, where the surface coordinates are known.
 
         let mut points = HashSet::new();
         let mut exteriors = Vec::new();
@@ -62,9 +57,13 @@ impl FaceApprox {
         //
         // See this issue for some background:
         // https://github.com/hannobraun/Fornjot/issues/250
+        //
+        // A face without an exterior cycle is only valid on a closed surface,
+        // like a sphere, where it covers the whole surface. Whether that is
+        // the case is checked during triangulation.
         let exterior = exteriors
             .pop()
-            .expect("Can't approximate face without exterior cycle");
+            .unwrap_or(CycleApprox { points: Vec::new() });
         assert!(
             exteriors.is_empty(),
             "Approximation only supports faces with one exterior cycle",
@@ -107,7 +106,7 @@ mod tests {
 
         let face = Face::builder(Surface::xy_plane(), &mut shape)
             .with_exterior_polygon([a, b, c, d])
-            .with_interior_polygon([e, f, g, h])
+            .with_interior_polygon([e, h, g, f])
             .build()?;
 
         assert_eq!(
@@ -118,7 +117,7 @@ mod tests {
                     points: vec![a, b, c, d, a],
                 },
                 interiors: set![CycleApprox {
-                    points: vec![e, f, g, h, e],
+                    points: vec![e, h, g, f, e],
                 }],
             }
         );
@@ -2,6 +2,10 @@ mod curves;
 mod cycles;
 mod edges;
 mod faces;
+mod surfaces;
 mod tolerance;
 
-pub use self::{cycles::CycleApprox, faces::FaceApprox, tolerance::Tolerance};
+pub use self::{
+    cycles::CycleApprox, faces::FaceApprox,
+    surfaces::approx_surface_coordinate, tolerance::Tolerance,
+};
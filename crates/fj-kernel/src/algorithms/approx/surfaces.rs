@@ -0,0 +1,162 @@
+use fj_math::{Point, Scalar};
+
+use crate::geometry::{NurbsSurface, Surface};
+
+use super::{
+    curves::{number_of_segments_for_spline, number_of_vertices_for_circle},
+    Tolerance,
+};
+
+/// Compute the values at which a surface coordinate needs to be sampled
+///
+/// Returns the values between `min` and `max` (both inclusive) at which the
+/// surface needs to be sampled along the coordinate with index `axis` (`0` for
+/// `u`, `1` for `v`), so that the approximation doesn't deviate from the
+/// surface by more than `tolerance`.
+///
+/// Returns `None`, if the surface is straight along that coordinate. In that
+/// case, the approximations of the edges that bound a face on the surface are
+/// sufficient to approximate the face, and no additional points are required.
+pub fn approx_surface_coordinate(
+    surface: &Surface,
+    axis: usize,
+    [min, max]: [Scalar; 2],
+    tolerance: Tolerance,
+) -> Option<Vec<Scalar>> {
+    let radius = match (surface, axis) {
+        (Surface::Sphere(sphere), _) => sphere.radius(),
+        (Surface::Torus(torus), 0) => {
+            torus.major_radius() + torus.minor_radius()
+        }
+        (Surface::Torus(torus), _) => torus.minor_radius(),
+        (Surface::Nurbs(surface), _) => {
+            return Some(approx_nurbs_coordinate(
+                surface,
+                axis,
+                [min, max],
+                tolerance,
+            ));
+        }
+
+        // Cones, cylinders, and swept curves are ruled surfaces. Along `v`,
+        // they are straight. Along `u`, their curvature is fully defined by the
+        // edges that bound them, so the edge approximation is sufficient.
+        _ => return None,
+    };
+
+    let step = Scalar::PI * 2.
+        / number_of_vertices_for_circle(tolerance, radius) as f64;
+    let n = ((max - min) / step).ceil().max(Scalar::ONE).into_u64();
+
+    Some(subdivide([min, max], n))
+}
+
+fn approx_nurbs_coordinate(
+    surface: &NurbsSurface,
+    axis: usize,
+    [min, max]: [Scalar; 2],
+    tolerance: Tolerance,
+) -> Vec<Scalar> {
+    let degree = surface.degree[axis];
+    let knots = &surface.knots[axis];
+
+    // Each column (or row) of the control net defines a curve along the axis.
+    // Use the one that requires the most segments.
+    let lines: Vec<Vec<Point<3>>> = match axis {
+        0 => (0..surface.control_points[0].len())
+            .map(|j| surface.control_points.iter().map(|row| row[j]).collect())
+            .collect(),
+        _ => surface.control_points.clone(),
+    };
+    let segments_per_span = lines
+        .iter()
+        .map(|line| number_of_segments_for_spline(degree, line, tolerance))
+        .max()
+        .unwrap_or(1);
+
+    let num_spans = knots
+        .windows(2)
+        // Can't panic, as we passed `2` to `windows`.
+        .filter(|span| span[0].max(min) < span[1].min(max))
+        .count()
+        .max(1);
+
+    subdivide([min, max], segments_per_span * num_spans as u64)
+}
+
+/// Subdivide a range into `n` segments, returning all segment boundaries
+fn subdivide([min, max]: [Scalar; 2], n: u64) -> Vec<Scalar> {
+    (0..=n)
+        .map(|i| {
+            // Return `max` exactly, instead of relying on the computation to
+            // end up there. Otherwise, floating point inaccuracies could lead
+            // to points right next to the boundary.
+            if i == n {
+                return max;
+            }
+
+            min + (max - min) * (i as f64 / n as f64)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Point, Scalar, Vector};
+
+    use crate::{
+        algorithms::Tolerance,
+        geometry::{Cylinder, Sphere, Surface},
+    };
+
+    use super::approx_surface_coordinate;
+
+    #[test]
+    fn ruled_surfaces_need_no_samples() {
+        let tolerance = Tolerance::from_scalar(0.1).unwrap();
+
+        let cylinder = Surface::Cylinder(Cylinder {
+            center: Point::origin(),
+            a: Vector::from([1., 0., 0.]),
+            b: Vector::from([0., 1., 0.]),
+            axis: Vector::from([0., 0., 1.]),
+        });
+
+        for surface in [Surface::xy_plane(), cylinder] {
+            for axis in [0, 1] {
+                assert_eq!(
+                    approx_surface_coordinate(
+                        &surface,
+                        axis,
+                        [Scalar::ZERO, Scalar::ONE],
+                        tolerance
+                    ),
+                    None,
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn sphere_samples_include_bounds() {
+        let tolerance = Tolerance::from_scalar(0.1).unwrap();
+
+        let sphere = Surface::Sphere(Sphere {
+            center: Point::origin(),
+            a: Vector::from([1., 0., 0.]),
+            b: Vector::from([0., 1., 0.]),
+            c: Vector::from([0., 0., 1.]),
+        });
+
+        let min = Scalar::ZERO;
+        let max = Scalar::PI * 2.;
+
+        let values =
+            approx_surface_coordinate(&sphere, 0, [min, max], tolerance)
+                .unwrap();
+
+        assert_eq!(values.first(), Some(&min));
+        assert_eq!(values.last(), Some(&max));
+        assert!(values.len() > 3);
+    }
+}
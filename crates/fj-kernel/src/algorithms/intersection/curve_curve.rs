@@ -0,0 +1,937 @@
+//! Intersection between two curves
+
+use fj_math::{Point, Scalar};
+
+use crate::{
+    algorithms::Tolerance,
+    geometry::{Curve, Ellipse, Line, Surface, SweptCurve},
+};
+
+use super::{
+    conic,
+    numeric::{self, Found},
+    quadratic::{self, Roots},
+    surface_surface,
+};
+
+/// An intersection between two curves
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CurveCurveIntersection {
+    /// The curves meet at a single point
+    Point {
+        /// The curve coordinate of the point on the first curve
+        on_a: Point<1>,
+
+        /// The curve coordinate of the point on the second curve
+        on_b: Point<1>,
+
+        /// The point in model coordinates
+        point: Point<3>,
+
+        /// Whether the curves touch at the point, instead of crossing
+        tangent: bool,
+    },
+
+    /// The curves coincide
+    ///
+    /// `on_a` is the range of curve coordinates on the first curve, in which
+    /// the curves coincide. `on_b` is the corresponding range on the second
+    /// curve, meaning `on_a[i]` and `on_b[i]` refer to the same point. If the
+    /// curves run in opposite directions, the range on the second curve is
+    /// reversed.
+    ///
+    /// Lines are infinite, which means two lines always coincide completely. In
+    /// that case, `on_a` is the range between the curve coordinates `0` and
+    /// `1`.
+    Coincident {
+        /// The range on the first curve
+        on_a: [Point<1>; 2],
+
+        /// The corresponding range on the second curve
+        on_b: [Point<1>; 2],
+    },
+}
+
+impl CurveCurveIntersection {
+    /// Exchange the roles of the two curves
+    fn swap(self) -> Self {
+        match self {
+            Self::Point {
+                on_a,
+                on_b,
+                point,
+                tangent,
+            } => Self::Point {
+                on_a: on_b,
+                on_b: on_a,
+                point,
+                tangent,
+            },
+            Self::Coincident { on_a, on_b } => Self::Coincident {
+                on_a: on_b,
+                on_b: on_a,
+            },
+        }
+    }
+
+    /// Access the first curve coordinate on the first curve
+    fn start_on_a(&self) -> Point<1> {
+        match self {
+            Self::Point { on_a, .. } => *on_a,
+            Self::Coincident { on_a, .. } => on_a[0],
+        }
+    }
+}
+
+/// Compute the intersections between two curves
+///
+/// Returns the intersections, sorted by their curve coordinates on `a`.
+///
+/// Intersections of lines with lines, circles and ellipses, as well as
+/// intersections between circles, are computed analytically. All other cases
+/// are handled by sampling one of the curves and refining the places where it
+/// comes close to the other. This can miss intersections that are very close
+/// together, relative to the size of the curves.
+pub fn curve_curve(
+    a: &Curve,
+    b: &Curve,
+    tolerance: Tolerance,
+) -> Vec<CurveCurveIntersection> {
+    let tolerance = tolerance.inner();
+
+    let mut intersections = match (a, b) {
+        (Curve::Line(a), Curve::Line(b)) => line_line(a, b, tolerance),
+        (Curve::Line(line), curve) => line_curve(line, curve, tolerance),
+        (curve, Curve::Line(line)) => line_curve(line, curve, tolerance)
+            .into_iter()
+            .map(CurveCurveIntersection::swap)
+            .collect(),
+        (a, b) => match (conic(a), conic(b)) {
+            (Some(conic_a), Some(conic_b)) => {
+                conic_conic([a, b], [conic_a, conic_b], tolerance)
+            }
+            _ => sampled(a, b, tolerance),
+        },
+    };
+
+    intersections.sort_by_key(|intersection| intersection.start_on_a());
+    intersections
+}
+
+fn line_line(
+    a: &Line,
+    b: &Line,
+    tolerance: Scalar,
+) -> Vec<CurveCurveIntersection> {
+    // Algorithm from Real-Time Collision Detection by Christer Ericson. See
+    // section 5.1.8, Closest Points of Two Lines.
+
+    let r = a.origin - b.origin;
+
+    let aa = a.direction.dot(&a.direction);
+    let ab = a.direction.dot(&b.direction);
+    let bb = b.direction.dot(&b.direction);
+    let ar = a.direction.dot(&r);
+    let br = b.direction.dot(&r);
+
+    let denom = aa * bb - ab * ab;
+
+    if denom <= aa * bb * f64::EPSILON {
+        // The lines are parallel.
+        let on_a = [Scalar::ZERO, Scalar::ONE].map(|t| Point::from([t]));
+        let points = on_a.map(|t| a.point_curve_to_model(&t));
+        let on_b = points.map(|point| b.point_model_to_curve(&point));
+
+        let distance =
+            (points[0] - b.point_curve_to_model(&on_b[0])).magnitude();
+        if distance > tolerance {
+            return Vec::new();
+        }
+
+        return vec![CurveCurveIntersection::Coincident { on_a, on_b }];
+    }
+
+    let s = (ab * br - bb * ar) / denom;
+    let t = (aa * br - ab * ar) / denom;
+
+    let on_a = Point::from([s]);
+    let on_b = Point::from([t]);
+
+    let point = a.point_curve_to_model(&on_a);
+    if (point - b.point_curve_to_model(&on_b)).magnitude() > tolerance {
+        return Vec::new();
+    }
+
+    vec![CurveCurveIntersection::Point {
+        on_a,
+        on_b,
+        point,
+        tangent: false,
+    }]
+}
+
+fn line_curve(
+    line: &Line,
+    curve: &Curve,
+    tolerance: Scalar,
+) -> Vec<CurveCurveIntersection> {
+    match conic(curve) {
+        Some(conic) => line_conic(line, curve, &conic, tolerance),
+        None => sampled(curve, &Curve::Line(*line), tolerance)
+            .into_iter()
+            .map(CurveCurveIntersection::swap)
+            .collect(),
+    }
+}
+
+/// Intersect a line with a circle or an ellipse
+///
+/// `curve` is the original curve, `conic` the same curve as an ellipse.
+fn line_conic(
+    line: &Line,
+    curve: &Curve,
+    conic: &Ellipse,
+    tolerance: Scalar,
+) -> Vec<CurveCurveIntersection> {
+    let normal = conic.a.cross(&conic.b).normalize();
+
+    let point = |t: Scalar| line.point_curve_to_model(&Point::from([t]));
+    let distance = |t: Scalar| {
+        let point = point(t);
+        let on_curve = curve.point_model_to_curve(&point);
+        (point - curve.point_curve_to_model(&on_curve)).magnitude()
+    };
+
+    let to_intersection = |(t, tangent): (Scalar, bool)| {
+        let point = point(t);
+        CurveCurveIntersection::Point {
+            on_a: Point::from([t]),
+            on_b: curve.point_model_to_curve(&point),
+            point,
+            tangent,
+        }
+    };
+
+    // The point on the line that is closest to the center of the conic. We
+    // check whether the line lies within the plane of the conic around that
+    // point.
+    let closest = line.point_model_to_curve(&conic.center);
+    let offset = point(closest.t) - conic.center;
+    let tilt = normal.dot(&line.direction.normalize()) * conic.major_radius();
+
+    let in_plane =
+        normal.dot(&offset).abs() <= tolerance && tilt.abs() <= tolerance;
+
+    if !in_plane {
+        // The line pierces the plane of the conic, which means there is at
+        // most one intersection.
+        let denom = normal.dot(&line.direction);
+        if denom == Scalar::ZERO {
+            return Vec::new();
+        }
+
+        let t = -normal.dot(&(line.origin - conic.center)) / denom;
+        if distance(t) > tolerance {
+            return Vec::new();
+        }
+
+        return vec![to_intersection((t, false))];
+    }
+
+    // Within the plane of the conic, we can use a coordinate system in which
+    // the conic is the unit circle.
+    let aa = conic.a.dot(&conic.a);
+    let bb = conic.b.dot(&conic.b);
+
+    let r = line.origin - conic.center;
+    let r = [r.dot(&conic.a) / aa, r.dot(&conic.b) / bb];
+    let d = [
+        line.direction.dot(&conic.a) / aa,
+        line.direction.dot(&conic.b) / bb,
+    ];
+
+    let coefficients = [
+        d[0] * d[0] + d[1] * d[1],
+        (r[0] * d[0] + r[1] * d[1]) * 2.,
+        r[0] * r[0] + r[1] * r[1] - Scalar::ONE,
+    ];
+
+    match quadratic::roots(coefficients, point, distance, tolerance) {
+        // This would only happen for a degenerate line.
+        Roots::All => Vec::new(),
+        Roots::Points(roots) => {
+            roots.into_iter().map(to_intersection).collect()
+        }
+    }
+}
+
+/// Intersect two curves that are circles or ellipses
+///
+/// `curves` are the original curves, `conics` the same curves as ellipses.
+fn conic_conic(
+    curves: [&Curve; 2],
+    conics: [Ellipse; 2],
+    tolerance: Scalar,
+) -> Vec<CurveCurveIntersection> {
+    let [curve_a, curve_b] = curves;
+    let [a, b] = conics;
+
+    if let Some(coincident) = conic_coincidence(curves, tolerance) {
+        return vec![coincident];
+    }
+
+    let normal = a.a.cross(&a.b).normalize();
+    let is_coplanar = [0., 1., 2., 3.]
+        .map(|i| Point::from([Scalar::PI / 2. * i]))
+        .into_iter()
+        .all(|t| {
+            let point = curve_b.point_curve_to_model(&t);
+            normal.dot(&(point - a.center)).abs() <= tolerance
+        });
+
+    if is_coplanar {
+        let is_circle = |conic: &Ellipse| {
+            conic.major_radius() - conic.minor_radius() <= tolerance
+        };
+
+        if is_circle(&a) && is_circle(&b) {
+            return circle_circle(curves, [a, b], tolerance);
+        }
+
+        return sampled(curve_a, curve_b, tolerance);
+    }
+
+    // The conics are not in the same plane. Any intersection must be on the
+    // line where the two planes intersect, which means we can compute the
+    // intersections of both conics with that line, and look for those that
+    // are shared.
+    let plane = |conic: &Ellipse| {
+        Surface::SweptCurve(SweptCurve {
+            curve: Curve::Line(Line {
+                origin: conic.center,
+                direction: conic.a,
+            }),
+            path: conic.b,
+        })
+    };
+    let line = match surface_surface::surface(&plane(&a), &plane(&b)) {
+        Some(Curve::Line(line)) => line,
+        _ => return Vec::new(),
+    };
+
+    let with_a = line_conic(&line, curve_a, &a, tolerance);
+    let with_b = line_conic(&line, curve_b, &b, tolerance);
+
+    let mut intersections = Vec::new();
+    for intersection_a in &with_a {
+        for intersection_b in &with_b {
+            if let (
+                CurveCurveIntersection::Point {
+                    on_b: on_a,
+                    point,
+                    tangent: tangent_a,
+                    ..
+                },
+                CurveCurveIntersection::Point {
+                    on_b,
+                    point: point_b,
+                    tangent: tangent_b,
+                    ..
+                },
+            ) = (intersection_a, intersection_b)
+            {
+                if (*point - *point_b).magnitude() > tolerance {
+                    continue;
+                }
+
+                // If both conics touch the line, they touch each other.
+                // Otherwise at least one of them crosses the line, and with it
+                // the other conic.
+                intersections.push(CurveCurveIntersection::Point {
+                    on_a: *on_a,
+                    on_b: *on_b,
+                    point: *point,
+                    tangent: *tangent_a && *tangent_b,
+                });
+            }
+        }
+    }
+
+    intersections
+}
+
+/// Check whether two circles or ellipses coincide
+fn conic_coincidence(
+    [a, b]: [&Curve; 2],
+    tolerance: Scalar,
+) -> Option<CurveCurveIntersection> {
+    let on_b = |i: f64| Point::from([Scalar::PI / 4. * i]);
+
+    // A conic is defined by five points in the same plane. If eight points of
+    // one conic are on the other, they coincide.
+    for i in 0..8 {
+        let point = b.point_curve_to_model(&on_b(i as f64));
+        let on_a = a.point_model_to_curve(&point);
+
+        if (point - a.point_curve_to_model(&on_a)).magnitude() > tolerance {
+            return None;
+        }
+    }
+
+    let period = Scalar::PI * 2.;
+
+    let [start, quarter] = [0., 2.].map(|i| {
+        let point = b.point_curve_to_model(&on_b(i));
+        a.point_model_to_curve(&point).t
+    });
+    let same_direction = (quarter - start + period) % period < Scalar::PI;
+
+    let end = if same_direction {
+        start + period
+    } else {
+        start - period
+    };
+
+    Some(CurveCurveIntersection::Coincident {
+        on_a: [start, end].map(|t| Point::from([t])),
+        on_b: [Scalar::ZERO, period].map(|t| Point::from([t])),
+    })
+}
+
+/// Intersect two circles that lie in the same plane
+fn circle_circle(
+    [curve_a, curve_b]: [&Curve; 2],
+    [a, b]: [Ellipse; 2],
+    tolerance: Scalar,
+) -> Vec<CurveCurveIntersection> {
+    let radius_a = a.major_radius();
+    let radius_b = b.major_radius();
+
+    let center_to_center = b.center - a.center;
+    let distance = center_to_center.magnitude();
+
+    // The circles are concentric, but don't coincide. Otherwise we wouldn't
+    // have gotten here.
+    if distance <= tolerance {
+        return Vec::new();
+    }
+
+    if distance > radius_a + radius_b + tolerance
+        || distance < (radius_a - radius_b).abs() - tolerance
+    {
+        return Vec::new();
+    }
+
+    let normal = a.a.cross(&a.b).normalize();
+    let along = center_to_center / distance;
+    let across = normal.cross(&along);
+
+    // The intersections are on the line that connects the two points. That
+    // line intersects the line between the centers at `x`.
+    let x = (distance * distance + radius_a * radius_a - radius_b * radius_b)
+        / (distance * 2.);
+    let h = (radius_a * radius_a - x * x).max(Scalar::ZERO).sqrt();
+
+    let foot = a.center + along * x;
+
+    let to_intersection = |point, tangent| CurveCurveIntersection::Point {
+        on_a: curve_a.point_model_to_curve(&point),
+        on_b: curve_b.point_model_to_curve(&point),
+        point,
+        tangent,
+    };
+
+    if h * 2. <= tolerance {
+        return vec![to_intersection(foot, true)];
+    }
+
+    vec![
+        to_intersection(foot + across * h, false),
+        to_intersection(foot + across * -h, false),
+    ]
+}
+
+/// Intersect two curves by sampling the first one
+///
+/// The first curve must be bounded.
+fn sampled(
+    a: &Curve,
+    b: &Curve,
+    tolerance: Scalar,
+) -> Vec<CurveCurveIntersection> {
+    let range = match a {
+        Curve::Circle(_) | Curve::Ellipse(_) => [Scalar::ZERO, Scalar::PI * 2.],
+        Curve::Nurbs(curve) => curve.range(),
+        Curve::Line(_) => {
+            unreachable!("Can't sample an infinite line")
+        }
+    };
+
+    let point_a = |t: Scalar| a.point_curve_to_model(&Point::from([t]));
+    let project = |point: Point<3>| {
+        let on_b = b.point_model_to_curve(&point);
+        (on_b, b.point_curve_to_model(&on_b))
+    };
+
+    let residual = |t: Scalar| {
+        let point = point_a(t);
+        let (_, on_b) = project(point);
+        point - on_b
+    };
+
+    numeric::search(a, range, residual, tolerance)
+        .into_iter()
+        .map(|found| match found {
+            Found::Point { t, tangent } => {
+                let point = point_a(t);
+                let (on_b, _) = project(point);
+
+                CurveCurveIntersection::Point {
+                    on_a: Point::from([t]),
+                    on_b,
+                    point,
+                    tangent,
+                }
+            }
+            Found::Overlap(range) => {
+                let on_a = range.map(|t| Point::from([t]));
+                let on_b = range.map(|t| project(point_a(t)).0);
+
+                CurveCurveIntersection::Coincident { on_a, on_b }
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_1_SQRT_2, FRAC_PI_2, PI};
+
+    use approx::assert_abs_diff_eq;
+    use fj_math::{Point, Scalar, Vector};
+
+    use crate::{
+        algorithms::Tolerance,
+        geometry::{Circle, Curve, Ellipse, Line, NurbsCurve},
+    };
+
+    use super::{curve_curve, CurveCurveIntersection};
+
+    #[test]
+    fn line_line_crossing() {
+        let a = line([0., 0., 0.], [1., 0., 0.]);
+        let b = line([1., -1., 0.], [0., 2., 0.]);
+
+        let intersections = curve_curve(&a, &b, tolerance());
+        assert_points(&intersections, &[([1., 0.5], false)]);
+        assert_point(&intersections[0], [1., 0., 0.]);
+    }
+
+    #[test]
+    fn line_line_skew() {
+        let a = line([0., 0., 0.], [1., 0., 0.]);
+
+        // Misses `a` by more than the tolerance.
+        let b = line([1., -1., 0.1], [0., 1., 0.]);
+        assert_eq!(curve_curve(&a, &b, tolerance()), []);
+
+        // Misses `a` by less than the tolerance.
+        let b = line([1., -1., 0.0001], [0., 1., 0.]);
+        assert_eq!(curve_curve(&a, &b, tolerance()).len(), 1);
+    }
+
+    #[test]
+    fn line_line_parallel() {
+        let a = line([0., 0., 0.], [1., 0., 0.]);
+        let b = line([0., 1., 0.], [1., 0., 0.]);
+
+        assert_eq!(curve_curve(&a, &b, tolerance()), []);
+    }
+
+    #[test]
+    fn line_line_coincident() {
+        let a = line([0., 0., 0.], [1., 0., 0.]);
+        let b = line([3., 0., 0.], [-2., 0., 0.]);
+
+        let intersections = curve_curve(&a, &b, tolerance());
+        assert_ranges(&intersections, &[([0., 1.], [1.5, 1.])]);
+    }
+
+    #[test]
+    fn line_circle_crossing() {
+        let a = line([-2., 0., 0.], [1., 0., 0.]);
+        let b = circle([0., 0., 0.], 1.);
+
+        let intersections = curve_curve(&a, &b, tolerance());
+        assert_points(&intersections, &[([1., PI], false), ([3., 0.], false)]);
+
+        // The same, with the curves swapped.
+        let intersections = curve_curve(&b, &a, tolerance());
+        assert_points(&intersections, &[([0., 3.], false), ([PI, 1.], false)]);
+    }
+
+    #[test]
+    fn line_circle_tangent() {
+        let b = circle([0., 0., 0.], 1.);
+
+        // Touches the circle exactly.
+        let a = line([0., 1., 0.], [1., 0., 0.]);
+        assert_points(
+            &curve_curve(&a, &b, tolerance()),
+            &[([0., FRAC_PI_2], true)],
+        );
+
+        // Misses the circle by less than the tolerance.
+        let a = line([0., 1.0005, 0.], [1., 0., 0.]);
+        assert_points(
+            &curve_curve(&a, &b, tolerance()),
+            &[([0., FRAC_PI_2], true)],
+        );
+
+        // Crosses the circle, but the intersections are closer together than
+        // the tolerance.
+        let a = line([0., 0.9999999, 0.], [1., 0., 0.]);
+        assert_points(
+            &curve_curve(&a, &b, tolerance()),
+            &[([0., FRAC_PI_2], true)],
+        );
+
+        // Misses the circle by more than the tolerance.
+        let a = line([0., 1.1, 0.], [1., 0., 0.]);
+        assert_eq!(curve_curve(&a, &b, tolerance()), []);
+    }
+
+    #[test]
+    fn line_circle_piercing() {
+        let b = circle([0., 0., 0.], 1.);
+
+        let a = line([0., 1., -1.], [0., 0., 1.]);
+        assert_points(
+            &curve_curve(&a, &b, tolerance()),
+            &[([1., FRAC_PI_2], false)],
+        );
+
+        let a = line([0., 0., -1.], [0., 0., 1.]);
+        assert_eq!(curve_curve(&a, &b, tolerance()), []);
+    }
+
+    #[test]
+    fn line_ellipse() {
+        let b = ellipse([0., 0., 0.], [2., 1.]);
+
+        let a = line([0., -2., 0.], [0., 1., 0.]);
+        assert_points(
+            &curve_curve(&a, &b, tolerance()),
+            &[([1., PI * 1.5], false), ([3., FRAC_PI_2], false)],
+        );
+
+        let a = line([2., -2., 0.], [0., 1., 0.]);
+        assert_points(&curve_curve(&a, &b, tolerance()), &[([2., 0.], true)]);
+    }
+
+    #[test]
+    fn circle_circle_crossing() {
+        let a = circle([0., 0., 0.], 1.);
+        let b = circle([1., 0., 0.], 1.);
+
+        let intersections = curve_curve(&a, &b, tolerance());
+        assert_points(
+            &intersections,
+            &[
+                ([PI / 3., PI / 3. * 2.], false),
+                ([PI / 3. * 5., PI / 3. * 4.], false),
+            ],
+        );
+    }
+
+    #[test]
+    fn circle_circle_tangent() {
+        let a = circle([0., 0., 0.], 1.);
+
+        // Touching from the outside.
+        let b = circle([3., 0., 0.], 2.);
+        assert_points(&curve_curve(&a, &b, tolerance()), &[([0., PI], true)]);
+
+        // Touching from the inside.
+        let b = circle([1., 0., 0.], 2.);
+        assert_points(&curve_curve(&a, &b, tolerance()), &[([PI, PI], true)]);
+
+        // Close enough to count as touching.
+        let b = circle([3.0005, 0., 0.], 2.);
+        assert_points(&curve_curve(&a, &b, tolerance()), &[([0., PI], true)]);
+
+        // Too far away.
+        let b = circle([3.1, 0., 0.], 2.);
+        assert_eq!(curve_curve(&a, &b, tolerance()), []);
+    }
+
+    #[test]
+    fn circle_circle_concentric() {
+        let a = circle([0., 0., 0.], 1.);
+        let b = circle([0., 0., 0.], 2.);
+
+        assert_eq!(curve_curve(&a, &b, tolerance()), []);
+    }
+
+    #[test]
+    fn circle_circle_coincident() {
+        let a = circle([0., 0., 0.], 1.);
+
+        let b = a.clone();
+        assert_ranges(
+            &curve_curve(&a, &b, tolerance()),
+            &[([0., PI * 2.], [0., PI * 2.])],
+        );
+
+        let b = a.clone().reverse();
+        assert_ranges(
+            &curve_curve(&a, &b, tolerance()),
+            &[([0., -PI * 2.], [0., PI * 2.])],
+        );
+    }
+
+    #[test]
+    fn circle_circle_different_planes() {
+        let a = circle([0., 0., 0.], 1.);
+
+        // A circle in the xz-plane, crossing the xy-plane at the x-axis.
+        let b = Curve::Circle(Circle {
+            center: Point::from([2., 0., 0.]),
+            a: Vector::from([1., 0., 0.]),
+            b: Vector::from([0., 0., 1.]),
+        });
+        assert_points(&curve_curve(&a, &b, tolerance()), &[([0., PI], false)]);
+
+        // A circle in the xz-plane, touching the xy-plane at the point where
+        // it meets `a`.
+        let b = Curve::Circle(Circle {
+            center: Point::from([1., 0., 1.]),
+            a: Vector::from([1., 0., 0.]),
+            b: Vector::from([0., 0., 1.]),
+        });
+        assert_points(
+            &curve_curve(&a, &b, tolerance()),
+            &[([0., PI * 1.5], false)],
+        );
+
+        // A circle in the yz-plane, touching `a` where both curves are tangent
+        // to the y-axis.
+        let b = Curve::Circle(Circle {
+            center: Point::from([1., 0., 1.]),
+            a: Vector::from([0., 1., 0.]),
+            b: Vector::from([0., 0., 1.]),
+        });
+        assert_points(
+            &curve_curve(&a, &b, tolerance()),
+            &[([0., PI * 1.5], true)],
+        );
+    }
+
+    #[test]
+    fn circle_ellipse() {
+        let a = ellipse([0., 0., 0.], [2., 1.]);
+
+        let b = circle([0., 0., 0.], 1.5);
+        let intersections = curve_curve(&a, &b, tolerance());
+        assert_eq!(intersections.len(), 4);
+        for intersection in &intersections {
+            assert!(matches!(
+                intersection,
+                CurveCurveIntersection::Point { tangent: false, .. }
+            ));
+        }
+
+        let b = circle([0., 0., 0.], 1.);
+        assert_points(
+            &curve_curve(&a, &b, tolerance()),
+            &[([FRAC_PI_2, FRAC_PI_2], true), ([PI * 1.5, PI * 1.5], true)],
+        );
+    }
+
+    #[test]
+    fn ellipse_ellipse_coincident() {
+        let a = ellipse([0., 0., 0.], [2., 1.]);
+        let b = Curve::Ellipse(Ellipse {
+            center: Point::origin(),
+            a: Vector::from([-2., 0., 0.]),
+            b: Vector::from([0., -1., 0.]),
+        });
+
+        assert_ranges(
+            &curve_curve(&a, &b, tolerance()),
+            &[([PI, PI * 3.], [0., PI * 2.])],
+        );
+    }
+
+    #[test]
+    fn nurbs_line() {
+        let a = quarter_circle();
+        let b = line([0., 0., 0.], [1., 1., 0.]);
+
+        let intersections = curve_curve(&a, &b, tolerance());
+        assert_eq!(intersections.len(), 1);
+        assert_point(&intersections[0], [FRAC_1_SQRT_2, FRAC_1_SQRT_2, 0.]);
+    }
+
+    #[test]
+    fn nurbs_circle_tangent() {
+        let a = quarter_circle();
+        let b = circle([2. * FRAC_1_SQRT_2, 2. * FRAC_1_SQRT_2, 0.], 1.);
+
+        let intersections = curve_curve(&a, &b, tolerance());
+        assert_eq!(intersections.len(), 1);
+        assert_point(&intersections[0], [FRAC_1_SQRT_2, FRAC_1_SQRT_2, 0.]);
+        assert!(matches!(
+            intersections[0],
+            CurveCurveIntersection::Point { tangent: true, .. }
+        ));
+    }
+
+    #[test]
+    fn nurbs_nurbs_coincident() {
+        let a = quarter_circle();
+        let b = quarter_circle().reverse();
+
+        assert_ranges(
+            &curve_curve(&a, &b, tolerance()),
+            &[([0., 1.], [1., 0.])],
+        );
+    }
+
+    #[test]
+    fn nurbs_circle_partial_overlap() {
+        let a = quarter_circle();
+        let b = Curve::Circle(Circle {
+            center: Point::origin(),
+            a: Vector::from([0., 1., 0.]),
+            b: Vector::from([-1., 0., 0.]),
+        });
+
+        // The quarter circle overlaps the unit circle completely, but the
+        // unit circle starts in the middle of it.
+        let intersections = curve_curve(&a, &b, tolerance());
+        assert_eq!(intersections.len(), 1);
+        assert!(matches!(
+            intersections[0],
+            CurveCurveIntersection::Coincident { .. }
+        ));
+    }
+
+    fn tolerance() -> Tolerance {
+        Tolerance::from_scalar(0.001).unwrap()
+    }
+
+    fn line(origin: [f64; 3], direction: [f64; 3]) -> Curve {
+        Curve::Line(Line {
+            origin: Point::from(origin),
+            direction: Vector::from(direction),
+        })
+    }
+
+    fn circle(center: [f64; 3], radius: f64) -> Curve {
+        Curve::Circle(Circle {
+            center: Point::from(center),
+            a: Vector::from([radius, 0., 0.]),
+            b: Vector::from([0., radius, 0.]),
+        })
+    }
+
+    fn ellipse(center: [f64; 3], [a, b]: [f64; 2]) -> Curve {
+        Curve::Ellipse(Ellipse {
+            center: Point::from(center),
+            a: Vector::from([a, 0., 0.]),
+            b: Vector::from([0., b, 0.]),
+        })
+    }
+
+    fn quarter_circle() -> Curve {
+        Curve::Nurbs(NurbsCurve {
+            degree: 2,
+            control_points: vec![
+                Point::from([1., 0., 0.]),
+                Point::from([1., 1., 0.]),
+                Point::from([0., 1., 0.]),
+            ],
+            weights: vec![
+                Scalar::ONE,
+                Scalar::from(FRAC_1_SQRT_2),
+                Scalar::ONE,
+            ],
+            knots: [0., 0., 0., 1., 1., 1.].map(Scalar::from).to_vec(),
+        })
+    }
+
+    fn assert_points(
+        intersections: &[CurveCurveIntersection],
+        expected: &[([f64; 2], bool)],
+    ) {
+        let points: Vec<_> = intersections
+            .iter()
+            .map(|intersection| match intersection {
+                CurveCurveIntersection::Point {
+                    on_a,
+                    on_b,
+                    tangent,
+                    ..
+                } => ([round(on_a.t), round(on_b.t)], *tangent),
+                CurveCurveIntersection::Coincident { .. } => {
+                    panic!("Unexpected intersection: {:?}", intersection)
+                }
+            })
+            .collect();
+        let expected: Vec<_> = expected
+            .iter()
+            .map(|&(on, tangent)| (on.map(Scalar::from).map(round), tangent))
+            .collect();
+
+        assert_eq!(points, expected);
+    }
+
+    fn assert_ranges(
+        intersections: &[CurveCurveIntersection],
+        expected: &[([f64; 2], [f64; 2])],
+    ) {
+        let ranges: Vec<_> = intersections
+            .iter()
+            .map(|intersection| match intersection {
+                CurveCurveIntersection::Coincident { on_a, on_b } => {
+                    (on_a.map(|t| round(t.t)), on_b.map(|t| round(t.t)))
+                }
+                CurveCurveIntersection::Point { .. } => {
+                    panic!("Unexpected intersection: {:?}", intersection)
+                }
+            })
+            .collect();
+        let expected: Vec<_> = expected
+            .iter()
+            .map(|(on_a, on_b)| {
+                let round =
+                    |range: [f64; 2]| range.map(Scalar::from).map(round);
+                (round(*on_a), round(*on_b))
+            })
+            .collect();
+
+        assert_eq!(ranges, expected);
+    }
+
+    fn assert_point(intersection: &CurveCurveIntersection, expected: [f64; 3]) {
+        match intersection {
+            CurveCurveIntersection::Point { point, .. } => {
+                assert_abs_diff_eq!(
+                    *point,
+                    Point::from(expected),
+                    epsilon = 1e-6
+                );
+            }
+            CurveCurveIntersection::Coincident { .. } => {
+                panic!("Unexpected intersection: {:?}", intersection)
+            }
+        }
+    }
+
+    /// Round a curve coordinate, to make it comparable
+    fn round(t: Scalar) -> f64 {
+        // Adding zero turns negative zero into positive zero.
+        (t.into_f64() * 1e6).round() / 1e6 + 0.
+    }
+}
@@ -0,0 +1,777 @@
+//! Intersection between a curve and a surface
+
+use fj_math::{Point, Scalar, Vector};
+
+use crate::{
+    algorithms::Tolerance,
+    geometry::{Cone, Curve, Cylinder, Ellipse, Line, Surface, SweptCurve},
+};
+
+use super::{
+    bounding_sphere, bounding_sphere_of_points, conic,
+    numeric::{self, Found},
+    quadratic::{self, Roots},
+    surface_surface,
+};
+
+/// An intersection between a curve and a surface
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CurveSurfaceIntersection {
+    /// The curve meets the surface at a single point
+    Point {
+        /// The curve coordinate of the point
+        on_curve: Point<1>,
+
+        /// The surface coordinates of the point
+        on_surface: Point<2>,
+
+        /// The point in model coordinates
+        point: Point<3>,
+
+        /// Whether the curve touches the surface at the point, instead of
+        /// crossing it
+        tangent: bool,
+    },
+
+    /// The curve lies within the surface
+    ///
+    /// `on_curve` is the range of curve coordinates, in which the curve lies
+    /// within the surface. If a line lies within the surface completely, the
+    /// range is between the curve coordinates `0` and `1`.
+    Coincident {
+        /// The range on the curve
+        on_curve: [Point<1>; 2],
+    },
+}
+
+impl CurveSurfaceIntersection {
+    /// Access the first curve coordinate of the intersection
+    fn start_on_curve(&self) -> Point<1> {
+        match self {
+            Self::Point { on_curve, .. } => *on_curve,
+            Self::Coincident { on_curve } => on_curve[0],
+        }
+    }
+}
+
+/// Compute the intersections between a curve and a surface
+///
+/// Returns the intersections, sorted by their curve coordinates.
+///
+/// Intersections of lines and planes, circles and planes, ellipses and planes,
+/// as well as lines and cylinders or cones, are computed analytically. All
+/// other cases are handled by sampling the curve and refining the places where
+/// it comes close to the surface. This can miss intersections that are very
+/// close together, relative to the size of the curve.
+pub fn curve_surface(
+    curve: &Curve,
+    surface: &Surface,
+    tolerance: Tolerance,
+) -> Vec<CurveSurfaceIntersection> {
+    let tolerance = tolerance.inner();
+
+    let mut intersections = match (curve, surface) {
+        (Curve::Line(line), surface) => line_surface(line, surface, tolerance),
+        (curve, surface) => match surface_surface::plane(surface) {
+            Some(plane) => match conic(curve) {
+                Some(conic) => {
+                    conic_plane(curve, &conic, plane, surface, tolerance)
+                }
+                None => sampled(curve, None, surface, tolerance),
+            },
+            None => sampled(curve, None, surface, tolerance),
+        },
+    };
+
+    intersections.sort_by_key(|intersection| intersection.start_on_curve());
+    intersections
+}
+
+fn line_surface(
+    line: &Line,
+    surface: &Surface,
+    tolerance: Scalar,
+) -> Vec<CurveSurfaceIntersection> {
+    if let Some((normal, distance)) = surface_surface::plane(surface) {
+        let coefficients = [
+            Scalar::ZERO,
+            normal.dot(&line.direction),
+            normal.dot(&line.origin.coords) - distance,
+        ];
+        return line_quadric(line, coefficients, surface, tolerance);
+    }
+
+    match surface {
+        Surface::Cone(cone) => line_cone(line, cone, surface, tolerance),
+        Surface::Cylinder(cylinder) => {
+            line_cylinder(line, cylinder, surface, tolerance)
+        }
+        Surface::SweptCurve(swept) => {
+            line_swept_curve(line, swept, surface, tolerance)
+        }
+        Surface::Nurbs(nurbs) => {
+            let (center, radius) = bounding_sphere_of_points(
+                nurbs.control_points.iter().flatten().copied(),
+            );
+            let range = line_range(line, center, radius + tolerance, None);
+            sampled_line(line, range, surface, tolerance)
+        }
+        Surface::Sphere(sphere) => {
+            let radius = sphere.radius() + tolerance;
+            let range = line_range(line, sphere.center, radius, None);
+            sampled_line(line, range, surface, tolerance)
+        }
+        Surface::Torus(torus) => {
+            let radius =
+                torus.major_radius() + torus.minor_radius() + tolerance;
+            let range = line_range(line, torus.center, radius, None);
+            sampled_line(line, range, surface, tolerance)
+        }
+    }
+}
+
+fn line_cylinder(
+    line: &Line,
+    cylinder: &Cylinder,
+    surface: &Surface,
+    tolerance: Scalar,
+) -> Vec<CurveSurfaceIntersection> {
+    let axis = cylinder.axis.normalize();
+    let perpendicular = |v: Vector<3>| v - axis * v.dot(&axis);
+
+    let r = perpendicular(line.origin - cylinder.center);
+    let d = perpendicular(line.direction);
+
+    let radius = cylinder.radius();
+    let coefficients = [d.dot(&d), r.dot(&d) * 2., r.dot(&r) - radius * radius];
+
+    line_quadric(line, coefficients, surface, tolerance)
+}
+
+fn line_cone(
+    line: &Line,
+    cone: &Cone,
+    surface: &Surface,
+    tolerance: Scalar,
+) -> Vec<CurveSurfaceIntersection> {
+    let axis = cone.axis.normalize();
+
+    // The ratio of the radius to the distance from the apex.
+    let slope = cone.a.magnitude() / cone.axis.magnitude();
+    let k = slope * slope;
+
+    let r = line.origin - cone.apex;
+    let d = line.direction;
+
+    let r_along = r.dot(&axis);
+    let d_along = d.dot(&axis);
+    let r_across = r - axis * r_along;
+    let d_across = d - axis * d_along;
+
+    let coefficients = [
+        d_across.dot(&d_across) - k * d_along * d_along,
+        (r_across.dot(&d_across) - k * r_along * d_along) * 2.,
+        r_across.dot(&r_across) - k * r_along * r_along,
+    ];
+
+    line_quadric(line, coefficients, surface, tolerance)
+}
+
+fn line_swept_curve(
+    line: &Line,
+    swept: &SweptCurve,
+    surface: &Surface,
+    tolerance: Scalar,
+) -> Vec<CurveSurfaceIntersection> {
+    let (center, radius) = match bounding_sphere(&swept.curve) {
+        Some(bounds) => bounds,
+        None => {
+            unreachable!("A curve swept from a line is a plane")
+        }
+    };
+
+    let cross = line.direction.cross(&swept.path).magnitude();
+    let is_parallel = cross
+        <= line.direction.magnitude() * swept.path.magnitude() * f64::EPSILON;
+
+    if is_parallel {
+        // The line is parallel to the path, which means it either lies within
+        // the surface completely, or it doesn't intersect it at all.
+        let is_on_surface =
+            distance_to_surface(surface, &line.origin) <= tolerance;
+
+        if is_on_surface {
+            return vec![CurveSurfaceIntersection::Coincident {
+                on_curve: [Scalar::ZERO, Scalar::ONE].map(|t| Point::from([t])),
+            }];
+        }
+
+        return Vec::new();
+    }
+
+    let range = line_range(line, center, radius + tolerance, Some(swept.path));
+    sampled_line(line, range, surface, tolerance)
+}
+
+/// Intersect a line with a surface, using a quadratic equation
+///
+/// The roots of the quadratic equation defined by `coefficients` must be the
+/// curve coordinates at which the line intersects the surface.
+fn line_quadric(
+    line: &Line,
+    coefficients: [Scalar; 3],
+    surface: &Surface,
+    tolerance: Scalar,
+) -> Vec<CurveSurfaceIntersection> {
+    let point = |t: Scalar| line.point_curve_to_model(&Point::from([t]));
+    let distance = |t: Scalar| distance_to_surface(surface, &point(t));
+
+    match quadratic::roots(coefficients, point, distance, tolerance) {
+        Roots::All => vec![CurveSurfaceIntersection::Coincident {
+            on_curve: [Scalar::ZERO, Scalar::ONE].map(|t| Point::from([t])),
+        }],
+        Roots::Points(roots) => roots
+            .into_iter()
+            .map(|(t, tangent)| {
+                let point = point(t);
+
+                CurveSurfaceIntersection::Point {
+                    on_curve: Point::from([t]),
+                    on_surface: surface.point_model_to_surface(point).native(),
+                    point,
+                    tangent,
+                }
+            })
+            .collect(),
+    }
+}
+
+/// Intersect a circle or an ellipse with a plane
+///
+/// `curve` is the original curve, `conic` the same curve as an ellipse. `plane`
+/// is the plane in constant-normal form, `surface` the original surface.
+fn conic_plane(
+    curve: &Curve,
+    conic: &Ellipse,
+    (normal, distance): (Vector<3>, Scalar),
+    surface: &Surface,
+    tolerance: Scalar,
+) -> Vec<CurveSurfaceIntersection> {
+    // The signed distance of the point at the curve coordinate `t` from the
+    // plane is `k + alpha * cos(t) + beta * sin(t)`, which is the same as
+    // `k + amplitude * cos(t - phase)`.
+    let k = normal.dot(&conic.center.coords) - distance;
+    let alpha = normal.dot(&conic.a);
+    let beta = normal.dot(&conic.b);
+
+    let amplitude = (alpha * alpha + beta * beta).sqrt();
+    let phase = Scalar::atan2(beta, alpha);
+
+    if k.abs() + amplitude <= tolerance {
+        return vec![CurveSurfaceIntersection::Coincident {
+            on_curve: [Scalar::ZERO, Scalar::PI * 2.].map(|t| Point::from([t])),
+        }];
+    }
+    if amplitude == Scalar::ZERO {
+        return Vec::new();
+    }
+
+    let point = |t: Scalar| curve.point_curve_to_model(&Point::from([t]));
+    let to_intersection = |t: Scalar, tangent| {
+        let period = Scalar::PI * 2.;
+        let t = (t % period + period) % period;
+        let point = point(t);
+
+        CurveSurfaceIntersection::Point {
+            on_curve: Point::from([t]),
+            on_surface: surface.point_model_to_surface(point).native(),
+            point,
+            tangent,
+        }
+    };
+
+    let cos = -k / amplitude;
+
+    if cos.abs() > Scalar::ONE {
+        // The conic doesn't reach the plane. But if it comes close enough, it
+        // touches it.
+        let gap = k.abs() - amplitude;
+        if gap > tolerance {
+            return Vec::new();
+        }
+
+        let t = if cos > Scalar::ZERO {
+            phase
+        } else {
+            phase + Scalar::PI
+        };
+        return vec![to_intersection(t, true)];
+    }
+
+    let delta = cos.acos();
+    let [t1, t2] = [phase - delta, phase + delta];
+
+    if (point(t2) - point(t1)).magnitude() <= tolerance {
+        let t = if delta < Scalar::PI / 2. {
+            phase
+        } else {
+            phase + Scalar::PI
+        };
+        return vec![to_intersection(t, true)];
+    }
+
+    vec![to_intersection(t1, false), to_intersection(t2, false)]
+}
+
+/// Compute the range in which a line passes through a sphere or cylinder
+///
+/// If `axis` is `None`, the line is intersected with the sphere defined by
+/// `center` and `radius`. Otherwise the line is intersected with the infinite
+/// cylinder around `axis`.
+fn line_range(
+    line: &Line,
+    center: Point<3>,
+    radius: Scalar,
+    axis: Option<Vector<3>>,
+) -> Option<[Scalar; 2]> {
+    let perpendicular = |v: Vector<3>| match axis {
+        Some(axis) => {
+            let axis = axis.normalize();
+            v - axis * v.dot(&axis)
+        }
+        None => v,
+    };
+
+    let r = perpendicular(line.origin - center);
+    let d = perpendicular(line.direction);
+
+    let a = d.dot(&d);
+    if a == Scalar::ZERO {
+        return None;
+    }
+
+    let t = -r.dot(&d) / a;
+    let closest = r + d * t;
+
+    let half_width_squared = (radius * radius - closest.dot(&closest)) / a;
+    if half_width_squared < Scalar::ZERO {
+        return None;
+    }
+
+    let half_width = half_width_squared.sqrt();
+    Some([t - half_width, t + half_width])
+}
+
+/// Intersect a line with a surface by sampling it within the given range
+fn sampled_line(
+    line: &Line,
+    range: Option<[Scalar; 2]>,
+    surface: &Surface,
+    tolerance: Scalar,
+) -> Vec<CurveSurfaceIntersection> {
+    match range {
+        Some(range) => {
+            sampled(&Curve::Line(*line), Some(range), surface, tolerance)
+        }
+        None => Vec::new(),
+    }
+}
+
+/// Intersect a curve with a surface by sampling the curve
+///
+/// If no range is provided, the curve must be bounded.
+fn sampled(
+    curve: &Curve,
+    range: Option<[Scalar; 2]>,
+    surface: &Surface,
+    tolerance: Scalar,
+) -> Vec<CurveSurfaceIntersection> {
+    let range = range.unwrap_or_else(|| match curve {
+        Curve::Circle(_) | Curve::Ellipse(_) => [Scalar::ZERO, Scalar::PI * 2.],
+        Curve::Nurbs(curve) => curve.range(),
+        Curve::Line(_) => {
+            unreachable!("Can't sample an infinite line")
+        }
+    });
+
+    let point = |t: Scalar| curve.point_curve_to_model(&Point::from([t]));
+    let residual = |t: Scalar| {
+        let point = point(t);
+        let on_surface = surface.point_model_to_surface(point).native();
+        point - surface.point_surface_to_model(&on_surface)
+    };
+
+    numeric::search(curve, range, residual, tolerance)
+        .into_iter()
+        .map(|found| match found {
+            Found::Point { t, tangent } => {
+                let point = point(t);
+
+                CurveSurfaceIntersection::Point {
+                    on_curve: Point::from([t]),
+                    on_surface: surface.point_model_to_surface(point).native(),
+                    point,
+                    tangent,
+                }
+            }
+            Found::Overlap(range) => CurveSurfaceIntersection::Coincident {
+                on_curve: range.map(|t| Point::from([t])),
+            },
+        })
+        .collect()
+}
+
+/// Compute the distance of a point from a surface
+fn distance_to_surface(surface: &Surface, point: &Point<3>) -> Scalar {
+    let on_surface = surface.point_model_to_surface(*point).native();
+    (point - surface.point_surface_to_model(&on_surface)).magnitude()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_1_SQRT_2, PI};
+
+    use approx::assert_abs_diff_eq;
+    use fj_math::{Point, Scalar, Vector};
+
+    use crate::{
+        algorithms::Tolerance,
+        geometry::{
+            Circle, Cone, Curve, Cylinder, Line, NurbsCurve, Sphere, Surface,
+            SweptCurve,
+        },
+    };
+
+    use super::{curve_surface, CurveSurfaceIntersection};
+
+    #[test]
+    fn line_plane() {
+        let surface = Surface::xy_plane();
+
+        let curve = line([0., 0., 1.], [0., 0., -2.]);
+        let intersections = curve_surface(&curve, &surface, tolerance());
+        assert_points(&intersections, &[(0.5, false)]);
+        assert_point(&intersections[0], [0., 0., 0.]);
+
+        // Parallel to the plane.
+        let curve = line([0., 0., 1.], [1., 0., 0.]);
+        assert_eq!(curve_surface(&curve, &surface, tolerance()), []);
+
+        // Within the plane.
+        let curve = line([0., 0., 0.], [1., 0., 0.]);
+        assert_ranges(
+            &curve_surface(&curve, &surface, tolerance()),
+            &[[0., 1.]],
+        );
+    }
+
+    #[test]
+    fn circle_plane() {
+        let curve = circle([0., 0., 0.], 1.);
+
+        // Crossing the plane.
+        let surface = Surface::xz_plane();
+        assert_points(
+            &curve_surface(&curve, &surface, tolerance()),
+            &[(0., false), (PI, false)],
+        );
+
+        // Touching the plane.
+        let surface = plane_at_x(1.);
+        assert_points(
+            &curve_surface(&curve, &surface, tolerance()),
+            &[(0., true)],
+        );
+        let surface = plane_at_x(-1.0005);
+        assert_points(
+            &curve_surface(&curve, &surface, tolerance()),
+            &[(PI, true)],
+        );
+
+        // Missing the plane.
+        let surface = plane_at_x(1.1);
+        assert_eq!(curve_surface(&curve, &surface, tolerance()), []);
+
+        // Within the plane.
+        let surface = Surface::xy_plane();
+        assert_ranges(
+            &curve_surface(&curve, &surface, tolerance()),
+            &[[0., PI * 2.]],
+        );
+    }
+
+    #[test]
+    fn line_cylinder() {
+        let surface = Surface::Cylinder(Cylinder {
+            center: Point::origin(),
+            a: Vector::from([1., 0., 0.]),
+            b: Vector::from([0., 1., 0.]),
+            axis: Vector::from([0., 0., 1.]),
+        });
+
+        let curve = line([-2., 0., 1.], [1., 0., 0.]);
+        let intersections = curve_surface(&curve, &surface, tolerance());
+        assert_points(&intersections, &[(1., false), (3., false)]);
+        assert_on_surface(&intersections[0], [PI, 1.]);
+
+        let curve = line([-2., 1., 1.], [1., 0., 0.]);
+        assert_points(
+            &curve_surface(&curve, &surface, tolerance()),
+            &[(2., true)],
+        );
+
+        // Parallel to the axis, on the surface.
+        let curve = line([1., 0., 0.], [0., 0., 1.]);
+        assert_ranges(
+            &curve_surface(&curve, &surface, tolerance()),
+            &[[0., 1.]],
+        );
+
+        // Parallel to the axis, inside of the cylinder.
+        let curve = line([0.5, 0., 0.], [0., 0., 1.]);
+        assert_eq!(curve_surface(&curve, &surface, tolerance()), []);
+    }
+
+    #[test]
+    fn line_cone() {
+        let surface = Surface::Cone(Cone {
+            apex: Point::origin(),
+            a: Vector::from([1., 0., 0.]),
+            b: Vector::from([0., 1., 0.]),
+            axis: Vector::from([0., 0., 1.]),
+        });
+
+        let curve = line([-2., 0., 1.], [1., 0., 0.]);
+        let intersections = curve_surface(&curve, &surface, tolerance());
+        assert_points(&intersections, &[(1., false), (3., false)]);
+        assert_on_surface(&intersections[1], [0., 1.]);
+
+        // Along the cone.
+        let curve = line([0., 0., 0.], [1., 0., 1.]);
+        assert_ranges(
+            &curve_surface(&curve, &surface, tolerance()),
+            &[[0., 1.]],
+        );
+    }
+
+    #[test]
+    fn line_sphere() {
+        let surface = sphere();
+
+        let curve = line([-2., 0., 0.], [1., 0., 0.]);
+        let intersections = curve_surface(&curve, &surface, tolerance());
+        assert_points(&intersections, &[(1., false), (3., false)]);
+        assert_point(&intersections[0], [-1., 0., 0.]);
+
+        let curve = line([-2., 0., 1.], [1., 0., 0.]);
+        let intersections = curve_surface(&curve, &surface, tolerance());
+        assert_points(&intersections, &[(2., true)]);
+        assert_point(&intersections[0], [0., 0., 1.]);
+
+        let curve = line([-2., 0., 1.1], [1., 0., 0.]);
+        assert_eq!(curve_surface(&curve, &surface, tolerance()), []);
+    }
+
+    #[test]
+    fn circle_sphere() {
+        let surface = sphere();
+
+        // The equator.
+        let curve = circle([0., 0., 0.], 1.);
+        assert_ranges(
+            &curve_surface(&curve, &surface, tolerance()),
+            &[[0., PI * 2.]],
+        );
+
+        // Crossing the sphere.
+        let curve = circle([1., 0., 0.], 1.);
+        let intersections = curve_surface(&curve, &surface, tolerance());
+        assert_points(
+            &intersections,
+            &[(PI / 3. * 2., false), (PI / 3. * 4., false)],
+        );
+    }
+
+    #[test]
+    fn line_swept_curve() {
+        let surface = Surface::SweptCurve(SweptCurve {
+            curve: circle([0., 0., 0.], 1.),
+            path: Vector::from([0., 0., 1.]),
+        });
+
+        let curve = line([-2., 0., 0.5], [1., 0., 0.]);
+        let intersections = curve_surface(&curve, &surface, tolerance());
+        assert_points(&intersections, &[(1., false), (3., false)]);
+        assert_on_surface(&intersections[1], [0., 0.5]);
+
+        let curve = line([0., 1., 0.], [0., 0., 1.]);
+        assert_ranges(
+            &curve_surface(&curve, &surface, tolerance()),
+            &[[0., 1.]],
+        );
+    }
+
+    #[test]
+    fn nurbs_plane() {
+        let curve = Curve::Nurbs(NurbsCurve {
+            degree: 2,
+            control_points: vec![
+                Point::from([1., 0., 0.]),
+                Point::from([1., 1., 0.]),
+                Point::from([0., 1., 0.]),
+            ],
+            weights: vec![
+                Scalar::ONE,
+                Scalar::from(FRAC_1_SQRT_2),
+                Scalar::ONE,
+            ],
+            knots: [0., 0., 0., 1., 1., 1.].map(Scalar::from).to_vec(),
+        });
+
+        // A plane through the origin, at 45 degrees to the x-axis.
+        let surface = Surface::SweptCurve(SweptCurve {
+            curve: line([0., 0., 0.], [1., 1., 0.]),
+            path: Vector::from([0., 0., 1.]),
+        });
+
+        let intersections = curve_surface(&curve, &surface, tolerance());
+        assert_points(&intersections, &[(0.5, false)]);
+        assert_point(&intersections[0], [FRAC_1_SQRT_2, FRAC_1_SQRT_2, 0.]);
+
+        // The plane the curve lies in.
+        assert_ranges(
+            &curve_surface(&curve, &Surface::xy_plane(), tolerance()),
+            &[[0., 1.]],
+        );
+
+        // A plane touching the end of the curve.
+        let surface = plane_at_x(1.);
+        let intersections = curve_surface(&curve, &surface, tolerance());
+        assert_eq!(intersections.len(), 1);
+        assert_point(&intersections[0], [1., 0., 0.]);
+    }
+
+    fn tolerance() -> Tolerance {
+        Tolerance::from_scalar(0.001).unwrap()
+    }
+
+    fn line(origin: [f64; 3], direction: [f64; 3]) -> Curve {
+        Curve::Line(Line {
+            origin: Point::from(origin),
+            direction: Vector::from(direction),
+        })
+    }
+
+    fn circle(center: [f64; 3], radius: f64) -> Curve {
+        Curve::Circle(Circle {
+            center: Point::from(center),
+            a: Vector::from([radius, 0., 0.]),
+            b: Vector::from([0., radius, 0.]),
+        })
+    }
+
+    fn sphere() -> Surface {
+        Surface::Sphere(Sphere {
+            center: Point::origin(),
+            a: Vector::from([1., 0., 0.]),
+            b: Vector::from([0., 1., 0.]),
+            c: Vector::from([0., 0., 1.]),
+        })
+    }
+
+    /// A plane that is parallel to the yz-plane
+    fn plane_at_x(x: f64) -> Surface {
+        Surface::SweptCurve(SweptCurve {
+            curve: line([x, 0., 0.], [0., 1., 0.]),
+            path: Vector::from([0., 0., 1.]),
+        })
+    }
+
+    fn assert_points(
+        intersections: &[CurveSurfaceIntersection],
+        expected: &[(f64, bool)],
+    ) {
+        let points: Vec<_> = intersections
+            .iter()
+            .map(|intersection| match intersection {
+                CurveSurfaceIntersection::Point {
+                    on_curve, tangent, ..
+                } => (round(on_curve.t), *tangent),
+                CurveSurfaceIntersection::Coincident { .. } => {
+                    panic!("Unexpected intersection: {:?}", intersection)
+                }
+            })
+            .collect();
+        let expected: Vec<_> = expected
+            .iter()
+            .map(|&(t, tangent)| (round(Scalar::from(t)), tangent))
+            .collect();
+
+        assert_eq!(points, expected);
+    }
+
+    fn assert_ranges(
+        intersections: &[CurveSurfaceIntersection],
+        expected: &[[f64; 2]],
+    ) {
+        let ranges: Vec<_> = intersections
+            .iter()
+            .map(|intersection| match intersection {
+                CurveSurfaceIntersection::Coincident { on_curve } => {
+                    on_curve.map(|t| round(t.t))
+                }
+                CurveSurfaceIntersection::Point { .. } => {
+                    panic!("Unexpected intersection: {:?}", intersection)
+                }
+            })
+            .collect();
+        let expected: Vec<_> = expected
+            .iter()
+            .map(|range| range.map(Scalar::from).map(round))
+            .collect();
+
+        assert_eq!(ranges, expected);
+    }
+
+    fn assert_point(
+        intersection: &CurveSurfaceIntersection,
+        expected: [f64; 3],
+    ) {
+        match intersection {
+            CurveSurfaceIntersection::Point { point, .. } => {
+                assert_abs_diff_eq!(
+                    *point,
+                    Point::from(expected),
+                    epsilon = 1e-6
+                );
+            }
+            CurveSurfaceIntersection::Coincident { .. } => {
+                panic!("Unexpected intersection: {:?}", intersection)
+            }
+        }
+    }
+
+    fn assert_on_surface(
+        intersection: &CurveSurfaceIntersection,
+        expected: [f64; 2],
+    ) {
+        match intersection {
+            CurveSurfaceIntersection::Point { on_surface, .. } => {
+                assert_abs_diff_eq!(
+                    *on_surface,
+                    Point::from(expected),
+                    epsilon = 1e-6
+                );
+            }
+            CurveSurfaceIntersection::Coincident { .. } => {
+                panic!("Unexpected intersection: {:?}", intersection)
+            }
+        }
+    }
+
+    /// Round a curve coordinate, to make it comparable
+    fn round(t: Scalar) -> f64 {
+        // Adding zero turns negative zero into positive zero.
+        (t.into_f64() * 1e6).round() / 1e6 + 0.
+    }
+}
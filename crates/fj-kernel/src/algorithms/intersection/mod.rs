@@ -0,0 +1,69 @@
+//! Intersection algorithms
+//!
+//! There are no dedicated 2D curves. Intersections between curves in 2D are
+//! covered by the intersection of curves that lie in the same plane.
+//!
+//! All algorithms take a [`Tolerance`], which defines the distance below which
+//! two points are considered to be identical. This determines whether curves
+//! touch, or barely miss each other, as well as when two intersection points
+//! merge into a single point of tangency.
+//!
+//! [`Tolerance`]: crate::algorithms::Tolerance
+
+mod curve_curve;
+mod curve_surface;
+mod numeric;
+mod quadratic;
+mod surface_surface;
+
+pub use self::{
+    curve_curve::{curve_curve, CurveCurveIntersection},
+    curve_surface::{curve_surface, CurveSurfaceIntersection},
+    surface_surface::surface,
+};
+
+use fj_math::{Aabb, Point, Scalar};
+
+use crate::geometry::{Curve, Ellipse};
+
+/// Access a circle or an ellipse as an ellipse
+///
+/// Circles are a special case of ellipses, which means this allows for handling
+/// both kinds of curves the same way. Returns `None`, if the curve is neither.
+fn conic(curve: &Curve) -> Option<Ellipse> {
+    match curve {
+        Curve::Circle(circle) => Some(Ellipse {
+            center: circle.center,
+            a: circle.a,
+            b: circle.b,
+        }),
+        Curve::Ellipse(ellipse) => Some(*ellipse),
+        Curve::Line(_) | Curve::Nurbs(_) => None,
+    }
+}
+
+/// Compute a sphere that contains the whole curve
+///
+/// Returns the center and the radius of the sphere, or `None`, if the curve is
+/// not bounded.
+fn bounding_sphere(curve: &Curve) -> Option<(Point<3>, Scalar)> {
+    match curve {
+        Curve::Circle(_) | Curve::Ellipse(_) => {
+            // Can't panic. We just checked that this is a conic.
+            let conic = conic(curve).unwrap();
+            Some((conic.center, conic.major_radius()))
+        }
+        Curve::Line(_) => None,
+        Curve::Nurbs(curve) => {
+            Some(bounding_sphere_of_points(curve.control_points.clone()))
+        }
+    }
+}
+
+/// Compute a sphere that contains all of the provided points
+fn bounding_sphere_of_points(
+    points: impl IntoIterator<Item = Point<3>>,
+) -> (Point<3>, Scalar) {
+    let aabb = Aabb::<3>::from_points(points);
+    (aabb.center(), aabb.size().magnitude() / 2.)
+}
@@ -0,0 +1,261 @@
+//! Sampling-based search for intersections
+//!
+//! This is used for all cases that are not handled analytically. It samples a
+//! curve, looks for the places where the curve comes close to the other object,
+//! and refines those.
+
+use fj_math::{Scalar, Vector};
+
+use crate::geometry::Curve;
+
+/// An intersection, as found by [`search`]
+#[derive(Debug)]
+pub enum Found {
+    /// The curve touches or crosses the other object at a single point
+    Point {
+        /// The curve coordinate of the point
+        t: Scalar,
+
+        /// Whether the curve only touches the other object there
+        tangent: bool,
+    },
+
+    /// The curve lies on the other object within the given range
+    Overlap([Scalar; 2]),
+}
+
+/// Search for the places where a curve comes within `tolerance` of an object
+///
+/// `residual` returns the vector from the object to the point on the curve at
+/// the given curve coordinate. The object is typically another curve or a
+/// surface, and the vector points from the closest point on that object.
+///
+/// The curve is sampled within `range`. Intersections that are closer together
+/// than the distance between two samples might be missed. If the curve is
+/// periodic, `range` must cover exactly one period.
+pub fn search(
+    curve: &Curve,
+    [start, end]: [Scalar; 2],
+    residual: impl Fn(Scalar) -> Vector<3>,
+    tolerance: Scalar,
+) -> Vec<Found> {
+    let distance = |t: Scalar| residual(t).magnitude();
+
+    let periodic = curve.period().is_some();
+    let num_segments = number_of_segments(curve);
+    let step = (end - start) / num_segments as f64;
+
+    // For a periodic curve, the last sample would be identical to the first.
+    // We don't need it, as the indices wrap around.
+    let num_samples = if periodic {
+        num_segments
+    } else {
+        num_segments + 1
+    };
+    let distances: Vec<_> = (0..num_samples)
+        .map(|i| distance(start + step * i as f64))
+        .collect();
+
+    // Curves that touch come close to each other over some distance around the
+    // point of tangency. To distinguish that from an actual overlap, samples
+    // only count as overlapping, if they are much closer than the tolerance.
+    let overlap_tolerance = tolerance * OVERLAP_TOLERANCE;
+
+    if distances
+        .iter()
+        .all(|&distance| distance <= overlap_tolerance)
+    {
+        return vec![Found::Overlap([start, end])];
+    }
+
+    // If the curve is periodic, we start at the sample that is farthest away,
+    // to make sure that no run of close samples wraps around the end of the
+    // samples. Positions used from here on are relative to that sample, and
+    // might extend beyond the end of the range.
+    let first = if periodic {
+        // Can't panic. The curve has at least one sample.
+        (0..num_samples).max_by_key(|&i| distances[i]).unwrap()
+    } else {
+        0
+    };
+    let t_at = |position: usize| start + step * (first + position) as f64;
+    let distance_at =
+        |position: usize| distances[(first + position) % num_samples];
+    let is_close = |position: usize| distance_at(position) <= tolerance;
+
+    // The positions of the samples before and after a position, if any. For a
+    // periodic curve, the position after the last one is the first one again.
+    let before = |position: usize| position.checked_sub(1);
+    let after = |position: usize| {
+        (periodic || position + 1 < num_samples).then(|| position + 1)
+    };
+
+    // Find the point of closest approach within the given range. If that is
+    // within the tolerance, we found an intersection.
+    let refine = |[lower, upper]: [Scalar; 2]| {
+        let t = minimize([lower, upper], distance);
+        if distance(t) > tolerance {
+            return None;
+        }
+
+        // At a crossing, the residual changes its direction. If the curve only
+        // touches the object, it doesn't.
+        let h = step * CLASSIFICATION_STEP;
+        let [a, b] = if periodic {
+            [t - h, t + h]
+        } else {
+            [(t - h).max(start), (t + h).min(end)]
+        };
+        let tangent = residual(a).dot(&residual(b)) > Scalar::ZERO;
+
+        let t = if periodic {
+            let period = end - start;
+            let t = (t - start) % period;
+            let t = if t < Scalar::ZERO { t + period } else { t };
+            start + t
+        } else {
+            t
+        };
+
+        Some(Found::Point { t, tangent })
+    };
+
+    let mut found = Vec::new();
+
+    let mut position = 0;
+    while position < num_samples {
+        let run_start = position;
+        let mut run_end = position;
+        while run_end + 1 < num_samples
+            && is_close(run_start) == is_close(run_end + 1)
+        {
+            run_end += 1;
+        }
+        position = run_end + 1;
+
+        if is_close(run_start) {
+            // A run of samples that are within the tolerance is either an
+            // overlap, or contains a single intersection.
+            let num_overlapping = (run_start..=run_end)
+                .map(distance_at)
+                .fold((0, 0), |(longest, current), distance| {
+                    let current = if distance <= overlap_tolerance {
+                        current + 1
+                    } else {
+                        0
+                    };
+                    (longest.max(current), current)
+                })
+                .0;
+
+            if num_overlapping >= MIN_OVERLAP_SAMPLES {
+                let boundary =
+                    |inside: usize, outside: Option<usize>| match outside {
+                        Some(outside) => {
+                            bisect([t_at(inside), t_at(outside)], |t| {
+                                distance(t) <= tolerance
+                            })
+                        }
+                        None => t_at(inside),
+                    };
+
+                found.push(Found::Overlap([
+                    boundary(run_start, before(run_start)),
+                    boundary(run_end, after(run_end)),
+                ]));
+                continue;
+            }
+
+            let lower = before(run_start).map(t_at).unwrap_or(t_at(run_start));
+            let upper = after(run_end).map(t_at).unwrap_or(t_at(run_end));
+
+            found.extend(refine([lower, upper]));
+            continue;
+        }
+
+        // None of the samples in this run are within the tolerance. But the
+        // curve might still come close between two samples.
+        for position in run_start..=run_end {
+            let is_local_minimum = before(position)
+                .map(|before| distance_at(position) <= distance_at(before))
+                .unwrap_or(true)
+                && after(position)
+                    .map(|after| distance_at(position) < distance_at(after))
+                    .unwrap_or(true);
+            if !is_local_minimum {
+                continue;
+            }
+
+            let lower = before(position).map(t_at).unwrap_or(t_at(position));
+            let upper = after(position).map(t_at).unwrap_or(t_at(position));
+
+            found.extend(refine([lower, upper]));
+        }
+    }
+
+    found
+}
+/// The number of segments that a curve is sampled with
+fn number_of_segments(curve: &Curve) -> usize {
+    match curve {
+        Curve::Circle(_) | Curve::Ellipse(_) | Curve::Line(_) => 64,
+        Curve::Nurbs(curve) => curve.control_points.len().max(4) * 16,
+    }
+}
+
+/// Find the curve coordinate at which `predicate` changes
+///
+/// `predicate` must be `true` at the first curve coordinate, and `false` at the
+/// second.
+fn bisect(
+    [mut inside, mut outside]: [Scalar; 2],
+    predicate: impl Fn(Scalar) -> bool,
+) -> Scalar {
+    for _ in 0..MAX_ITERATIONS {
+        let t = (inside + outside) / 2.;
+
+        if predicate(t) {
+            inside = t;
+        } else {
+            outside = t;
+        }
+    }
+
+    inside
+}
+
+/// Find the minimum of `f` within the given range
+///
+/// Uses golden-section search, which requires `f` to only have one minimum
+/// within the range.
+fn minimize(
+    [mut lower, mut upper]: [Scalar; 2],
+    f: impl Fn(Scalar) -> Scalar,
+) -> Scalar {
+    let ratio = (5f64.sqrt() - 1.) / 2.;
+
+    for _ in 0..MAX_ITERATIONS {
+        let a = upper - (upper - lower) * ratio;
+        let b = lower + (upper - lower) * ratio;
+
+        if f(a) < f(b) {
+            upper = b;
+        } else {
+            lower = a;
+        }
+    }
+
+    (lower + upper) / 2.
+}
+
+/// The number of consecutive samples that must be close to count as an overlap
+const MIN_OVERLAP_SAMPLES: usize = 3;
+
+/// The fraction of the tolerance, below which samples count as overlapping
+const OVERLAP_TOLERANCE: f64 = 1e-6;
+
+/// The fraction of the sample distance used to classify intersections
+const CLASSIFICATION_STEP: f64 = 1e-3;
+
+/// The number of iterations for bisection and minimization
+const MAX_ITERATIONS: usize = 64;
@@ -0,0 +1,73 @@
+//! Roots of quadratic equations, as they come up in analytic intersections
+
+use fj_math::{Point, Scalar};
+
+/// The roots of a quadratic equation, as found by [`roots`]
+#[derive(Debug)]
+pub enum Roots {
+    /// The equation holds for any parameter
+    All,
+
+    /// The equation holds at these parameters
+    ///
+    /// The `bool` indicates whether the root is a point of tangency, meaning a
+    /// double root.
+    Points(Vec<(Scalar, bool)>),
+}
+
+/// Find the roots of `a * t^2 + b * t + c`, within a tolerance
+///
+/// `point` maps a parameter to a point in model coordinates, `distance` maps it
+/// to the distance of that point from the object that is being intersected.
+/// Those are used to apply the tolerance in model space:
+///
+/// - Two roots that are closer than `tolerance` to each other are merged into
+///   a single point of tangency.
+/// - If there is no real root, but the point of closest approach is closer to
+///   the object than `tolerance`, that point becomes a point of tangency.
+/// - If the equation degenerates into a constant, it holds for every parameter,
+///   as long as the points are within `tolerance`.
+pub fn roots(
+    [a, b, c]: [Scalar; 3],
+    point: impl Fn(Scalar) -> Point<3>,
+    distance: impl Fn(Scalar) -> Scalar,
+    tolerance: Scalar,
+) -> Roots {
+    let scale = a.abs().max(b.abs()).max(c.abs());
+    let epsilon = scale * f64::EPSILON;
+
+    if a.abs() <= epsilon {
+        if b.abs() <= epsilon {
+            let all = distance(Scalar::ZERO) <= tolerance
+                && distance(Scalar::ONE) <= tolerance;
+
+            if all {
+                return Roots::All;
+            }
+
+            return Roots::Points(Vec::new());
+        }
+
+        return Roots::Points(vec![(-c / b, false)]);
+    }
+
+    let t = -b / (a * 2.);
+    let discriminant = b * b - a * c * 4.;
+
+    if discriminant < Scalar::ZERO {
+        if distance(t) <= tolerance {
+            return Roots::Points(vec![(t, true)]);
+        }
+
+        return Roots::Points(Vec::new());
+    }
+
+    let half_width = discriminant.sqrt() / (a.abs() * 2.);
+    let [t1, t2] = [t - half_width, t + half_width];
+
+    if (point(t2) - point(t1)).magnitude() <= tolerance {
+        return Roots::Points(vec![(t, true)]);
+    }
+
+    Roots::Points(vec![(t1, false), (t2, false)])
+}
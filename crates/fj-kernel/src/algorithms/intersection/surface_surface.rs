@@ -1,16 +1,19 @@
-//! Intersection algorithms
+//! Intersection between two surfaces
 
 use fj_math::{Point, Scalar, Vector};
 
-use crate::geometry::{Curve, Line, Surface};
+use crate::geometry::{Curve, Line, Surface, SweptCurve};
 
 /// Test intersection between two surfaces
+///
+/// Only intersections between planes are currently supported. Returns `None`,
+/// if the surfaces don't intersect, or if either of them is not a plane.
 pub fn surface(a: &Surface, b: &Surface) -> Option<Curve> {
     // Algorithm from Real-Time Collision Detection by Christer Ericson. See
     // section 5.4.4, Intersection of Two Planes.
 
-    let (a_normal, a_distance) = extract_plane(a);
-    let (b_normal, b_distance) = extract_plane(b);
+    let (a_normal, a_distance) = plane(a)?;
+    let (b_normal, b_distance) = plane(b)?;
 
     let direction = a_normal.cross(&b_normal);
 
@@ -34,20 +37,20 @@ pub fn surface(a: &Surface, b: &Surface) -> Option<Curve> {
     Some(Curve::Line(Line { origin, direction }))
 }
 
-/// Extract a plane in constant-normal form from a `Surface`
-///
-/// Panics, if the given `Surface` is not a plane.
-fn extract_plane(surface: &Surface) -> (Vector<3>, Scalar) {
-    let Surface::SweptCurve(surface) = surface;
-    let line = match surface.curve {
-        Curve::Line(line) => line,
-        _ => todo!("Only plane-plane intersection is currently supported."),
+/// Access a `Surface` as a plane in constant-normal form, if it is one
+pub(super) fn plane(surface: &Surface) -> Option<(Vector<3>, Scalar)> {
+    let (line, path) = match surface {
+        Surface::SweptCurve(SweptCurve {
+            curve: Curve::Line(line),
+            path,
+        }) => (*line, *path),
+        _ => return None,
     };
 
     // Convert plane from parametric form to three-point form.
     let a = line.origin;
     let b = line.origin + line.direction;
-    let c = line.origin + surface.path;
+    let c = line.origin + path;
 
     // Convert plane from three-point form to constant-normal form. See
     // Real-Time Collision Detection by Christer Ericson, section 3.6, Planes
@@ -55,14 +58,14 @@ fn extract_plane(surface: &Surface) -> (Vector<3>, Scalar) {
     let normal = (b - a).cross(&(c - a)).normalize();
     let distance = normal.dot(&a.coords);
 
-    (normal, distance)
+    Some((normal, distance))
 }
 
 #[cfg(test)]
 mod tests {
-    use fj_math::Transform;
+    use fj_math::{Point, Transform, Vector};
 
-    use crate::geometry::{Curve, Surface};
+    use crate::geometry::{Curve, Cylinder, Surface};
 
     use super::surface;
 
@@ -73,9 +76,26 @@ mod tests {
 
         assert_eq!(surface(&xy, &xy), None);
         assert_eq!(
-            surface(&xy, &xy.transform(&Transform::translation([0., 0., 1.]))),
+            surface(
+                &xy,
+                &xy.clone().transform(&Transform::translation([0., 0., 1.]))
+            ),
             None,
         );
         assert_eq!(surface(&xy, &xz), Some(Curve::x_axis()));
     }
+
+    #[test]
+    fn plane_cylinder() {
+        let xy = Surface::xy_plane();
+        let cylinder = Surface::Cylinder(Cylinder {
+            center: Point::origin(),
+            a: Vector::from([1., 0., 0.]),
+            b: Vector::from([0., 1., 0.]),
+            axis: Vector::from([0., 0., 1.]),
+        });
+
+        assert_eq!(surface(&xy, &cylinder), None);
+        assert_eq!(surface(&cylinder, &xy), None);
+    }
 }
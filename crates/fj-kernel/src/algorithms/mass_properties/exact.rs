@@ -0,0 +1,338 @@
+//! Integration over faces, based on their exact geometry
+//!
+//! The integrals over the volume of the shape are turned into integrals over
+//! its faces by the divergence theorem. The integral over a face is an integral
+//! over its region in surface coordinates, which Green's theorem turns into an
+//! integral along the boundary of that region. That leaves integrals along one
+//! dimension, which are computed with Gauss-Legendre quadrature.
+
+use std::f64::consts::PI;
+
+use fj_math::{Point, Scalar, Vector};
+
+use crate::{
+    geometry::{Cone, Curve, Cylinder, Sphere, Surface, SweptCurve, Torus},
+    topology::{Cycle, Face},
+};
+
+use super::{add, quadrature::Quadrature, Integrals};
+
+/// Compute the integrals of a face from its exact geometry
+///
+/// Returns `None`, if the geometry of the face is not supported. This is the
+/// case for NURBS curves and surfaces, for cycles that wrap around a surface in
+/// the direction of its `v` coordinate, and for faces that the triangulation
+/// doesn't support either.
+pub fn face_integrals(
+    face: &Face,
+    quadrature: &Quadrature,
+) -> Option<Integrals> {
+    let surface = face.surface();
+    let integrator = FaceIntegrator::new(&surface, quadrature)?;
+
+    let mut integrals = [0.; 11];
+    let mut wrapping = Vec::new();
+
+    for cycle in face.all_cycles() {
+        let (cycle_integrals, [delta_u, delta_v]) = integrator.cycle(&cycle)?;
+
+        // All periods of the supported surfaces are full circles. A cycle that
+        // doesn't wrap around ends at the `u` and `v` where it started.
+        if delta_v.abs() > PI {
+            return None;
+        }
+        if delta_u.abs() > PI {
+            wrapping.push(delta_u);
+        }
+
+        add(&mut integrals, cycle_integrals);
+    }
+
+    // The region of the face in surface coordinates is cut open along the seam
+    // of the surface, if it wraps around. The seam runs along `v`, so it doesn't
+    // contribute to the integral along the boundary, which is an integral over
+    // `u`. What's left are the bounds of the surface, where the region might
+    // end instead of at a cycle.
+    let closing = match wrapping.as_slice() {
+        [] if face.exteriors.is_empty() => {
+            // The face covers the whole surface, except for its interiors.
+            let [min, max] = surface.closed_bounds()?;
+            Some((max.u - min.u).into_f64())
+        }
+        [] | [_, _] => None,
+        &[delta_u] => {
+            // A single cycle that wraps around only bounds a face, if the
+            // surface is bounded in the other direction.
+            if surface.periods()[1].is_some() {
+                return None;
+            }
+            Some(delta_u)
+        }
+        _ => return None,
+    };
+
+    if let Some(delta_u) = closing {
+        let [_, max] = surface.closed_bounds()?;
+
+        // The face is on the left of the cycle, so depending on the direction
+        // of the cycle, it is closed along the upper or the lower bound. The
+        // integral over `v` starts at the lower bound, so there's nothing to
+        // add in that case.
+        if delta_u > 0. {
+            let v = max.v.into_f64();
+            let bound = quadrature.integrate([0., delta_u], true, |u| {
+                integrator.integral_over_v(u, v)
+            })?;
+
+            add(&mut integrals, bound);
+        }
+    }
+
+    Some(integrals)
+}
+
+/// Integrates over the region of a face in the coordinates of its surface
+struct FaceIntegrator<'r> {
+    surface: &'r Surface,
+    quadrature: &'r Quadrature,
+
+    /// Whether the `u` and `v` coordinates of the surface are angles
+    angular: [bool; 2],
+
+    /// The `v` coordinate that the integrals over `v` start at
+    v0: f64,
+}
+
+impl<'r> FaceIntegrator<'r> {
+    /// Construct an instance of `FaceIntegrator`
+    ///
+    /// Returns `None`, if the surface is not supported.
+    fn new(surface: &'r Surface, quadrature: &'r Quadrature) -> Option<Self> {
+        let angular = match surface {
+            Surface::SweptCurve(SweptCurve { curve, .. }) => {
+                [is_angular(curve)?, false]
+            }
+            Surface::Cone(_) | Surface::Cylinder(_) => [true, false],
+            Surface::Sphere(_) | Surface::Torus(_) => [true, true],
+            Surface::Nurbs(_) => return None,
+        };
+
+        let v0 = surface
+            .closed_bounds()
+            .map(|[min, _]| min.v.into_f64())
+            .unwrap_or(0.);
+
+        Some(Self {
+            surface,
+            quadrature,
+            angular,
+            v0,
+        })
+    }
+
+    /// Integrate along a cycle
+    ///
+    /// By Green's theorem, the integral over a region is the integral of
+    /// `-integral_over_v(u, v) * du` along its boundary, if that runs
+    /// counter-clockwise. That's the case for the exterior cycles of a face,
+    /// and the interior cycles run the other way.
+    ///
+    /// Also returns by how much `u` and `v` change along the cycle, which is
+    /// not zero for cycles that wrap around the surface.
+    fn cycle(&self, cycle: &Cycle) -> Option<(Integrals, [f64; 2])> {
+        let v_period = self.surface.periods()[1].map(Scalar::into_f64);
+
+        let mut integrals = [0.; 11];
+        let mut delta = [0.; 2];
+
+        // The `v` coordinate of the previous point on the cycle. If `v` wraps
+        // around, it needs to be continuous along the cycle, as the integral
+        // over `v` depends on it.
+        let mut previous_v: Option<f64> = None;
+
+        for (edge, &reversed) in cycle.edges().zip(&cycle.reversed) {
+            let curve = edge.curve();
+
+            let [start, end] = match edge.vertices() {
+                Some(vertices) => {
+                    curve.range_between(vertices.map(|vertex| vertex.point()))
+                }
+                None => curve.closed_range()?,
+            }
+            .map(|point| point.t.into_f64());
+            let range = if reversed { [end, start] } else { [start, end] };
+
+            let edge_integrals =
+                self.quadrature.integrate(range, is_angular(&curve)?, |t| {
+                    let t = Scalar::from_f64(t);
+
+                    let point = curve.point_curve_to_model(&Point::from([t]));
+                    let tangent = curve_tangent(&curve, t)?;
+
+                    let coords =
+                        self.surface.point_model_to_surface(point).native();
+                    let u = coords.u.into_f64();
+                    let mut v = coords.v.into_f64();
+
+                    if let (Some(period), Some(previous)) =
+                        (v_period, previous_v)
+                    {
+                        v += period * ((previous - v) / period).round();
+                    }
+                    previous_v = Some(v);
+
+                    let [du, dv] = self.surface_derivative(u, v, tangent)?;
+
+                    let mut result = [0.; 13];
+                    for (result, integral) in
+                        result.iter_mut().zip(self.integral_over_v(u, v)?)
+                    {
+                        *result = -integral * du;
+                    }
+                    result[11] = du;
+                    result[12] = dv;
+
+                    Some(result)
+                })?;
+
+            for (integral, value) in integrals.iter_mut().zip(edge_integrals) {
+                *integral += value;
+            }
+            delta[0] += edge_integrals[11];
+            delta[1] += edge_integrals[12];
+        }
+
+        Some((integrals, delta))
+    }
+
+    /// Integrate over `v`, from `v0` to the given `v`, at the given `u`
+    fn integral_over_v(&self, u: f64, v: f64) -> Option<Integrals> {
+        self.quadrature
+            .integrate([self.v0, v], self.angular[1], |v| self.integrands(u, v))
+    }
+
+    /// Compute the integrands at the given surface coordinates
+    ///
+    /// The integrals over the volume of 1, x, y, z, x^2, y^2, z^2, xy, yz, and
+    /// zx are the integrals over the boundary of the vector fields below, whose
+    /// divergence is the respective function. The integrand of those is the
+    /// dot product of the vector field with the normal, scaled by the area in
+    /// model coordinates per area in surface coordinates. The last integrand
+    /// is just that scale, which integrates to the area.
+    fn integrands(&self, u: f64, v: f64) -> Option<Integrals> {
+        let (point, [du, dv]) = self.point_and_derivatives(u, v)?;
+        let normal = du.cross(&dv);
+
+        let [x, y, z] = point.coords.components.map(Scalar::into_f64);
+        let [nx, ny, nz] = normal.components.map(Scalar::into_f64);
+
+        Some([
+            x * nx,
+            x * x / 2. * nx,
+            y * y / 2. * ny,
+            z * z / 2. * nz,
+            x * x * x / 3. * nx,
+            y * y * y / 3. * ny,
+            z * z * z / 3. * nz,
+            x * x * y / 2. * nx,
+            y * y * z / 2. * ny,
+            z * z * x / 2. * nz,
+            normal.magnitude().into_f64(),
+        ])
+    }
+
+    /// Compute a point on the surface, and its derivatives by `u` and `v`
+    fn point_and_derivatives(
+        &self,
+        u: f64,
+        v: f64,
+    ) -> Option<(Point<3>, [Vector<3>; 2])> {
+        let [u, v] = [u, v].map(Scalar::from_f64);
+        let point = self.surface.point_surface_to_model(&Point::from([u, v]));
+
+        let (sin_u, cos_u) = u.sin_cos();
+        let (sin_v, cos_v) = v.sin_cos();
+
+        let derivatives = match self.surface {
+            Surface::SweptCurve(SweptCurve { curve, path }) => {
+                [curve_tangent(curve, u)?, *path]
+            }
+            Surface::Cylinder(Cylinder { a, b, axis, .. }) => {
+                [*b * cos_u - *a * sin_u, *axis]
+            }
+            Surface::Cone(Cone { a, b, axis, .. }) => [
+                (*b * cos_u - *a * sin_u) * v,
+                *a * cos_u + *b * sin_u + *axis,
+            ],
+            Surface::Sphere(Sphere { a, b, c, .. }) => [
+                (*b * cos_u - *a * sin_u) * cos_v,
+                *c * cos_v - (*a * cos_u + *b * sin_u) * sin_v,
+            ],
+            Surface::Torus(torus) => {
+                let Torus { a, b, c, .. } = torus;
+                let k = torus.minor_radius() / torus.major_radius();
+
+                [
+                    (*b * cos_u - *a * sin_u) * (Scalar::ONE + k * cos_v),
+                    *c * cos_v - (*a * cos_u + *b * sin_u) * k * sin_v,
+                ]
+            }
+            Surface::Nurbs(_) => return None,
+        };
+
+        Some((point, derivatives))
+    }
+
+    /// Compute the derivative of the surface coordinates along a curve
+    ///
+    /// `tangent` is the derivative of the curve in model coordinates. Returns
+    /// `None`, if the surface is degenerate at that point, like a sphere at its
+    /// poles.
+    fn surface_derivative(
+        &self,
+        u: f64,
+        v: f64,
+        tangent: Vector<3>,
+    ) -> Option<[f64; 2]> {
+        let (_, [du, dv]) = self.point_and_derivatives(u, v)?;
+
+        // Solve `du * a + dv * b == tangent` in the least-squares sense.
+        let [uu, uv, vv] = [du.dot(&du), du.dot(&dv), dv.dot(&dv)];
+        let [ut, vt] = [du.dot(&tangent), dv.dot(&tangent)];
+
+        let det = uu * vv - uv * uv;
+        if det <= uu * vv * Scalar::from_f64(1e-12) {
+            return None;
+        }
+
+        let a = (ut * vv - vt * uv) / det;
+        let b = (vt * uu - ut * uv) / det;
+
+        Some([a.into_f64(), b.into_f64()])
+    }
+}
+
+/// Indicate whether the coordinate of a curve is an angle
+///
+/// Returns `None`, if the curve is not supported.
+fn is_angular(curve: &Curve) -> Option<bool> {
+    match curve {
+        Curve::Circle(_) | Curve::Ellipse(_) => Some(true),
+        Curve::Line(_) => Some(false),
+        Curve::Nurbs(_) => None,
+    }
+}
+
+/// Compute the derivative of a curve at the given curve coordinate
+///
+/// Returns `None`, if the curve is not supported.
+fn curve_tangent(curve: &Curve, t: Scalar) -> Option<Vector<3>> {
+    let (sin, cos) = t.sin_cos();
+
+    match curve {
+        Curve::Circle(circle) => Some(circle.b * cos - circle.a * sin),
+        Curve::Ellipse(ellipse) => Some(ellipse.b * cos - ellipse.a * sin),
+        Curve::Line(line) => Some(line.direction),
+        Curve::Nurbs(_) => None,
+    }
+}
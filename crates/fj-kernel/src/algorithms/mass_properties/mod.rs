@@ -0,0 +1,546 @@
+mod exact;
+mod quadrature;
+
+use fj_interop::{debug::DebugInfo, mesh::Mesh};
+use fj_math::{Point, Scalar};
+
+use crate::shape::Shape;
+
+use self::quadrature::Quadrature;
+
+use super::{triangulation::triangulate_face, Tolerance, TriangulationError};
+
+/// The physical properties of a solid shape
+///
+/// All properties are computed using the divergence theorem, which turns
+/// integrals over the volume into integrals over the boundary of the shape.
+///
+/// When computed from a [`Shape`], the integrals are computed from the exact
+/// geometry of each face. The results are exact (up to floating point
+/// precision) for faces on planes, cylinders, cones, spheres, and tori, that
+/// are bounded by lines, circles, and ellipses. Only faces that involve other
+/// geometry, like NURBS curves and surfaces, are triangulated, and are only as
+/// precise as that triangulation.
+///
+/// When computed from a triangle mesh, the results are only exact for shapes
+/// that are bounded by planar faces with straight edges, as only those are
+/// exactly represented by their triangles. See David Eberly, "Polyhedral Mass
+/// Properties (Revisited)". The mesh must be closed, and all its triangles must
+/// be consistently wound. Whether they face outward or inward doesn't matter.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MassProperties {
+    /// The volume enclosed by the shape
+    pub volume: Scalar,
+
+    /// The area of the surface of the shape
+    pub area: Scalar,
+
+    /// The mass of the shape
+    ///
+    /// This is the volume, multiplied by the density that was used to compute
+    /// the mass properties.
+    pub mass: Scalar,
+
+    /// The center of mass, assuming uniform density
+    ///
+    /// This is the origin, if the shape doesn't enclose any volume.
+    pub center_of_mass: Point<3>,
+
+    /// The inertia tensor, relative to the center of mass
+    ///
+    /// The rows and columns refer to the x, y, and z axes, in that order.
+    pub inertia: [[Scalar; 3]; 3],
+}
+
+impl MassProperties {
+    /// Compute the mass properties of a shape
+    ///
+    /// `tolerance` is used to triangulate the faces whose geometry is not
+    /// supported by the exact computation, and determines how precise the
+    /// results are for those. `density` is the mass per unit of volume, and is
+    /// assumed to be uniform.
+    ///
+    /// Returns an error, if a face that needs to be triangulated can't be.
+    pub fn from_shape(
+        shape: &Shape,
+        tolerance: Tolerance,
+        density: impl Into<Scalar>,
+    ) -> Result<Self, TriangulationError> {
+        let quadrature = Quadrature::new();
+        let mut debug_info = DebugInfo::new();
+
+        let mut integrals = [0.; 11];
+
+        for face in shape.faces().values() {
+            match exact::face_integrals(&face, &quadrature) {
+                Some(face_integrals) => add(&mut integrals, face_integrals),
+                None => {
+                    for (triangle, _) in
+                        triangulate_face(&face, tolerance, &mut debug_info)?
+                    {
+                        add(&mut integrals, triangle_integrals(triangle));
+                    }
+                }
+            }
+        }
+
+        Ok(Self::from_integrals(integrals, density.into()))
+    }
+
+    /// Compute the mass properties of a triangle mesh
+    ///
+    /// `density` is the mass per unit of volume, and is assumed to be uniform.
+    pub fn from_mesh(
+        mesh: &Mesh<Point<3>>,
+        density: impl Into<Scalar>,
+    ) -> Self {
+        let mut integrals = [0.; 11];
+
+        for triangle in mesh.triangles() {
+            add(&mut integrals, triangle_integrals(triangle.points));
+        }
+
+        Self::from_integrals(integrals, density.into())
+    }
+
+    fn from_integrals(mut integrals: Integrals, density: Scalar) -> Self {
+        let density = density.into_f64();
+
+        // If the boundary faces inward, all integrals over the volume come out
+        // negated. The area doesn't depend on the orientation.
+        if integrals[0] < 0. {
+            for integral in &mut integrals[..10] {
+                *integral = -*integral;
+            }
+        }
+
+        let [volume, x, y, z, xx, yy, zz, xy, yz, zx, area] = integrals;
+        let area = Scalar::from(area);
+
+        if volume == 0. {
+            return Self {
+                volume: Scalar::ZERO,
+                area,
+                mass: Scalar::ZERO,
+                center_of_mass: Point::origin(),
+                inertia: [[Scalar::ZERO; 3]; 3],
+            };
+        }
+
+        let [cx, cy, cz] = [x / volume, y / volume, z / volume];
+
+        // The inertia tensor relative to the center of mass, for a density of
+        // one.
+        let ixx = yy + zz - volume * (cy * cy + cz * cz);
+        let iyy = zz + xx - volume * (cz * cz + cx * cx);
+        let izz = xx + yy - volume * (cx * cx + cy * cy);
+        let ixy = -(xy - volume * cx * cy);
+        let iyz = -(yz - volume * cy * cz);
+        let izx = -(zx - volume * cz * cx);
+
+        let inertia = [[ixx, ixy, izx], [ixy, iyy, iyz], [izx, iyz, izz]]
+            .map(|row| row.map(|value| Scalar::from(value * density)));
+
+        Self {
+            volume: Scalar::from(volume),
+            area,
+            mass: Scalar::from(volume * density),
+            center_of_mass: Point::from([cx, cy, cz]),
+            inertia,
+        }
+    }
+}
+
+/// The integrals that the mass properties are computed from
+///
+/// The integrals of 1, x, y, z, x^2, y^2, z^2, xy, yz, and zx over the volume,
+/// in that order, followed by the area of the boundary. Each face (or
+/// triangle) of the boundary contributes its part.
+type Integrals = [f64; 11];
+
+fn add(integrals: &mut Integrals, other: Integrals) {
+    for (integral, other) in integrals.iter_mut().zip(other) {
+        *integral += other;
+    }
+}
+
+/// Compute the contribution of a triangle to the integrals
+fn triangle_integrals([p0, p1, p2]: [Point<3>; 3]) -> Integrals {
+    let area = (p1 - p0).cross(&(p2 - p0)).magnitude() / 2.;
+
+    let [x0, y0, z0] = p0.coords.components.map(Scalar::into_f64);
+    let [x1, y1, z1] = p1.coords.components.map(Scalar::into_f64);
+    let [x2, y2, z2] = p2.coords.components.map(Scalar::into_f64);
+
+    // The (unnormalized) normal of the triangle
+    let [a1, b1, c1] = [x1 - x0, y1 - y0, z1 - z0];
+    let [a2, b2, c2] = [x2 - x0, y2 - y0, z2 - z0];
+    let [d0, d1, d2] =
+        [b1 * c2 - b2 * c1, a2 * c1 - a1 * c2, a1 * b2 - a2 * b1];
+
+    let x = Subexpressions::new([x0, x1, x2]);
+    let y = Subexpressions::new([y0, y1, y2]);
+    let z = Subexpressions::new([z0, z1, z2]);
+
+    [
+        d0 * x.f1 / 6.,
+        d0 * x.f2 / 24.,
+        d1 * y.f2 / 24.,
+        d2 * z.f2 / 24.,
+        d0 * x.f3 / 60.,
+        d1 * y.f3 / 60.,
+        d2 * z.f3 / 60.,
+        d0 * (y0 * x.g[0] + y1 * x.g[1] + y2 * x.g[2]) / 120.,
+        d1 * (z0 * y.g[0] + z1 * y.g[1] + z2 * y.g[2]) / 120.,
+        d2 * (x0 * z.g[0] + x1 * z.g[1] + x2 * z.g[2]) / 120.,
+        area.into_f64(),
+    ]
+}
+
+/// Subexpressions that are shared between the integrals of one triangle
+///
+/// Computed for one coordinate of the three points of the triangle.
+struct Subexpressions {
+    f1: f64,
+    f2: f64,
+    f3: f64,
+    g: [f64; 3],
+}
+
+impl Subexpressions {
+    fn new([w0, w1, w2]: [f64; 3]) -> Self {
+        let temp0 = w0 + w1;
+        let f1 = temp0 + w2;
+        let temp1 = w0 * w0;
+        let temp2 = temp1 + w1 * temp0;
+        let f2 = temp2 + w2 * f1;
+        let f3 = w0 * temp1 + w1 * temp2 + w2 * f2;
+
+        let g = [w0, w1, w2].map(|w| f2 + w * (f1 + w));
+
+        Self { f1, f2, f3, g }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use approx::assert_abs_diff_eq;
+    use fj_math::{Point, Scalar, Vector};
+
+    use crate::{
+        algorithms::{sweep_shape, triangulate, Tolerance},
+        geometry::{Circle, Curve, NurbsCurve, Sphere, Surface, Torus},
+        shape::Shape,
+        topology::{Cycle, Edge, Face, Vertex},
+    };
+
+    use super::MassProperties;
+
+    #[test]
+    fn cuboid() -> anyhow::Result<()> {
+        let mut sketch = Shape::new();
+        Face::builder(Surface::xy_plane(), &mut sketch)
+            .with_exterior_polygon([
+                [1., 1., 0.],
+                [3., 1., 0.],
+                [3., 2., 0.],
+                [1., 2., 0.],
+            ])
+            .build()?;
+        let shape =
+            sweep_shape(sketch, Vector::from([0., 0., 3.]), [255, 0, 0, 255]);
+
+        let properties = MassProperties::from_shape(&shape, tolerance(), 2.)?;
+        assert_cuboid(properties);
+
+        Ok(())
+    }
+
+    #[test]
+    fn cuboid_with_triangulated_faces() -> anyhow::Result<()> {
+        let mut sketch = Shape::new();
+
+        let [a, b, c, d] =
+            [[1., 1., 0.], [3., 1., 0.], [3., 2., 0.], [1., 2., 0.]].map(
+                |point| Vertex::builder(&mut sketch).build_from_point(point),
+            );
+        let [a, b, c, d] = [a?, b?, c?, d?];
+
+        // The faces that are bounded by this edge, or swept from it, are not
+        // supported by the exact computation, and need to be triangulated.
+        let curve = sketch.insert(Curve::Nurbs(NurbsCurve::clamped(
+            1,
+            vec![c.get().point(), d.get().point()],
+        )))?;
+        let nurbs = sketch.insert(Edge {
+            curve,
+            vertices: Some([c.clone(), d.clone()]),
+        })?;
+
+        let edges = vec![
+            Edge::builder(&mut sketch)
+                .build_line_segment_from_vertices([a.clone(), b.clone()])?,
+            Edge::builder(&mut sketch)
+                .build_line_segment_from_vertices([b, c])?,
+            nurbs,
+            Edge::builder(&mut sketch)
+                .build_line_segment_from_vertices([d, a])?,
+        ];
+        let cycle = sketch.insert(Cycle::from_edges(edges))?;
+        let surface = sketch.insert(Surface::xy_plane())?;
+        sketch.insert(Face {
+            surface,
+            exteriors: vec![cycle],
+            interiors: Vec::new(),
+            color: [255, 0, 0, 255],
+        })?;
+
+        let shape =
+            sweep_shape(sketch, Vector::from([0., 0., 3.]), [255, 0, 0, 255]);
+
+        let properties = MassProperties::from_shape(&shape, tolerance(), 2.)?;
+        assert_cuboid(properties);
+
+        Ok(())
+    }
+
+    #[test]
+    fn cylinder() -> anyhow::Result<()> {
+        let mut sketch = Shape::new();
+
+        let edge = Edge::builder(&mut sketch).build_circle(Scalar::ONE)?;
+        let cycle = sketch.insert(Cycle::from_edges(vec![edge]))?;
+        let surface = sketch.insert(Surface::xy_plane())?;
+        sketch.insert(Face {
+            surface,
+            exteriors: vec![cycle],
+            interiors: Vec::new(),
+            color: [255, 0, 0, 255],
+        })?;
+
+        let shape =
+            sweep_shape(sketch, Vector::from([0., 0., 2.]), [255, 0, 0, 255]);
+
+        let properties = MassProperties::from_shape(&shape, tolerance(), 1.)?;
+
+        let volume = PI * 2.;
+        assert_abs_diff_eq!(
+            properties.volume.into_f64(),
+            volume,
+            epsilon = EPS
+        );
+        assert_abs_diff_eq!(properties.area.into_f64(), PI * 6., epsilon = EPS);
+        assert_abs_diff_eq!(
+            properties.center_of_mass,
+            Point::from([0., 0., 1.]),
+            epsilon = EPS
+        );
+
+        // The moments of inertia of a cylinder with mass `m`, radius `r`, and
+        // height `h` are `m / 12 * (3 * r^2 + h^2)` around the axes that are
+        // perpendicular to its axis, and `m * r^2 / 2` around its axis.
+        let m = volume;
+        assert_inertia(
+            properties,
+            [m / 12. * (3. + 4.), m / 12. * (3. + 4.), m / 2.],
+        );
+
+        // The triangulation is inscribed into the cylinder, so the volume of
+        // its mesh is slightly smaller.
+        let mesh = triangulate(shape, tolerance(), &mut Default::default())?;
+        let from_mesh = MassProperties::from_mesh(&mesh, 1.);
+        assert!(from_mesh.volume < properties.volume);
+
+        Ok(())
+    }
+
+    #[test]
+    fn sphere() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+
+        let surface = shape.insert(Surface::Sphere(Sphere {
+            center: Point::from([1., 2., 3.]),
+            a: Vector::from([2., 0., 0.]),
+            b: Vector::from([0., 2., 0.]),
+            c: Vector::from([0., 0., 2.]),
+        }))?;
+        shape.insert(Face {
+            surface,
+            exteriors: Vec::new(),
+            interiors: Vec::new(),
+            color: [255, 0, 0, 255],
+        })?;
+
+        let properties = MassProperties::from_shape(&shape, tolerance(), 1.)?;
+
+        let volume = PI * 4. / 3. * 8.;
+        assert_abs_diff_eq!(
+            properties.volume.into_f64(),
+            volume,
+            epsilon = EPS
+        );
+        assert_abs_diff_eq!(
+            properties.area.into_f64(),
+            PI * 16.,
+            epsilon = EPS
+        );
+        assert_abs_diff_eq!(
+            properties.center_of_mass,
+            Point::from([1., 2., 3.]),
+            epsilon = EPS
+        );
+
+        // `m * r^2 * 2 / 5` around every axis
+        let moment = volume * 4. * 2. / 5.;
+        assert_inertia(properties, [moment; 3]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn hemisphere() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+
+        let [a, b] = [Vector::unit_x(), Vector::unit_y()];
+        let curve = shape.insert(Curve::Circle(Circle {
+            center: Point::origin(),
+            a,
+            b,
+        }))?;
+        let equator = shape.insert(Edge {
+            curve,
+            vertices: None,
+        })?;
+
+        // The upper half of the sphere is on the left of the equator, as it
+        // runs counter-clockwise around the z-axis.
+        let upper = shape.insert(Cycle::from_edges(vec![equator.clone()]))?;
+        let surface = shape.insert(Surface::Sphere(Sphere {
+            center: Point::origin(),
+            a,
+            b,
+            c: Vector::unit_z(),
+        }))?;
+        shape.insert(Face {
+            surface,
+            exteriors: vec![upper],
+            interiors: Vec::new(),
+            color: [255, 0, 0, 255],
+        })?;
+
+        // The bottom faces downward, so it uses the equator the other way.
+        let lower = shape.insert(Cycle::from_edges(vec![equator]).reverse())?;
+        let surface = shape.insert(Surface::xy_plane().reverse())?;
+        shape.insert(Face {
+            surface,
+            exteriors: vec![lower],
+            interiors: Vec::new(),
+            color: [255, 0, 0, 255],
+        })?;
+
+        assert!(shape.validate_solid().is_valid());
+
+        let properties = MassProperties::from_shape(&shape, tolerance(), 1.)?;
+
+        assert_abs_diff_eq!(
+            properties.volume.into_f64(),
+            PI * 2. / 3.,
+            epsilon = EPS
+        );
+        assert_abs_diff_eq!(properties.area.into_f64(), PI * 3., epsilon = EPS);
+        assert_abs_diff_eq!(
+            properties.center_of_mass,
+            Point::from([0., 0., 3. / 8.]),
+            epsilon = EPS
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn torus() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+
+        let [major, minor] = [3., 1.];
+        let surface = shape.insert(Surface::Torus(Torus {
+            center: Point::origin(),
+            a: Vector::from([major, 0., 0.]),
+            b: Vector::from([0., major, 0.]),
+            c: Vector::from([0., 0., minor]),
+        }))?;
+        shape.insert(Face {
+            surface,
+            exteriors: Vec::new(),
+            interiors: Vec::new(),
+            color: [255, 0, 0, 255],
+        })?;
+
+        let properties = MassProperties::from_shape(&shape, tolerance(), 1.)?;
+
+        let volume = 2. * PI * PI * major * minor * minor;
+        assert_abs_diff_eq!(
+            properties.volume.into_f64(),
+            volume,
+            epsilon = EPS
+        );
+        assert_abs_diff_eq!(
+            properties.area.into_f64(),
+            4. * PI * PI * major * minor,
+            epsilon = EPS
+        );
+
+        // The moment of inertia around the axis of the torus is
+        // `m * (R^2 + 3 / 4 * r^2)`, the other two are
+        // `m / 8 * (4 * R^2 + 5 * r^2)`.
+        let m = volume;
+        let axial = m * (major * major + 3. / 4. * minor * minor);
+        let other = m / 8. * (4. * major * major + 5. * minor * minor);
+        assert_inertia(properties, [other, other, axial]);
+
+        Ok(())
+    }
+
+    /// The precision that exact results are expected to have
+    const EPS: f64 = 1e-12;
+
+    fn assert_cuboid(properties: MassProperties) {
+        assert_abs_diff_eq!(properties.volume.into_f64(), 6., epsilon = EPS);
+        assert_abs_diff_eq!(properties.area.into_f64(), 22., epsilon = EPS);
+        assert_abs_diff_eq!(properties.mass.into_f64(), 12., epsilon = EPS);
+        assert_abs_diff_eq!(
+            properties.center_of_mass,
+            Point::from([2., 1.5, 1.5]),
+            epsilon = EPS
+        );
+
+        // The inertia tensor of a cuboid with mass `m` and side lengths `a`,
+        // `b`, and `c` is diagonal, with the moments `m / 12 * (b^2 + c^2)`,
+        // and so on.
+        let [a, b, c] = [2., 1., 3.];
+        let m = 12.;
+        assert_inertia(
+            properties,
+            [
+                m / 12. * (b * b + c * c),
+                m / 12. * (c * c + a * a),
+                m / 12. * (a * a + b * b),
+            ],
+        );
+    }
+
+    /// Assert that the inertia tensor is diagonal, with the given moments
+    fn assert_inertia(properties: MassProperties, moments: [f64; 3]) {
+        for (i, row) in properties.inertia.iter().enumerate() {
+            for (j, &value) in row.iter().enumerate() {
+                let expected = if i == j { moments[i] } else { 0. };
+                assert_abs_diff_eq!(value.into_f64(), expected, epsilon = EPS);
+            }
+        }
+    }
+
+    fn tolerance() -> Tolerance {
+        Tolerance::from_scalar(0.001).unwrap()
+    }
+}
@@ -0,0 +1,148 @@
+use std::f64::consts::PI;
+
+/// The number of points of the quadrature rule
+///
+/// A Gauss-Legendre rule with `N` points integrates polynomials of a degree of
+/// up to `2 * N - 1` exactly.
+const N: usize = 12;
+
+/// The longest range of an angle that is integrated in one piece
+///
+/// Integrands that depend on angles are not polynomials, but they are smooth.
+/// Integrating them in pieces of this length makes the error of the quadrature
+/// rule vanish below the precision of `f64`.
+const MAX_ANGLE: f64 = PI / 4.;
+
+/// Integrates functions over a range, using Gauss-Legendre quadrature
+pub struct Quadrature {
+    /// The points of the rule on `[-1, 1]`, with their weights
+    ///
+    /// The points are sorted in ascending order.
+    points: [(f64, f64); N],
+}
+
+impl Quadrature {
+    /// Compute the points and weights of the quadrature rule
+    ///
+    /// The points are the roots of the Legendre polynomial of degree `N`, which
+    /// are found using Newton's method.
+    pub fn new() -> Self {
+        let mut points = [(0., 0.); N];
+
+        for i in 0..N - N / 2 {
+            // An approximation of the root, which is close enough for Newton's
+            // method to converge quickly.
+            let mut x = (PI * (i as f64 + 0.75) / (N as f64 + 0.5)).cos();
+
+            for _ in 0..100 {
+                let (value, derivative) = legendre(x);
+
+                let previous = x;
+                x -= value / derivative;
+
+                if (x - previous).abs() <= f64::EPSILON {
+                    break;
+                }
+            }
+
+            let (_, derivative) = legendre(x);
+            let weight = 2. / ((1. - x * x) * derivative * derivative);
+
+            points[i] = (-x, weight);
+            points[N - 1 - i] = (x, weight);
+        }
+
+        Self { points }
+    }
+
+    /// Integrate `f` from the start of `range` to its end
+    ///
+    /// If the end is smaller than the start, the result is negated, as usual.
+    /// `f` is evaluated at points that are ordered from the start of the range
+    /// to its end.
+    ///
+    /// Set `angular`, if `f` depends on an angle, and not polynomially on its
+    /// argument. The range is split into pieces in that case.
+    ///
+    /// Returns `None`, if `f` does.
+    pub fn integrate<const D: usize>(
+        &self,
+        [start, end]: [f64; 2],
+        angular: bool,
+        mut f: impl FnMut(f64) -> Option<[f64; D]>,
+    ) -> Option<[f64; D]> {
+        let pieces = if angular {
+            ((end - start).abs() / MAX_ANGLE).ceil().max(1.) as usize
+        } else {
+            1
+        };
+        let length = (end - start) / pieces as f64;
+
+        let mut sum = [0.; D];
+
+        for piece in 0..pieces {
+            let center = start + length * (piece as f64 + 0.5);
+            let half = length / 2.;
+
+            for &(x, weight) in &self.points {
+                let value = f(center + half * x)?;
+
+                for (sum, value) in sum.iter_mut().zip(value) {
+                    *sum += value * weight * half;
+                }
+            }
+        }
+
+        Some(sum)
+    }
+}
+
+/// Evaluate the Legendre polynomial of degree `N` and its derivative at `x`
+fn legendre(x: f64) -> (f64, f64) {
+    let mut value = 1.;
+    let mut previous = 0.;
+
+    for j in 0..N {
+        let j = j as f64;
+        let next = ((2. * j + 1.) * x * value - j * previous) / (j + 1.);
+
+        previous = value;
+        value = next;
+    }
+
+    let derivative = N as f64 * (x * value - previous) / (x * x - 1.);
+
+    (value, derivative)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use approx::assert_abs_diff_eq;
+
+    use super::Quadrature;
+
+    #[test]
+    fn polynomial() {
+        let quadrature = Quadrature::new();
+
+        let [integral] = quadrature
+            .integrate([-1., 2.], false, |x| Some([x.powi(23) + x.powi(2)]))
+            .unwrap();
+
+        let expected = (2f64.powi(24) - 1.) / 24. + (8. + 1.) / 3.;
+        assert_abs_diff_eq!(integral, expected, epsilon = 1e-7);
+    }
+
+    #[test]
+    fn angular() {
+        let quadrature = Quadrature::new();
+
+        let [integral] = quadrature
+            .integrate([PI, 0.], true, |x| Some([(5. * x).sin().powi(2)]))
+            .unwrap();
+
+        assert_abs_diff_eq!(integral, -PI / 2., epsilon = 1e-14);
+    }
+}
@@ -4,6 +4,8 @@
 //! on their respective purpose.
 
 mod approx;
+mod mass_properties;
+mod query;
 mod sweep;
 mod triangulation;
 
@@ -11,6 +13,8 @@ pub mod intersection;
 
 pub use self::{
     approx::{CycleApprox, FaceApprox, Tolerance},
+    mass_properties::MassProperties,
+    query::{ClosestPoint, RayHit, ShapeQuery},
     sweep::sweep_shape,
-    triangulation::triangulate,
+    triangulation::{triangulate, TriangulationError},
 };
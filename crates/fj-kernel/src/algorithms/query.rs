@@ -0,0 +1,398 @@
+//! Geometric queries on shapes
+//!
+//! The queries in this module don't operate on the exact geometry of a shape,
+//! but on the triangulation of its faces. The triangulation is computed once,
+//! when a [`ShapeQuery`] is constructed, and the results of all queries are
+//! only as precise as the tolerance used for that.
+
+use fj_interop::debug::DebugInfo;
+use fj_math::{Point, Scalar, Triangle, Vector};
+
+use crate::{
+    shape::{Handle, Shape},
+    topology::Face,
+};
+
+use super::{triangulation::triangulate_face, Tolerance, TriangulationError};
+
+/// Answers geometric queries about a shape
+///
+/// This is a shared implementation for everything that needs to know where a
+/// shape is in space: Validation, boolean operations, or picking in the viewer.
+pub struct ShapeQuery {
+    triangles: Vec<(Handle<Face>, Triangle<3>)>,
+    tolerance: Tolerance,
+}
+
+impl ShapeQuery {
+    /// Prepare the shape for queries
+    ///
+    /// `tolerance` defines how far the triangulation that the queries operate
+    /// on is allowed to deviate from the actual faces. It is also used to
+    /// decide, whether a point lies on the boundary of the shape.
+    ///
+    /// Returns an error, if the shape can't be triangulated.
+    pub fn new(
+        shape: &Shape,
+        tolerance: Tolerance,
+    ) -> Result<Self, TriangulationError> {
+        // Queries are not something we can show debug info for, so it's just
+        // thrown away.
+        let mut debug_info = DebugInfo::new();
+
+        let mut triangles = Vec::new();
+        for face in shape.faces() {
+            let face_triangles =
+                triangulate_face(&face.get(), tolerance, &mut debug_info)?;
+
+            for (points, _) in face_triangles {
+                // Triangles that don't span any area can't be hit by a ray,
+                // and the closest point on them is also the closest point on
+                // one of their neighbors.
+                let [a, b, c] = points;
+                if (b - a).cross(&(c - a)).magnitude() == Scalar::ZERO {
+                    continue;
+                }
+
+                triangles.push((face.clone(), Triangle::from_points(points)));
+            }
+        }
+
+        Ok(Self {
+            triangles,
+            tolerance,
+        })
+    }
+
+    /// Cast a ray against the shape
+    ///
+    /// Returns all faces that the ray hits, ordered by the distance from the
+    /// origin of the ray. A ray that hits a face multiple times, for example a
+    /// curved face, results in multiple hits for that face.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `direction` is the zero vector.
+    pub fn cast_ray(
+        &self,
+        origin: impl Into<Point<3>>,
+        direction: impl Into<Vector<3>>,
+    ) -> Vec<RayHit> {
+        let origin = origin.into();
+        let direction = direction.into().normalize();
+
+        self.cast_ray_inner(origin, direction)
+            .into_iter()
+            .map(|(hit, _)| hit)
+            .collect()
+    }
+
+    /// Determine whether a point is inside of the shape
+    ///
+    /// Points that are on the boundary of the shape, within the tolerance,
+    /// count as inside.
+    ///
+    /// This casts rays from the point and counts how often they cross the
+    /// boundary of the shape. The result is only meaningful, if the shape is
+    /// closed.
+    pub fn contains_point(&self, point: impl Into<Point<3>>) -> bool {
+        let point = point.into();
+
+        if let Some(distance) = self.distance(point) {
+            if distance <= self.tolerance.inner() {
+                return true;
+            }
+        }
+
+        // If a ray passes through an edge or a vertex of the triangulation, or
+        // just grazes a triangle, the number of crossings can't be trusted. We
+        // try different directions, until we find one that avoids all of
+        // those. If none does, the majority decides.
+        let mut votes = 0;
+        for direction in RAY_DIRECTIONS {
+            let direction = Vector::from(direction).normalize();
+            let hits = self.cast_ray_inner(point, direction);
+
+            let inside = hits.len() % 2 == 1;
+            if hits.iter().all(|(_, ambiguous)| !ambiguous) {
+                return inside;
+            }
+
+            votes += if inside { 1 } else { -1 };
+        }
+
+        votes > 0
+    }
+
+    /// Find the point on the boundary of the shape that is closest to a point
+    ///
+    /// Returns `None`, if the shape has no faces.
+    pub fn closest_point(
+        &self,
+        point: impl Into<Point<3>>,
+    ) -> Option<ClosestPoint> {
+        let point = point.into();
+
+        self.triangles
+            .iter()
+            .map(|(face, triangle)| {
+                let closest = triangle.closest_point(point);
+
+                ClosestPoint {
+                    face: face.clone(),
+                    point: closest,
+                    distance: (closest - point).magnitude(),
+                }
+            })
+            .min_by_key(|closest| closest.distance)
+    }
+
+    /// Compute the distance between a point and the boundary of the shape
+    ///
+    /// Returns `None`, if the shape has no faces.
+    pub fn distance(&self, point: impl Into<Point<3>>) -> Option<Scalar> {
+        self.closest_point(point).map(|closest| closest.distance)
+    }
+
+    /// Cast a ray, with a normalized direction
+    ///
+    /// In addition to each hit, returns whether the hit is ambiguous, meaning
+    /// it is so close to the boundary of a triangle, that neighboring triangles
+    /// might or might not be hit too.
+    fn cast_ray_inner(
+        &self,
+        origin: Point<3>,
+        direction: Vector<3>,
+    ) -> Vec<(RayHit, bool)> {
+        let mut hits: Vec<(RayHit, bool)> = Vec::new();
+
+        for (face, triangle) in &self.triangles {
+            let distance = match triangle.cast_local_ray(
+                origin,
+                direction,
+                f64::INFINITY,
+                true,
+            ) {
+                Some(distance) => distance,
+                None => continue,
+            };
+            let point = origin + direction * distance;
+            let ambiguous = is_ambiguous(triangle, direction, point);
+
+            // A ray that passes through an edge within a face hits both of the
+            // triangles adjacent to that edge. That's still only a single hit.
+            let duplicate = hits.iter_mut().find(|(hit, _)| {
+                &hit.face == face
+                    && (hit.point - point).magnitude() <= self.tolerance.inner()
+            });
+            if let Some((_, duplicate_ambiguous)) = duplicate {
+                *duplicate_ambiguous &= ambiguous;
+                continue;
+            }
+
+            hits.push((
+                RayHit {
+                    face: face.clone(),
+                    point,
+                    distance,
+                },
+                ambiguous,
+            ));
+        }
+
+        hits.sort_by_key(|(hit, _)| hit.distance);
+        hits
+    }
+}
+
+/// A face that was hit by a ray
+///
+/// Returned by [`ShapeQuery::cast_ray`].
+#[derive(Clone, Debug)]
+pub struct RayHit {
+    /// The face that was hit
+    pub face: Handle<Face>,
+
+    /// The point where the ray hit the face
+    pub point: Point<3>,
+
+    /// The distance from the origin of the ray to the hit
+    pub distance: Scalar,
+}
+
+/// The point on the boundary of a shape that is closest to another point
+///
+/// Returned by [`ShapeQuery::closest_point`].
+#[derive(Clone, Debug)]
+pub struct ClosestPoint {
+    /// The face that the closest point is on
+    pub face: Handle<Face>,
+
+    /// The closest point
+    pub point: Point<3>,
+
+    /// The distance between the closest point and the point of the query
+    pub distance: Scalar,
+}
+
+/// Determine whether a ray hit on a triangle is ambiguous
+///
+/// That is the case, if the ray is nearly parallel to the triangle, or the hit
+/// is right on one of its edges.
+fn is_ambiguous(
+    triangle: &Triangle<3>,
+    direction: Vector<3>,
+    point: Point<3>,
+) -> bool {
+    let [a, b, c] = triangle.points();
+    let normal = (b - a).cross(&(c - a));
+    let area = normal.dot(&normal);
+
+    if direction.dot(&normal).abs() <= normal.magnitude() * AMBIGUITY_EPSILON {
+        return true;
+    }
+
+    // The barycentric coordinates of the point. If any of them is close to
+    // zero, the point is close to the opposite edge.
+    let barycentric = [[b, c], [c, a], [a, b]].map(|[start, end]| {
+        (end - start).cross(&(point - start)).dot(&normal) / area
+    });
+
+    barycentric
+        .into_iter()
+        .any(|coordinate| coordinate.abs() <= Scalar::from(AMBIGUITY_EPSILON))
+}
+
+/// The directions of the rays that are used to check for containment
+///
+/// These are chosen to not line up with any axis or diagonal, where most edges
+/// and vertices of typical shapes are.
+const RAY_DIRECTIONS: [[f64; 3]; 5] = [
+    [0.8017, 0.3456, 0.4873],
+    [-0.2913, 0.7529, 0.5907],
+    [0.4139, -0.6271, 0.6593],
+    [-0.5581, -0.4429, -0.7013],
+    [0.6803, 0.5113, -0.5251],
+];
+
+/// The relative tolerance used to detect ambiguous ray hits
+const AMBIGUITY_EPSILON: f64 = 1e-9;
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Scalar, Vector};
+
+    use crate::{
+        algorithms::{sweep_shape, Tolerance},
+        geometry::Surface,
+        shape::Shape,
+        topology::{Cycle, Edge, Face},
+    };
+
+    use super::ShapeQuery;
+
+    #[test]
+    fn cast_ray() -> anyhow::Result<()> {
+        let query = ShapeQuery::new(&cube()?, tolerance())?;
+
+        let hits = query.cast_ray([0.5, 0.5, -1.], [0., 0., 1.]);
+        let distances: Vec<_> = hits.iter().map(|hit| hit.distance).collect();
+        assert_eq!(distances, [Scalar::ONE, Scalar::TWO]);
+        assert!(hits[0].face != hits[1].face);
+
+        // Passes through an edge of the triangulation within the bottom and
+        // top faces. That mustn't result in duplicate hits.
+        let hits = query.cast_ray([0.25, 0.25, 2.], [0., 0., -1.]);
+        assert_eq!(hits.len(), 2);
+
+        let hits = query.cast_ray([0.5, 0.5, -1.], [0., 0., -1.]);
+        assert!(hits.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn contains_point() -> anyhow::Result<()> {
+        let query = ShapeQuery::new(&cube()?, tolerance())?;
+
+        assert!(query.contains_point([0.5, 0.5, 0.5]));
+        assert!(query.contains_point([0.1, 0.9, 0.2]));
+
+        // On the boundary
+        assert!(query.contains_point([0.5, 0.5, 1.]));
+        assert!(query.contains_point([1., 1., 1.]));
+
+        assert!(!query.contains_point([1.5, 0.5, 0.5]));
+        assert!(!query.contains_point([0.5, 0.5, -0.5]));
+        assert!(!query.contains_point([-1., -1., -1.]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn contains_point_cylinder() -> anyhow::Result<()> {
+        let mut sketch = Shape::new();
+
+        let edge = Edge::builder(&mut sketch).build_circle(Scalar::ONE)?;
+        let cycle = sketch.insert(Cycle::from_edges(vec![edge]))?;
+        let surface = sketch.insert(Surface::xy_plane())?;
+        sketch.insert(Face {
+            surface,
+            exteriors: vec![cycle],
+            interiors: Vec::new(),
+            color: [255, 0, 0, 255],
+        })?;
+
+        let shape =
+            sweep_shape(sketch, Vector::from([0., 0., 1.]), [255, 0, 0, 255]);
+        let query = ShapeQuery::new(&shape, tolerance())?;
+
+        assert!(query.contains_point([0., 0., 0.5]));
+        assert!(query.contains_point([0.5, -0.5, 0.5]));
+
+        assert!(!query.contains_point([0.9, 0.9, 0.5]));
+        assert!(!query.contains_point([0., 0., 1.5]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn closest_point() -> anyhow::Result<()> {
+        let query = ShapeQuery::new(&cube()?, tolerance())?;
+
+        let closest = query.closest_point([0.5, 0.5, 3.]).unwrap();
+        assert_eq!(closest.point, [0.5, 0.5, 1.].into());
+        assert_eq!(closest.distance, Scalar::TWO);
+
+        assert_eq!(query.distance([0.5, 0.5, 0.25]), Some(Scalar::from(0.25)));
+        assert_eq!(query.distance([2., 0.5, 0.5]), Some(Scalar::ONE));
+
+        let query = ShapeQuery::new(&Shape::new(), tolerance())?;
+        assert!(query.closest_point([0., 0., 0.]).is_none());
+
+        Ok(())
+    }
+
+    fn cube() -> anyhow::Result<Shape> {
+        let mut sketch = Shape::new();
+
+        Face::builder(Surface::xy_plane(), &mut sketch)
+            .with_exterior_polygon([
+                [0., 0., 0.],
+                [1., 0., 0.],
+                [1., 1., 0.],
+                [0., 1., 0.],
+            ])
+            .build()?;
+
+        Ok(sweep_shape(
+            sketch,
+            Vector::from([0., 0., 1.]),
+            [255, 0, 0, 255],
+        ))
+    }
+
+    fn tolerance() -> Tolerance {
+        Tolerance::from_scalar(0.01).unwrap()
+    }
+}
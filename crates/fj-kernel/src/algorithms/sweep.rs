@@ -1,23 +1,23 @@
 use std::collections::HashMap;
 
-use fj_math::{Transform, Triangle, Vector};
+use fj_math::{Scalar, Transform, Vector};
 
 use crate::{
-    geometry::{Surface, SweptCurve},
+    geometry::{Curve, Cylinder, Line, Surface, SweptCurve},
     shape::{Handle, Shape},
-    topology::{Cycle, Edge, Face, Vertex},
+    topology::{Cycle, Edge, Face, Shell, Solid, Vertex},
 };
 
-use super::{CycleApprox, Tolerance};
-
 /// Create a new shape by sweeping an existing one
-pub fn sweep_shape(
-    source: Shape,
-    path: Vector<3>,
-    tolerance: Tolerance,
-    color: [u8; 4],
-) -> Shape {
-    let mut target = Shape::new();
+///
+/// Every face of the existing shape becomes a [`Solid`], bounded by the bottom
+/// and top faces created from it, and the side faces created from its cycles.
+///
+/// The side faces face in the direction that is to the right of the cycles
+/// they are created from. For the faces of the existing shape to end up facing
+/// outward, `path` must point to the side that their surfaces face.
+pub fn sweep_shape(source: Shape, path: Vector<3>, color: [u8; 4]) -> Shape {
+    let mut target = Shape::new().with_min_distance(source.min_distance());
 
     let translation = Transform::translation(path);
 
@@ -71,17 +71,14 @@ pub fn sweep_shape(
         source_to_top.edges.insert(edge_source, edge_top);
     }
 
-    // Create the new cycles.
+    // Create the new cycles. The bottom face faces the other way than the
+    // source face, so its cycles need to run the other way too.
     for cycle_source in source.cycles() {
-        let edges_bottom = source_to_bottom.edges_for_cycle(&cycle_source);
-        let edges_top = source_to_top.edges_for_cycle(&cycle_source);
+        let cycle_bottom = source_to_bottom.cycle(&cycle_source).reverse();
+        let cycle_top = source_to_top.cycle(&cycle_source);
 
-        let cycle_bottom = target
-            .insert(Cycle {
-                edges: edges_bottom,
-            })
-            .unwrap();
-        let cycle_top = target.insert(Cycle { edges: edges_top }).unwrap();
+        let cycle_bottom = target.insert(cycle_bottom).unwrap();
+        let cycle_top = target.insert(cycle_top).unwrap();
 
         source_to_bottom
             .cycles
@@ -89,11 +86,16 @@ pub fn sweep_shape(
         source_to_top.cycles.insert(cycle_source, cycle_top);
     }
 
+    // The faces created from each source face, and from each source cycle.
+    // Used to put together the solids at the end.
+    let mut caps = Vec::new();
+    let mut side_faces: HashMap<_, Vec<_>> = HashMap::new();
+
     // Create top faces.
     for face_source in source.faces().values() {
         let surface = face_source.surface();
 
-        let surface_bottom = target.insert(surface.reverse()).unwrap();
+        let surface_bottom = target.insert(surface.clone().reverse()).unwrap();
         let surface_top =
             target.insert(surface.transform(&translation)).unwrap();
 
@@ -104,66 +106,80 @@ pub fn sweep_shape(
         let exteriors_top = source_to_top.exteriors_for_face(&face_source);
         let interiors_top = source_to_top.interiors_for_face(&face_source);
 
-        target
-            .insert(Face::Face {
+        let face_bottom = target
+            .insert(Face {
                 surface: surface_bottom,
                 exteriors: exteriors_bottom,
                 interiors: interiors_bottom,
                 color,
             })
             .unwrap();
-        target
-            .insert(Face::Face {
+        let face_top = target
+            .insert(Face {
                 surface: surface_top,
                 exteriors: exteriors_top,
                 interiors: interiors_top,
                 color,
             })
             .unwrap();
+
+        caps.push((face_source, [face_bottom, face_top]));
     }
 
     for cycle_source in source.cycles() {
         if cycle_source.get().edges.len() == 1 {
             // If there's only one edge in the cycle, it must be a continuous
             // edge that connects to itself. By sweeping that, we create a
-            // continuous face.
-            //
-            // Continuous faces aren't currently supported by the approximation
-            // code, and hence can't be triangulated. To address that, we fall
-            // back to the old and almost obsolete triangle representation to
-            // create the face.
-            //
-            // This is the last piece of code that still uses the triangle
-            // representation.
-
-            let approx = CycleApprox::new(&cycle_source.get(), tolerance);
-
-            let mut quads = Vec::new();
-            for segment in approx.segments() {
-                let [v0, v1] = segment.points();
-                let [v3, v2] = {
-                    let segment = Transform::translation(path)
-                        .transform_segment(&segment);
-                    segment.points()
-                };
-
-                quads.push([v0, v1, v2, v3]);
-            }
-
-            let mut side_face: Vec<(Triangle<3>, _)> = Vec::new();
-            for [v0, v1, v2, v3] in quads {
-                side_face.push(([v0, v1, v2].into(), color));
-                side_face.push(([v0, v2, v3].into(), color));
-            }
-
-            target.insert(Face::Triangles(side_face)).unwrap();
+            // continuous face, which is bounded by the bottom and top cycles.
+
+            // Can't panic. We just checked that there's exactly one edge.
+            let edge_bottom = source_to_bottom
+                .edges
+                .get(&cycle_source.get().edges[0])
+                .unwrap()
+                .clone();
+            let reversed = cycle_source.get().reversed[0];
+            let surface = target
+                .insert(side_surface(
+                    &edge_bottom.get().curve(),
+                    reversed,
+                    path,
+                ))
+                .unwrap();
+
+            // The side face uses the bottom and top edges in the opposite
+            // direction than the bottom and top faces.
+            let cycle_bottom = target
+                .insert(source_to_bottom.cycle(&cycle_source))
+                .unwrap();
+            let cycle_top = target
+                .insert(source_to_top.cycle(&cycle_source).reverse())
+                .unwrap();
+
+            let face = target
+                .insert(Face {
+                    surface,
+                    exteriors: vec![cycle_bottom],
+                    interiors: vec![cycle_top],
+                    color,
+                })
+                .unwrap();
+            side_faces
+                .entry(cycle_source.clone())
+                .or_default()
+                .push(face);
         } else {
             // If there's no continuous edge, we can create the non-
             // continuous faces using boundary representation.
 
             let mut vertex_bottom_to_edge = HashMap::new();
 
-            for edge_source in &cycle_source.get().edges {
+            let cycle_source_edges = cycle_source.get();
+            for (edge_source, &reversed) in cycle_source_edges
+                .edges
+                .iter()
+                .zip(&cycle_source_edges.reversed)
+            {
                 // Can't panic. We already ruled out the continuous edge case
                 // above, so this edge must have vertices.
                 let vertices_source =
@@ -171,39 +187,45 @@ pub fn sweep_shape(
 
                 // Create (or retrieve from the cache, `vertex_bottom_to_edge`)
                 // side edges from the vertices of this source/bottom edge.
-                let [side_edge_a, side_edge_b] =
-                    vertices_source.map(|vertex_source| {
-                        let vertex_bottom = source_to_bottom
-                            .vertices
-                            .get(&vertex_source)
-                            .unwrap()
-                            .clone();
-
-                        vertex_bottom_to_edge
-                            .entry(vertex_bottom.clone())
-                            .or_insert_with(|| {
-                                let curve = target
-                                    .insert(edge_source.get().curve())
-                                    .unwrap();
-
-                                let vertex_top = source_to_top
-                                    .vertices
-                                    .get(&vertex_source)
-                                    .unwrap()
-                                    .clone();
-
-                                target
-                                    .insert(Edge {
-                                        curve,
-                                        vertices: Some([
-                                            vertex_bottom,
-                                            vertex_top,
-                                        ]),
-                                    })
-                                    .unwrap()
-                            })
-                            .clone()
-                    });
+                let [side_edge_start, side_edge_end] = {
+                    let mut vertices = vertices_source;
+                    if reversed {
+                        vertices.reverse();
+                    }
+                    vertices
+                }
+                .map(|vertex_source| {
+                    let vertex_bottom = source_to_bottom
+                        .vertices
+                        .get(&vertex_source)
+                        .unwrap()
+                        .clone();
+
+                    vertex_bottom_to_edge
+                        .entry(vertex_bottom.clone())
+                        .or_insert_with(|| {
+                            let vertex_top = source_to_top
+                                .vertices
+                                .get(&vertex_source)
+                                .unwrap()
+                                .clone();
+
+                            let curve = target
+                                .insert(Curve::Line(Line::from_points([
+                                    vertex_bottom.get().point(),
+                                    vertex_top.get().point(),
+                                ])))
+                                .unwrap();
+
+                            target
+                                .insert(Edge {
+                                    curve,
+                                    vertices: Some([vertex_bottom, vertex_top]),
+                                })
+                                .unwrap()
+                        })
+                        .clone()
+                });
 
                 // Now we have everything we need to create the side face from
                 // this source/bottom edge.
@@ -214,38 +236,112 @@ pub fn sweep_shape(
                     source_to_top.edges.get(edge_source).unwrap().clone();
 
                 let surface = target
-                    .insert(Surface::SweptCurve(SweptCurve {
-                        curve: bottom_edge.get().curve(),
+                    .insert(side_surface(
+                        &bottom_edge.get().curve(),
+                        reversed,
                         path,
-                    }))
+                    ))
                     .unwrap();
 
+                // The cycle runs along the bottom edge in the direction of the
+                // source cycle, up the side edge at its end, back along the top
+                // edge, and down the side edge at its start.
                 let cycle = target
                     .insert(Cycle {
                         edges: vec![
                             bottom_edge,
+                            side_edge_end,
                             top_edge,
-                            side_edge_a,
-                            side_edge_b,
+                            side_edge_start,
                         ],
+                        reversed: vec![reversed, false, !reversed, true],
                     })
                     .unwrap();
 
-                target
-                    .insert(Face::Face {
+                let face = target
+                    .insert(Face {
                         surface,
                         exteriors: vec![cycle],
                         interiors: Vec::new(),
                         color,
                     })
                     .unwrap();
+                side_faces
+                    .entry(cycle_source.clone())
+                    .or_default()
+                    .push(face);
             }
         }
     }
 
+    // Create the solids.
+    for (face_source, faces) in caps {
+        let mut faces = faces.to_vec();
+        for cycle_source in
+            face_source.exteriors.iter().chain(&face_source.interiors)
+        {
+            faces.extend(
+                side_faces.get(cycle_source).into_iter().flatten().cloned(),
+            );
+        }
+
+        let shell = target.insert(Shell { faces }).unwrap();
+        target
+            .insert(Solid {
+                exterior: shell,
+                interiors: Vec::new(),
+            })
+            .unwrap();
+    }
+
     target
 }
 
+/// How far the path may be from the axis of a circle, for the circle to be
+/// swept into a cylinder
+///
+/// This is the cosine of the angle between the path and the plane of the
+/// circle. It allows for paths that are meant to be perpendicular to that
+/// plane, but are slightly off due to rounding.
+const MAX_AXIS_DEVIATION: f64 = 1e-9;
+
+/// Create the surface of a side face, by sweeping a curve along a path
+///
+/// The curve is swept in the direction in which the source cycle runs through
+/// its edge, which is against the direction of the curve, if `reversed` is
+/// `true`. That makes the surface face to the right of the source cycle.
+///
+/// Circles that are swept along their axis create a cylinder. Any other curve
+/// creates a generic swept surface.
+fn side_surface(curve: &Curve, reversed: bool, path: Vector<3>) -> Surface {
+    let curve = if reversed {
+        curve.clone().reverse()
+    } else {
+        curve.clone()
+    };
+
+    let perpendicular = |vector: Vector<3>| {
+        vector.dot(&path).abs()
+            <= vector.magnitude()
+                * path.magnitude()
+                * Scalar::from_f64(MAX_AXIS_DEVIATION)
+    };
+
+    match curve {
+        Curve::Circle(circle)
+            if perpendicular(circle.a) && perpendicular(circle.b) =>
+        {
+            Surface::Cylinder(Cylinder {
+                center: circle.center,
+                a: circle.a,
+                b: circle.b,
+                axis: path,
+            })
+        }
+        curve => Surface::SweptCurve(SweptCurve { curve, path }),
+    }
+}
+
 struct Relation {
     vertices: HashMap<Handle<Vertex>, Handle<Vertex>>,
     edges: HashMap<Handle<Edge>, Handle<Edge>>,
@@ -270,42 +366,28 @@ impl Relation {
         })
     }
 
-    fn edges_for_cycle(&self, cycle: &Handle<Cycle>) -> Vec<Handle<Edge>> {
-        cycle
-            .get()
-            .edges
-            .iter()
-            .map(|edge| self.edges.get(edge).unwrap().clone())
-            .collect()
+    fn cycle(&self, cycle: &Handle<Cycle>) -> Cycle {
+        let cycle = cycle.get();
+
+        Cycle {
+            edges: cycle
+                .edges
+                .iter()
+                .map(|edge| self.edges.get(edge).unwrap().clone())
+                .collect(),
+            reversed: cycle.reversed,
+        }
     }
 
     fn exteriors_for_face(&self, face: &Face) -> Vec<Handle<Cycle>> {
-        let exteriors = match face {
-            Face::Face { exteriors, .. } => exteriors,
-            _ => {
-                // Sketches are created using boundary representation, so this
-                // case can't happen.
-                unreachable!()
-            }
-        };
-
-        exteriors
+        face.exteriors
             .iter()
             .map(|cycle| self.cycles.get(cycle).unwrap().clone())
             .collect()
     }
 
     fn interiors_for_face(&self, face: &Face) -> Vec<Handle<Cycle>> {
-        let interiors = match face {
-            Face::Face { interiors, .. } => interiors,
-            _ => {
-                // Sketches are created using boundary representation, so this
-                // case can't happen.
-                unreachable!()
-            }
-        };
-
-        interiors
+        face.interiors
             .iter()
             .map(|cycle| self.cycles.get(cycle).unwrap().clone())
             .collect()
@@ -317,25 +399,21 @@ mod tests {
     use fj_math::{Point, Scalar, Vector};
 
     use crate::{
-        algorithms::Tolerance,
-        geometry::{Surface, SweptCurve},
+        geometry::{Circle, Curve, Cylinder, Surface, SweptCurve},
         shape::{Handle, Shape},
         topology::{Cycle, Edge, Face},
     };
 
-    use super::sweep_shape;
+    use super::{side_surface, sweep_shape};
 
     #[test]
     fn sweep() -> anyhow::Result<()> {
-        let tolerance = Tolerance::from_scalar(Scalar::ONE).unwrap();
-
         let sketch =
             Triangle::new([[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]], false)?;
 
         let swept = sweep_shape(
             sketch.shape,
             Vector::from([0., 0., 1.]),
-            tolerance,
             [255, 0, 0, 255],
         );
 
@@ -352,25 +430,144 @@ mod tests {
         let mut contains_top_face = false;
 
         for face in swept.faces() {
-            if matches!(face.get(), Face::Face { .. }) {
-                if face.get().clone() == bottom_face {
-                    contains_bottom_face = true;
-                }
-                if face.get().clone() == top_face {
-                    contains_top_face = true;
-                }
+            if face.get().clone() == bottom_face {
+                contains_bottom_face = true;
+            }
+            if face.get().clone() == top_face {
+                contains_top_face = true;
             }
         }
 
         assert!(contains_bottom_face);
         assert!(contains_top_face);
 
-        // Side faces are not tested, as those use triangle representation. The
-        // plan is to start testing them, as they are transitioned to b-rep.
+        // The bottom, the top, and three side faces form one solid.
+        let solids: Vec<_> = swept.solids().collect();
+        assert_eq!(solids.len(), 1);
+        assert_eq!(solids[0].get().faces().count(), 5);
+
+        assert!(swept.validate_solid().is_valid());
+
+        Ok(())
+    }
+
+    #[test]
+    fn sweep_circle() -> anyhow::Result<()> {
+        let mut sketch = Shape::new();
+        let edge = Edge::builder(&mut sketch).build_circle(Scalar::ONE)?;
+        let cycle = sketch.insert(Cycle::from_edges(vec![edge]))?;
+        let surface = sketch.insert(Surface::xy_plane())?;
+        sketch.insert(Face {
+            surface,
+            exteriors: vec![cycle],
+            interiors: Vec::new(),
+            color: [255, 0, 0, 255],
+        })?;
+
+        let swept =
+            sweep_shape(sketch, Vector::from([0., 0., 2.]), [255, 0, 0, 255]);
+
+        let cylinder = Surface::Cylinder(Cylinder {
+            center: Point::origin(),
+            a: Vector::from([1., 0., 0.]),
+            b: Vector::from([0., 1., 0.]),
+            axis: Vector::from([0., 0., 2.]),
+        });
+
+        let side_faces: Vec<_> = swept
+            .faces()
+            .filter(|face| face.get().surface() == cylinder)
+            .collect();
+        assert_eq!(side_faces.len(), 1);
+
+        let side_face = side_faces[0].get();
+        assert_eq!(side_face.exteriors().count(), 1);
+        assert_eq!(side_face.interiors().count(), 1);
+
+        assert!(swept.validate_solid().is_valid());
 
         Ok(())
     }
 
+    #[test]
+    fn sweep_reversed_edges() -> anyhow::Result<()> {
+        let mut sketch = Shape::new();
+
+        // The cycle runs counter-clockwise, but against each of its edges.
+        let [a, b, c] = [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]];
+        let mut edges = Vec::new();
+        for points in [[b, a], [c, b], [a, c]] {
+            edges.push(
+                Edge::builder(&mut sketch)
+                    .build_line_segment_from_points(points)?,
+            );
+        }
+        let cycle = Cycle::from_edges(edges);
+        assert_eq!(cycle.reversed, vec![true; 3]);
+
+        let cycle = sketch.insert(cycle)?;
+        let surface = sketch.insert(Surface::xy_plane())?;
+        sketch.insert(Face {
+            surface,
+            exteriors: vec![cycle],
+            interiors: Vec::new(),
+            color: [255, 0, 0, 255],
+        })?;
+
+        let swept =
+            sweep_shape(sketch, Vector::from([0., 0., 1.]), [255, 0, 0, 255]);
+
+        let report = swept.validate_solid();
+        assert!(report.is_valid(), "{report}");
+
+        // The side surfaces are swept along the direction of the cycle, which
+        // makes them face outward.
+        let mut directions: Vec<_> = swept
+            .faces()
+            .filter_map(|face| match face.get().surface() {
+                Surface::SweptCurve(SweptCurve {
+                    curve: Curve::Line(line),
+                    path,
+                }) if path == Vector::from([0., 0., 1.]) => {
+                    Some(line.direction)
+                }
+                _ => None,
+            })
+            .collect();
+        directions.sort();
+
+        let [a, b, c] = [a, b, c].map(Point::from);
+        let mut expected = vec![b - a, c - b, a - c];
+        expected.sort();
+
+        assert_eq!(directions, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn side_surface_of_circle_swept_almost_along_axis() {
+        let circle = Curve::Circle(Circle {
+            center: Point::origin(),
+            a: Vector::from([1., 0., 0.]),
+            b: Vector::from([0., 1., 0.]),
+        });
+
+        // Off-axis by a rounding error. Should still be a cylinder.
+        let path = Vector::from([f64::EPSILON, 0., 1.]);
+        assert!(matches!(
+            side_surface(&circle, false, path),
+            Surface::Cylinder(_)
+        ));
+
+        // Clearly off-axis. Can't be a cylinder.
+        let path = Vector::from([0.5, 0., 1.]);
+        assert!(matches!(
+            side_surface(&circle, false, path),
+            Surface::SweptCurve(_)
+        ));
+    }
+
     pub struct Triangle {
         shape: Shape,
         face: Handle<Face>,
@@ -392,16 +589,18 @@ mod tests {
             let ca = Edge::builder(&mut shape)
                 .build_line_segment_from_points([c, a])?;
 
-            let cycles = shape.insert(Cycle {
-                edges: vec![ab, bc, ca],
-            })?;
+            // A face on the reversed surface needs a reversed cycle, to
+            // still be on its left.
+            let cycle = Cycle::from_edges(vec![ab, bc, ca]);
+            let cycle = if reverse { cycle.reverse() } else { cycle };
+            let cycles = shape.insert(cycle)?;
 
             let surface =
                 Surface::SweptCurve(SweptCurve::plane_from_points([a, b, c]));
             let surface = if reverse { surface.reverse() } else { surface };
             let surface = shape.insert(surface)?;
 
-            let abc = Face::Face {
+            let abc = Face {
                 surface,
                 exteriors: vec![cycles],
                 interiors: Vec::new(),
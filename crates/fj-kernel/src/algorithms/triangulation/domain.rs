@@ -0,0 +1,405 @@
+use fj_interop::debug::DebugInfo;
+use fj_math::{Aabb, Point, Scalar};
+
+use crate::{
+    algorithms::{approx::approx_surface_coordinate, FaceApprox, Tolerance},
+    geometry::{self, Surface},
+};
+
+use super::{polygon::Polygon, TriangulationError};
+
+/// A face approximation, converted into surface coordinates
+///
+/// Converting points into the coordinates of a surface whose coordinates wrap
+/// around, like the angle of a cylinder, requires some care. A cycle that goes
+/// around the cylinder once, starts and ends at the same point in model
+/// coordinates, but at points that are a full period apart in surface
+/// coordinates. `FaceDomain` takes care of that, and also adds the points that
+/// are required to approximate surfaces whose curvature is not fully defined
+/// by the edges of the face.
+pub struct FaceDomain {
+    /// All points that need to be triangulated
+    pub points: Vec<geometry::Point<2>>,
+
+    /// The polygon that bounds the face in surface coordinates
+    pub polygon: Polygon,
+}
+
+impl FaceDomain {
+    /// Convert a face approximation into surface coordinates
+    ///
+    /// Returns an error, if the cycles of the face don't describe an area of
+    /// the surface that can be triangulated.
+    pub fn new(
+        surface: Surface,
+        approx: FaceApprox,
+        tolerance: Tolerance,
+    ) -> Result<Self, TriangulationError> {
+        let exterior = unwrap_cycle(&surface, approx.exterior.points);
+        let interiors: Vec<_> = approx
+            .interiors
+            .into_iter()
+            .map(|interior| unwrap_cycle(&surface, interior.points))
+            .collect();
+
+        let mut chains = Vec::new();
+        let mut wrapping = Vec::new();
+
+        for chain in Some(exterior).into_iter().chain(interiors) {
+            if chain.is_empty() {
+                continue;
+            }
+
+            match wraps_around(&surface, &chain) {
+                Some(axis) => wrapping.push((axis, chain)),
+                None => chains.push(chain),
+            }
+        }
+
+        let (exterior, interiors) = match wrapping.len() {
+            0 if chains.is_empty() => {
+                let bounds = surface
+                    .closed_bounds()
+                    .ok_or(TriangulationError::Unbounded)?;
+                let chain = bounding_chain(&surface, bounds, tolerance);
+
+                (chain, Vec::new())
+            }
+            0 => {
+                let exterior = chains.remove(0);
+                (exterior, chains)
+            }
+            1 => {
+                let (axis, chain) = wrapping.remove(0);
+
+                // All remaining cycles must be holes in the area between the
+                // wrapping cycle and the bound of the surface.
+                (
+                    close_wrapping_cycle(&surface, axis, chain, tolerance)?,
+                    chains,
+                )
+            }
+            2 => {
+                let (axis_a, a) = wrapping.remove(0);
+                let (axis_b, b) = wrapping.remove(0);
+
+                if axis_a != axis_b {
+                    return Err(TriangulationError::MismatchedWrappingCycles);
+                }
+
+                // All remaining cycles must be holes in the band that is
+                // bounded by the two wrapping cycles.
+                (join_wrapping_cycles(&surface, axis_a, a, b), chains)
+            }
+            num => {
+                return Err(TriangulationError::TooManyWrappingCycles(num));
+            }
+        };
+
+        let mut points: Vec<_> = exterior
+            .iter()
+            .chain(interiors.iter().flatten())
+            .copied()
+            .collect();
+
+        let polygon =
+            Polygon::new(surface.clone())
+                .with_exterior(exterior.iter().map(|point| point.native()))
+                .with_interiors(interiors.iter().map(|interior| {
+                    interior.iter().map(|point| point.native())
+                }));
+
+        points.extend(surface_points(&surface, &points, &polygon, tolerance));
+
+        points.sort();
+        points.dedup();
+
+        Ok(Self { points, polygon })
+    }
+}
+
+/// Convert a cycle approximation into surface coordinates
+///
+/// For surfaces with coordinates that wrap around, each point is moved by a
+/// multiple of the period, so it's as close as possible to the previous point.
+fn unwrap_cycle(
+    surface: &Surface,
+    points: Vec<Point<3>>,
+) -> Vec<geometry::Point<2>> {
+    let periods = surface.periods();
+
+    let mut unwrapped: Vec<geometry::Point<2>> = Vec::new();
+
+    for point in points {
+        let point = surface.point_model_to_surface(point);
+        let mut native = point.native();
+
+        if let Some(previous) = unwrapped.last() {
+            for (axis, period) in periods.into_iter().enumerate() {
+                if let Some(period) = period {
+                    let previous = previous.native().coords.components[axis];
+                    let coord = &mut native.coords.components[axis];
+
+                    *coord = *coord
+                        - period * ((*coord - previous) / period).round();
+                }
+            }
+        }
+
+        unwrapped.push(geometry::Point::new(native, point.canonical()));
+    }
+
+    unwrapped
+}
+
+/// Determine whether an unwrapped cycle wraps around the surface
+///
+/// Returns the index of the coordinate along which the cycle wraps around, if
+/// it does.
+fn wraps_around(
+    surface: &Surface,
+    chain: &[geometry::Point<2>],
+) -> Option<usize> {
+    let (first, last) = match (chain.first(), chain.last()) {
+        (Some(first), Some(last)) => (first.native(), last.native()),
+        _ => return None,
+    };
+
+    surface
+        .periods()
+        .into_iter()
+        .enumerate()
+        .find_map(|(axis, period)| {
+            let period = period?;
+            let distance = (last.coords.components[axis]
+                - first.coords.components[axis])
+                .abs();
+
+            if distance > period / 2. {
+                Some(axis)
+            } else {
+                None
+            }
+        })
+}
+
+/// Join two cycles that wrap around the surface into a single chain
+///
+/// The two cycles bound a band that goes around the surface. This band is cut
+/// open along the seam of the surface, resulting in a single closed chain.
+fn join_wrapping_cycles(
+    surface: &Surface,
+    axis: usize,
+    a: Vec<geometry::Point<2>>,
+    b: Vec<geometry::Point<2>>,
+) -> Vec<geometry::Point<2>> {
+    // Can't panic. We only get here, if the surface wraps around along `axis`.
+    let period = surface.periods()[axis].unwrap();
+    let other = 1 - axis;
+
+    let average = |chain: &[geometry::Point<2>]| {
+        chain
+            .iter()
+            .map(|point| point.native().coords.components[other])
+            .fold(Scalar::ZERO, |sum, coord| sum + coord)
+            / chain.len() as f64
+    };
+    let (mut lower, mut upper) = if average(&a) <= average(&b) {
+        (a, b)
+    } else {
+        (b, a)
+    };
+
+    let coord =
+        |point: &geometry::Point<2>| point.native().coords.components[axis];
+
+    // The lower chain needs to go forward along the wrapping axis, the upper
+    // one backward. That way, the joined chain goes around the band.
+    //
+    // Can't panic. Wrapping chains are never empty.
+    if coord(lower.last().unwrap()) < coord(&lower[0]) {
+        lower.reverse();
+    }
+    if coord(upper.last().unwrap()) > coord(&upper[0]) {
+        upper.reverse();
+    }
+
+    // Move the upper chain, so it starts where the lower chain ends.
+    let end_of_lower = coord(lower.last().unwrap());
+    let offset = period * ((end_of_lower - coord(&upper[0])) / period).round();
+    let upper = upper.into_iter().map(|point| {
+        let mut native = point.native();
+        native.coords.components[axis] += offset;
+        geometry::Point::new(native, point.canonical())
+    });
+
+    let mut chain = lower;
+    chain.extend(upper);
+
+    // Close the chain.
+    if let Some(&first) = chain.first() {
+        chain.push(first);
+    }
+
+    chain
+}
+
+/// Close a single cycle that wraps around the surface along its bound
+///
+/// A single cycle that wraps around a surface only bounds a face, if the
+/// surface is bounded in the other direction, like a sphere is bounded by its
+/// poles. The face is on the left of the cycle, so depending on the direction
+/// of the cycle, it is closed along the lower or upper bound of the surface.
+fn close_wrapping_cycle(
+    surface: &Surface,
+    axis: usize,
+    mut chain: Vec<geometry::Point<2>>,
+    tolerance: Tolerance,
+) -> Result<Vec<geometry::Point<2>>, TriangulationError> {
+    let other = 1 - axis;
+
+    // If the surface wraps around in the other direction too, like a torus
+    // does, a single cycle doesn't separate it into two parts.
+    if surface.periods()[other].is_some() {
+        return Err(TriangulationError::Unbounded);
+    }
+    let [min, max] = surface
+        .closed_bounds()
+        .ok_or(TriangulationError::Unbounded)?;
+
+    let coord =
+        |point: &geometry::Point<2>| point.native().coords.components[axis];
+
+    // Can't panic. Wrapping chains are never empty.
+    let start = coord(&chain[0]);
+    let end = coord(chain.last().unwrap());
+
+    // Going forward along `u`, the left side is towards higher `v`. Going
+    // forward along `v`, it's towards lower `u`.
+    let forward = end > start;
+    let bound = if (axis == 0) == forward {
+        max.coords.components[other]
+    } else {
+        min.coords.components[other]
+    };
+
+    let mut coords = approx_surface_coordinate(
+        surface,
+        axis,
+        [start.min(end), start.max(end)],
+        tolerance,
+    )
+    .unwrap_or_else(|| vec![start.min(end), start.max(end)]);
+
+    // The bound is traversed from the end of the chain back to its start.
+    if forward {
+        coords.reverse();
+    }
+
+    for value in coords {
+        let mut native = Point::origin();
+        native.coords.components[axis] = value;
+        native.coords.components[other] = bound;
+
+        chain.push(surface_point(surface, native));
+    }
+
+    // Close the chain.
+    if let Some(&first) = chain.first() {
+        chain.push(first);
+    }
+
+    Ok(chain)
+}
+
+/// Create a closed chain along the bounds of a closed surface
+fn bounding_chain(
+    surface: &Surface,
+    [min, max]: [Point<2>; 2],
+    tolerance: Tolerance,
+) -> Vec<geometry::Point<2>> {
+    let coords = |axis: usize| {
+        let bounds = [min.coords.components[axis], max.coords.components[axis]];
+        approx_surface_coordinate(surface, axis, bounds, tolerance)
+            .unwrap_or_else(|| bounds.to_vec())
+    };
+
+    let us = coords(0);
+    let vs = coords(1);
+
+    let mut native = Vec::new();
+    native.extend(us.iter().map(|&u| Point::from([u, min.v])));
+    native.extend(vs.iter().skip(1).map(|&v| Point::from([max.u, v])));
+    native.extend(us.iter().rev().skip(1).map(|&u| Point::from([u, max.v])));
+    native.extend(vs.iter().rev().skip(1).map(|&v| Point::from([min.u, v])));
+
+    native
+        .into_iter()
+        .map(|point| surface_point(surface, point))
+        .collect()
+}
+
+/// Compute the points from within the face that approximate the surface
+fn surface_points(
+    surface: &Surface,
+    boundary: &[geometry::Point<2>],
+    polygon: &Polygon,
+    tolerance: Tolerance,
+) -> Vec<geometry::Point<2>> {
+    let mut points = Vec::new();
+
+    if boundary.is_empty() {
+        return points;
+    }
+
+    let aabb = Aabb::<2>::from_points(boundary.iter().map(|p| p.native()));
+
+    let coords = |axis: usize| {
+        let bounds = [
+            aabb.min.coords.components[axis],
+            aabb.max.coords.components[axis],
+        ];
+        approx_surface_coordinate(surface, axis, bounds, tolerance)
+    };
+
+    let (us, vs) = match (coords(0), coords(1)) {
+        (Some(us), Some(vs)) => (us, vs),
+        _ => return points,
+    };
+
+    // Points on the boundary of the bounding box are skipped. They could be on
+    // the boundary of the polygon, where the containment check is not reliable.
+    // The boundary itself is already approximated by the cycles.
+    let inner = |values: &[Scalar]| values[1..values.len() - 1].to_vec();
+
+    for &u in &inner(&us) {
+        for &v in &inner(&vs) {
+            let point = Point::from([u, v]);
+
+            // The debug info generated here is of no interest. The interesting
+            // checks happen when checking the triangles.
+            if polygon.contains_point(point, &mut DebugInfo::new()) {
+                points.push(surface_point(surface, point));
+            }
+        }
+    }
+
+    points
+}
+
+/// Create a point from surface coordinates
+///
+/// Coordinates that wrap around are moved into the first period, before
+/// converting them into model coordinates. This makes sure that the model
+/// coordinates of points along the seam of the surface are identical.
+fn surface_point(surface: &Surface, point: Point<2>) -> geometry::Point<2> {
+    let mut wrapped = point;
+    for (axis, period) in surface.periods().into_iter().enumerate() {
+        if let Some(period) = period {
+            let coord = &mut wrapped.coords.components[axis];
+            *coord = *coord % period;
+        }
+    }
+
+    geometry::Point::new(point, surface.point_surface_to_model(&wrapped))
+}
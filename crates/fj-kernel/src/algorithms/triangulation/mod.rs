@@ -1,13 +1,17 @@
 mod delaunay;
+mod domain;
 mod polygon;
 mod ray;
 
-use fj_interop::{debug::DebugInfo, mesh::Mesh};
+use fj_interop::{
+    debug::DebugInfo,
+    mesh::{Color, Mesh},
+};
 use fj_math::Point;
 
 use crate::{shape::Shape, topology::Face};
 
-use self::polygon::Polygon;
+use self::domain::FaceDomain;
 
 use super::{FaceApprox, Tolerance};
 
@@ -16,75 +20,84 @@ pub fn triangulate(
     shape: Shape,
     tolerance: Tolerance,
     debug_info: &mut DebugInfo,
-) -> Mesh<Point<3>> {
+) -> Result<Mesh<Point<3>>, TriangulationError> {
     let mut mesh = Mesh::new();
 
     for face in shape.faces() {
-        let face = face.get();
-        match &face {
-            Face::Face { surface, color, .. } => {
-                let surface = surface.get();
-                let approx = FaceApprox::new(&face, tolerance);
-
-                let points: Vec<_> = approx
-                    .points
-                    .into_iter()
-                    .map(|vertex| {
-                        // Can't panic, unless the approximation wrongfully
-                        // generates points that are not in the surface.
-                        surface.point_model_to_surface(vertex)
-                    })
-                    .collect();
-                let face_as_polygon = Polygon::new(surface)
-                    .with_exterior(approx.exterior.points.into_iter().map(
-                        |point| {
-                            // Can't panic, unless the approximation wrongfully
-                            // generates points that are not in the surface.
-                            surface.point_model_to_surface(point).native()
-                        },
-                    ))
-                    .with_interiors(approx.interiors.into_iter().map(
-                        |interior| {
-                            interior.points.into_iter().map(|point| {
-                                // Can't panic, unless the approximation
-                                // wrongfully generates points that are not in
-                                // the surface.
-                                surface.point_model_to_surface(point).native()
-                            })
-                        },
-                    ));
-
-                let mut triangles = delaunay::triangulate(points);
-                triangles.retain(|triangle| {
-                    face_as_polygon.contains_triangle(
-                        triangle.map(|point| point.native()),
-                        debug_info,
-                    )
-                });
-
-                for triangle in triangles {
-                    let points = triangle.map(|point| point.canonical());
-                    mesh.push_triangle(points, *color);
-                }
-            }
-            Face::Triangles(triangles) => {
-                for &(triangle, color) in triangles {
-                    mesh.push_triangle(triangle.points(), color);
-                }
-            }
+        for (triangle, color) in
+            triangulate_face(&face.get(), tolerance, debug_info)?
+        {
+            mesh.push_triangle(triangle, color);
         }
     }
 
-    mesh
+    Ok(mesh)
+}
+
+/// Triangulate a single face
+///
+/// Returns the triangles in model coordinates, together with their color.
+pub(crate) fn triangulate_face(
+    face: &Face,
+    tolerance: Tolerance,
+    debug_info: &mut DebugInfo,
+) -> Result<Vec<([Point<3>; 3], Color)>, TriangulationError> {
+    let surface = face.surface();
+    let approx = FaceApprox::new(face, tolerance);
+    let domain = FaceDomain::new(surface, approx, tolerance)?;
+
+    let mut triangles = delaunay::triangulate(domain.points);
+    triangles.retain(|triangle| {
+        // Triangles that touch the poles of a sphere can collapse in model
+        // coordinates. Those are of no use.
+        let [a, b, c] = triangle.map(|point| point.canonical());
+        if a == b || b == c || c == a {
+            return false;
+        }
+
+        domain
+            .polygon
+            .contains_triangle(triangle.map(|point| point.native()), debug_info)
+    });
+
+    let triangles = triangles
+        .into_iter()
+        .map(|triangle| (triangle.map(|point| point.canonical()), face.color))
+        .collect();
+
+    Ok(triangles)
+}
+
+/// An error that can occur while triangulating a face
+#[derive(Debug, thiserror::Error)]
+pub enum TriangulationError {
+    /// The cycles of the face don't bound it on all sides
+    #[error("Face is not bounded on all sides")]
+    Unbounded,
+
+    /// Two cycles of the face wrap around the surface in different directions
+    #[error("Cycles of face wrap around the surface in different directions")]
+    MismatchedWrappingCycles,
+
+    /// More than two cycles of the face wrap around the surface
+    #[error(
+        "Face has {0} cycles that wrap around the surface; at most two are \
+        supported"
+    )]
+    TooManyWrappingCycles(usize),
 }
 
 #[cfg(test)]
 mod tests {
+    use approx::assert_abs_diff_eq;
     use fj_interop::{debug::DebugInfo, mesh::Mesh};
-    use fj_math::{Point, Scalar};
+    use fj_math::{Point, Scalar, Vector};
 
     use crate::{
-        algorithms::Tolerance, geometry::Surface, shape::Shape, topology::Face,
+        algorithms::{sweep_shape, Tolerance, TriangulationError},
+        geometry::{Sphere, Surface},
+        shape::Shape,
+        topology::{Cycle, Edge, Face},
     };
 
     #[test]
@@ -144,10 +157,149 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn cylinder() -> anyhow::Result<()> {
+        let mut sketch = Shape::new();
+
+        let edge = Edge::builder(&mut sketch).build_circle(Scalar::ONE)?;
+        let cycle = sketch.insert(Cycle::from_edges(vec![edge]))?;
+        let surface = sketch.insert(Surface::xy_plane())?;
+        sketch.insert(Face {
+            surface,
+            exteriors: vec![cycle],
+            interiors: Vec::new(),
+            color: [255, 0, 0, 255],
+        })?;
+
+        let shape =
+            sweep_shape(sketch, Vector::from([0., 0., 1.]), [255, 0, 0, 255]);
+        let triangles = triangulate(shape);
+
+        let mut num_side_triangles = 0;
+        for triangle in triangles.triangles() {
+            let [a, b, c] = triangle.points;
+
+            // Skip the triangles of the top and bottom faces.
+            if a.z == b.z && b.z == c.z {
+                continue;
+            }
+
+            num_side_triangles += 1;
+
+            for point in [a, b, c] {
+                let radius = (point.x * point.x + point.y * point.y).sqrt();
+                assert_abs_diff_eq!(radius, Scalar::ONE, epsilon = 1e-12);
+            }
+        }
+
+        assert!(num_side_triangles > 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn sphere() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+
+        let surface = shape.insert(Surface::Sphere(Sphere {
+            center: Point::origin(),
+            a: Vector::from([1., 0., 0.]),
+            b: Vector::from([0., 1., 0.]),
+            c: Vector::from([0., 0., 1.]),
+        }))?;
+        shape.insert(Face {
+            surface,
+            exteriors: Vec::new(),
+            interiors: Vec::new(),
+            color: [255, 0, 0, 255],
+        })?;
+
+        let triangles = triangulate(shape);
+
+        let mut num_triangles = 0;
+        for triangle in triangles.triangles() {
+            num_triangles += 1;
+
+            for point in triangle.points {
+                assert_abs_diff_eq!(
+                    point.coords.magnitude(),
+                    Scalar::ONE,
+                    epsilon = 1e-12
+                );
+            }
+        }
+
+        assert!(num_triangles > 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn hemisphere() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+
+        let edge = Edge::builder(&mut shape).build_circle(Scalar::ONE)?;
+        let cycle = shape.insert(Cycle::from_edges(vec![edge]))?;
+        let surface = shape.insert(Surface::Sphere(Sphere {
+            center: Point::origin(),
+            a: Vector::from([1., 0., 0.]),
+            b: Vector::from([0., 1., 0.]),
+            c: Vector::from([0., 0., 1.]),
+        }))?;
+        shape.insert(Face {
+            surface,
+            exteriors: vec![cycle],
+            interiors: Vec::new(),
+            color: [255, 0, 0, 255],
+        })?;
+
+        let triangles = triangulate(shape);
+
+        // The equator wraps around the sphere. The face is on its left, which
+        // is the northern hemisphere.
+        let mut num_triangles = 0;
+        for triangle in triangles.triangles() {
+            num_triangles += 1;
+
+            for point in triangle.points {
+                assert_abs_diff_eq!(
+                    point.coords.magnitude(),
+                    Scalar::ONE,
+                    epsilon = 1e-12
+                );
+                assert!(point.z >= -Scalar::from(1e-12));
+            }
+        }
+
+        assert!(num_triangles > 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn unbounded() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+
+        let surface = shape.insert(Surface::xy_plane())?;
+        shape.insert(Face {
+            surface,
+            exteriors: Vec::new(),
+            interiors: Vec::new(),
+            color: [255, 0, 0, 255],
+        })?;
+
+        let tolerance = Tolerance::from_scalar(Scalar::ONE).unwrap();
+        let result =
+            super::triangulate(shape, tolerance, &mut DebugInfo::new());
+        assert!(matches!(result, Err(TriangulationError::Unbounded)));
+
+        Ok(())
+    }
+
     fn triangulate(shape: Shape) -> Mesh<Point<3>> {
         let tolerance = Tolerance::from_scalar(Scalar::ONE).unwrap();
 
         let mut debug_info = DebugInfo::new();
-        super::triangulate(shape, tolerance, &mut debug_info)
+        super::triangulate(shape, tolerance, &mut debug_info).unwrap()
     }
 }
@@ -0,0 +1,233 @@
+//! B-spline basis functions and related algorithms
+//!
+//! These are shared by the NURBS curves and surfaces. The algorithms are taken
+//! from The NURBS Book by Les Piegl and Wayne Tiller. Control points are
+//! handled in homogeneous coordinates, with the weight as the fourth component,
+//! which makes the algorithms work for rational and non-rational splines alike.
+
+use fj_math::{Point, Scalar, Vector};
+
+/// Access the parameter range of a B-spline
+///
+/// The spline is only defined between the knot at index `degree` and the knot
+/// at index `knots.len() - degree - 1`.
+pub fn parameter_range(degree: usize, knots: &[Scalar]) -> [Scalar; 2] {
+    [knots[degree], knots[knots.len() - degree - 1]]
+}
+
+/// Find the knot span that contains `t`
+///
+/// `n` is the index of the last control point. Values of `t` outside of the
+/// parameter range are clamped to the first or last span respectively.
+///
+/// This is algorithm A2.1 from The NURBS Book.
+pub fn find_span(
+    t: Scalar,
+    degree: usize,
+    n: usize,
+    knots: &[Scalar],
+) -> usize {
+    if t >= knots[n + 1] {
+        return n;
+    }
+    if t <= knots[degree] {
+        return degree;
+    }
+
+    let mut low = degree;
+    let mut high = n + 1;
+    let mut mid = (low + high) / 2;
+
+    while t < knots[mid] || t >= knots[mid + 1] {
+        if t < knots[mid] {
+            high = mid;
+        } else {
+            low = mid;
+        }
+        mid = (low + high) / 2;
+    }
+
+    mid
+}
+
+/// Compute the non-vanishing basis functions at `t`
+///
+/// Returns `degree + 1` values, which belong to the control points with the
+/// indices `span - degree` to `span`.
+///
+/// This is algorithm A2.2 from The NURBS Book.
+pub fn basis_functions(
+    span: usize,
+    t: Scalar,
+    degree: usize,
+    knots: &[Scalar],
+) -> Vec<Scalar> {
+    let mut n = vec![Scalar::ZERO; degree + 1];
+    let mut left = vec![Scalar::ZERO; degree + 1];
+    let mut right = vec![Scalar::ZERO; degree + 1];
+
+    n[0] = Scalar::ONE;
+
+    for j in 1..=degree {
+        left[j] = t - knots[span + 1 - j];
+        right[j] = knots[span + j] - t;
+
+        let mut saved = Scalar::ZERO;
+
+        for r in 0..j {
+            let temp = n[r] / (right[r + 1] + left[j - r]);
+            n[r] = saved + right[r + 1] * temp;
+            saved = left[j - r] * temp;
+        }
+
+        n[j] = saved;
+    }
+
+    n
+}
+
+/// Compute the first derivatives of the non-vanishing basis functions at `t`
+///
+/// Returns `degree + 1` values, which belong to the same control points as the
+/// values returned by [`basis_functions`].
+pub fn basis_derivatives(
+    span: usize,
+    t: Scalar,
+    degree: usize,
+    knots: &[Scalar],
+) -> Vec<Scalar> {
+    if degree == 0 {
+        return vec![Scalar::ZERO];
+    }
+
+    // The derivatives are computed from the basis functions of the next-lower
+    // degree. Those belong to the control points `span - degree + 1` to `span`.
+    let lower = basis_functions(span, t, degree - 1, knots);
+    let p = Scalar::from_u64(degree as u64);
+
+    let term = |n: Scalar, a: usize, b: usize| {
+        let denominator = knots[b] - knots[a];
+        if denominator == Scalar::ZERO {
+            return Scalar::ZERO;
+        }
+
+        n * p / denominator
+    };
+
+    (0..=degree)
+        .map(|k| {
+            let i = span - degree + k;
+
+            let mut derivative = Scalar::ZERO;
+            if k > 0 {
+                derivative += term(lower[k - 1], i, i + degree);
+            }
+            if k < degree {
+                derivative = derivative - term(lower[k], i + 1, i + degree + 1);
+            }
+
+            derivative
+        })
+        .collect()
+}
+
+/// Insert a knot into a B-spline, without changing its shape
+///
+/// Returns the new knot vector and the new control points.
+///
+/// This is algorithm A5.1 from The NURBS Book, restricted to inserting the knot
+/// once.
+pub fn insert_knot(
+    t: Scalar,
+    degree: usize,
+    knots: &[Scalar],
+    points: &[Vector<4>],
+) -> (Vec<Scalar>, Vec<Vector<4>>) {
+    let n = points.len() - 1;
+    let span = find_span(t, degree, n, knots);
+
+    let mut new_knots = knots.to_vec();
+    new_knots.insert(span + 1, t);
+
+    let new_points = (0..=n + 1)
+        .map(|i| {
+            if i + degree <= span {
+                points[i]
+            } else if i > span {
+                points[i - 1]
+            } else {
+                let alpha = (t - knots[i]) / (knots[i + degree] - knots[i]);
+                points[i] * alpha + points[i - 1] * (Scalar::ONE - alpha)
+            }
+        })
+        .collect();
+
+    (new_knots, new_points)
+}
+
+/// Convert a weighted control point into homogeneous coordinates
+pub fn to_homogeneous(point: Point<3>, weight: Scalar) -> Vector<4> {
+    Vector::from([point.x * weight, point.y * weight, point.z * weight, weight])
+}
+
+/// Convert a point in homogeneous coordinates into a weighted control point
+pub fn from_homogeneous(point: Vector<4>) -> (Point<3>, Scalar) {
+    let [x, y, z, w] = point.components;
+    (Point::from([x / w, y / w, z / w]), w)
+}
+
+/// Compute the derivative of a rational function from its homogeneous form
+///
+/// `point` is the point in homogeneous coordinates, `derivative` the derivative
+/// of the homogeneous coordinates.
+pub fn rational_derivative(
+    point: Vector<4>,
+    derivative: Vector<4>,
+) -> Vector<3> {
+    let [x, y, z, w] = point.components;
+    let [dx, dy, dz, dw] = derivative.components;
+
+    Vector::from([dx - dw * x / w, dy - dw * y / w, dz - dw * z / w]) / w
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+    use fj_math::Scalar;
+
+    #[test]
+    fn basis_functions_partition_unity() {
+        let knots = knots([0., 0., 0., 1., 2., 3., 3., 3.]);
+        let n = 4;
+
+        for t in [0., 0.5, 1., 1.7, 2.9, 3.] {
+            let t = Scalar::from(t);
+            let span = super::find_span(t, 2, n, &knots);
+
+            let sum = super::basis_functions(span, t, 2, &knots)
+                .into_iter()
+                .fold(Scalar::ZERO, |sum, n| sum + n);
+            assert_abs_diff_eq!(sum, Scalar::ONE, epsilon = 1e-12);
+
+            let sum = super::basis_derivatives(span, t, 2, &knots)
+                .into_iter()
+                .fold(Scalar::ZERO, |sum, n| sum + n);
+            assert_abs_diff_eq!(sum, Scalar::ZERO, epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn find_span() {
+        let knots = knots([0., 0., 0., 1., 2., 3., 3., 3.]);
+        let n = 4;
+
+        assert_eq!(super::find_span(Scalar::ZERO, 2, n, &knots), 2);
+        assert_eq!(super::find_span(Scalar::from(0.5), 2, n, &knots), 2);
+        assert_eq!(super::find_span(Scalar::ONE, 2, n, &knots), 3);
+        assert_eq!(super::find_span(Scalar::from(3.), 2, n, &knots), 4);
+    }
+
+    fn knots<const N: usize>(knots: [f64; N]) -> Vec<Scalar> {
+        knots.into_iter().map(Scalar::from).collect()
+    }
+}
@@ -57,7 +57,14 @@ impl Circle {
     /// error.
     pub fn point_model_to_curve(&self, point: &Point<3>) -> Point<1> {
         let v = point - self.center;
-        let atan = Scalar::atan2(v.y, v.x);
+
+        // Project the point into the plane of the circle, using the circle's
+        // own coordinate system. Using the model's x and y axes here would
+        // only work for circles in the xy-plane.
+        let x = v.dot(&self.a) / self.a.dot(&self.a);
+        let y = v.dot(&self.b) / self.b.dot(&self.b);
+
+        let atan = Scalar::atan2(y, x);
         let coord = if atan >= Scalar::ZERO {
             atan
         } else {
@@ -113,4 +120,26 @@ mod tests {
             Point::from([FRAC_PI_2 * 3.]),
         );
     }
+
+    #[test]
+    fn point_model_to_curve_outside_of_xy_plane() {
+        let circle = Circle {
+            center: Point::from([0., 0., 0.]),
+            a: Vector::from([0., 1., 0.]),
+            b: Vector::from([0., 0., 1.]),
+        };
+
+        assert_eq!(
+            circle.point_model_to_curve(&Point::from([0., 1., 0.])),
+            Point::from([0.]),
+        );
+        assert_eq!(
+            circle.point_model_to_curve(&Point::from([0., 0., 1.])),
+            Point::from([FRAC_PI_2]),
+        );
+        assert_eq!(
+            circle.point_model_to_curve(&Point::from([0., -1., 0.])),
+            Point::from([PI]),
+        );
+    }
 }
@@ -0,0 +1,148 @@
+use fj_math::{Point, Scalar, Transform, Vector};
+
+/// An ellipse
+///
+/// The curve coordinate is the eccentric anomaly, an angle that is defined
+/// like the angle of a [`Circle`], but with `a` and `b` being of different
+/// length.
+///
+/// [`Circle`]: super::Circle
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Ellipse {
+    /// The center point of the ellipse
+    pub center: Point<3>,
+
+    /// A vector from the center to the starting point of the ellipse
+    ///
+    /// The length of this vector defines the first semi-axis of the ellipse.
+    /// Please also refer to the documentation of `b`.
+    pub a: Vector<3>,
+
+    /// A vector from the center to the point at the curve coordinate `PI / 2.`
+    ///
+    /// The length of this vector defines the second semi-axis of the ellipse.
+    /// The vector must be perpendicular to `a`. Code working with ellipses
+    /// might assume that this condition is met.
+    pub b: Vector<3>,
+}
+
+impl Ellipse {
+    /// Access the origin of the curve's coordinate system
+    pub fn origin(&self) -> Point<3> {
+        self.center
+    }
+
+    /// Access the length of the longer of the two semi-axes
+    pub fn major_radius(&self) -> Scalar {
+        self.a.magnitude().max(self.b.magnitude())
+    }
+
+    /// Access the length of the shorter of the two semi-axes
+    pub fn minor_radius(&self) -> Scalar {
+        self.a.magnitude().min(self.b.magnitude())
+    }
+
+    /// Create a new instance that is reversed
+    #[must_use]
+    pub fn reverse(mut self) -> Self {
+        self.b = -self.b;
+        self
+    }
+
+    /// Create a new instance that is transformed by `transform`
+    #[must_use]
+    pub fn transform(self, transform: &Transform) -> Self {
+        Self {
+            center: transform.transform_point(&self.center),
+            a: transform.transform_vector(&self.a),
+            b: transform.transform_vector(&self.b),
+        }
+    }
+
+    /// Convert a point in model coordinates to curve coordinates
+    ///
+    /// Converts the provided point into curve coordinates between `0.`
+    /// (inclusive) and `PI * 2.` (exclusive).
+    ///
+    /// The point is scaled onto the ellipse along the direction from the
+    /// center, in the coordinate system defined by `a` and `b`. For points on
+    /// the ellipse, this is the exact inverse of
+    /// [`Ellipse::point_curve_to_model`]. For points that are not on the
+    /// ellipse, the result is not necessarily the closest point.
+    pub fn point_model_to_curve(&self, point: &Point<3>) -> Point<1> {
+        let v = point - self.center;
+
+        let x = v.dot(&self.a) / self.a.dot(&self.a);
+        let y = v.dot(&self.b) / self.b.dot(&self.b);
+
+        let atan = Scalar::atan2(y, x);
+        let coord = if atan >= Scalar::ZERO {
+            atan
+        } else {
+            atan + Scalar::PI * 2.
+        };
+        Point::from([coord])
+    }
+
+    /// Convert a point on the curve into model coordinates
+    pub fn point_curve_to_model(&self, point: &Point<1>) -> Point<3> {
+        self.center + self.vector_curve_to_model(&point.coords)
+    }
+
+    /// Convert a vector on the curve into model coordinates
+    pub fn vector_curve_to_model(&self, vector: &Vector<1>) -> Vector<3> {
+        let angle = vector.t;
+        let (sin, cos) = angle.sin_cos();
+
+        self.a * cos + self.b * sin
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_PI_2, PI};
+
+    use approx::assert_abs_diff_eq;
+    use fj_math::{Point, Vector};
+
+    use super::Ellipse;
+
+    #[test]
+    fn point_curve_to_model() {
+        let ellipse = ellipse();
+
+        assert_abs_diff_eq!(
+            ellipse.point_curve_to_model(&Point::from([0.])),
+            Point::from([3., 2., 3.]),
+            epsilon = 1e-12,
+        );
+        assert_abs_diff_eq!(
+            ellipse.point_curve_to_model(&Point::from([FRAC_PI_2])),
+            Point::from([1., 3., 3.]),
+            epsilon = 1e-12,
+        );
+    }
+
+    #[test]
+    fn point_model_to_curve() {
+        let ellipse = ellipse();
+
+        for t in [0., 0.3, FRAC_PI_2, PI, 4., 6.] {
+            let point = ellipse.point_curve_to_model(&Point::from([t]));
+
+            assert_abs_diff_eq!(
+                ellipse.point_model_to_curve(&point),
+                Point::from([t]),
+                epsilon = 1e-12,
+            );
+        }
+    }
+
+    fn ellipse() -> Ellipse {
+        Ellipse {
+            center: Point::from([1., 2., 3.]),
+            a: Vector::from([2., 0., 0.]),
+            b: Vector::from([0., 1., 0.]),
+        }
+    }
+}
@@ -1,9 +1,13 @@
 mod circle;
+mod ellipse;
 mod line;
+mod nurbs;
 
-pub use self::{circle::Circle, line::Line};
+pub use self::{
+    circle::Circle, ellipse::Ellipse, line::Line, nurbs::NurbsCurve,
+};
 
-use fj_math::{Point, Transform, Vector};
+use fj_math::{Point, Scalar, Transform, Vector};
 
 /// A one-dimensional shape
 ///
@@ -14,13 +18,19 @@ use fj_math::{Point, Transform, Vector};
 /// The nomenclature is inspired by Boundary Representation Modelling Techniques
 /// by Ian Stroud. "Curve" refers to unbounded one-dimensional geometry, while
 /// while edges are bounded portions of curves.
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub enum Curve {
     /// A circle
     Circle(Circle),
 
+    /// An ellipse
+    Ellipse(Ellipse),
+
     /// A line
     Line(Line),
+
+    /// A NURBS curve
+    Nurbs(NurbsCurve),
 }
 
 impl Curve {
@@ -52,7 +62,9 @@ impl Curve {
     pub fn origin(&self) -> Point<3> {
         match self {
             Self::Circle(curve) => curve.origin(),
+            Self::Ellipse(curve) => curve.origin(),
             Self::Line(curve) => curve.origin(),
+            Self::Nurbs(curve) => curve.origin(),
         }
     }
 
@@ -61,7 +73,9 @@ impl Curve {
     pub fn reverse(self) -> Self {
         match self {
             Self::Circle(curve) => Self::Circle(curve.reverse()),
+            Self::Ellipse(curve) => Self::Ellipse(curve.reverse()),
             Self::Line(curve) => Self::Line(curve.reverse()),
+            Self::Nurbs(curve) => Self::Nurbs(curve.reverse()),
         }
     }
 
@@ -70,10 +84,64 @@ impl Curve {
     pub fn transform(self, transform: &Transform) -> Self {
         match self {
             Self::Circle(curve) => Self::Circle(curve.transform(transform)),
+            Self::Ellipse(curve) => Self::Ellipse(curve.transform(transform)),
             Self::Line(curve) => Self::Line(curve.transform(transform)),
+            Self::Nurbs(curve) => Self::Nurbs(curve.transform(transform)),
+        }
+    }
+
+    /// Access the period of the curve coordinates, if the curve is periodic
+    ///
+    /// For periodic curves, like circles, curve coordinates that differ by a
+    /// multiple of the period refer to the same point in model coordinates.
+    pub fn period(&self) -> Option<Scalar> {
+        match self {
+            Self::Circle(_) | Self::Ellipse(_) => Some(Scalar::PI * 2.),
+            Self::Line(_) | Self::Nurbs(_) => None,
         }
     }
 
+    /// Access the range of curve coordinates that covers the whole curve
+    ///
+    /// Returns `None`, if the curve doesn't connect to itself. Only such curves
+    /// can be the curve of an edge that is not bounded by vertices.
+    pub fn closed_range(&self) -> Option<[Point<1>; 2]> {
+        match self {
+            Self::Circle(_) | Self::Ellipse(_) => Some([
+                Point::from([Scalar::ZERO]),
+                Point::from([Scalar::PI * 2.]),
+            ]),
+            Self::Line(_) => None,
+            Self::Nurbs(curve) => curve
+                .is_closed()
+                .then(|| curve.range().map(|t| Point::from([t]))),
+        }
+    }
+
+    /// Compute the range of curve coordinates between two points
+    ///
+    /// This is the range of curve coordinates that is covered by an edge that
+    /// is bounded by vertices at the given points. An edge goes from its first
+    /// to its second vertex, in the direction of the curve.
+    ///
+    /// For periodic curves, this means that the end of the range is always
+    /// larger than the start. For example, an arc of a circle that starts at
+    /// the curve coordinate `PI * 1.5` and ends at `PI * 0.5` is represented by
+    /// the range from `PI * 1.5` to `PI * 2.5`. If both points are identical,
+    /// the range covers the full period.
+    pub fn range_between(&self, [a, b]: [Point<3>; 2]) -> [Point<1>; 2] {
+        let start = self.point_model_to_curve(&a);
+        let mut end = self.point_model_to_curve(&b);
+
+        if let Some(period) = self.period() {
+            while end.t <= start.t {
+                end.t += period;
+            }
+        }
+
+        [start, end]
+    }
+
     /// Convert a point in model coordinates to curve coordinates
     ///
     /// Projects the point onto the curve before computing curve coordinate.
@@ -86,7 +154,9 @@ impl Curve {
     pub fn point_model_to_curve(&self, point: &Point<3>) -> Point<1> {
         match self {
             Self::Circle(curve) => curve.point_model_to_curve(point),
+            Self::Ellipse(curve) => curve.point_model_to_curve(point),
             Self::Line(curve) => curve.point_model_to_curve(point),
+            Self::Nurbs(curve) => curve.point_model_to_curve(point),
         }
     }
 
@@ -94,7 +164,9 @@ impl Curve {
     pub fn point_curve_to_model(&self, point: &Point<1>) -> Point<3> {
         match self {
             Self::Circle(curve) => curve.point_curve_to_model(point),
+            Self::Ellipse(curve) => curve.point_curve_to_model(point),
             Self::Line(curve) => curve.point_curve_to_model(point),
+            Self::Nurbs(curve) => curve.point_curve_to_model(point),
         }
     }
 
@@ -102,7 +174,9 @@ impl Curve {
     pub fn vector_curve_to_model(&self, point: &Vector<1>) -> Vector<3> {
         match self {
             Self::Circle(curve) => curve.vector_curve_to_model(point),
+            Self::Ellipse(curve) => curve.vector_curve_to_model(point),
             Self::Line(curve) => curve.vector_curve_to_model(point),
+            Self::Nurbs(curve) => curve.vector_curve_to_model(point),
         }
     }
 }
@@ -0,0 +1,364 @@
+use fj_math::{Point, Scalar, Transform, Vector};
+
+use crate::geometry::bspline;
+
+/// A NURBS curve
+///
+/// Non-uniform rational B-splines can represent freeform curves, as well as
+/// all conic sections. A non-rational B-spline is a NURBS curve where all
+/// weights are equal.
+///
+/// The curve coordinate is the parameter of the spline. The curve is defined
+/// within the range returned by [`NurbsCurve::range`].
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct NurbsCurve {
+    /// The degree of the curve
+    pub degree: usize,
+
+    /// The control points of the curve
+    pub control_points: Vec<Point<3>>,
+
+    /// The weights of the control points
+    ///
+    /// Must contain one weight for each control point. All weights must be
+    /// positive.
+    pub weights: Vec<Scalar>,
+
+    /// The knot vector of the curve
+    ///
+    /// Must be non-decreasing, and contain `control_points.len() + degree + 1`
+    /// knots. Code working with NURBS curves might assume that these conditions
+    /// are met.
+    pub knots: Vec<Scalar>,
+}
+
+impl NurbsCurve {
+    /// Construct a non-rational B-spline with a clamped, uniform knot vector
+    ///
+    /// The resulting curve starts at the first control point, ends at the last
+    /// one, and is defined between the curve coordinates `0.` and `1.`.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if there are not more control points than `degree`.
+    pub fn clamped(degree: usize, control_points: Vec<Point<3>>) -> Self {
+        assert!(
+            control_points.len() > degree,
+            "B-spline of degree {} needs at least {} control points",
+            degree,
+            degree + 1,
+        );
+
+        let weights = vec![Scalar::ONE; control_points.len()];
+        let knots = clamped_uniform_knots(degree, control_points.len());
+
+        Self {
+            degree,
+            control_points,
+            weights,
+            knots,
+        }
+    }
+
+    /// Access the origin of the curve's coordinate system
+    ///
+    /// This is the point at the start of the parameter range.
+    pub fn origin(&self) -> Point<3> {
+        let [start, _] = self.range();
+        self.point_curve_to_model(&Point::from([start]))
+    }
+
+    /// Access the range of curve coordinates in which the curve is defined
+    pub fn range(&self) -> [Scalar; 2] {
+        bspline::parameter_range(self.degree, &self.knots)
+    }
+
+    /// Indicate whether the curve connects to itself
+    pub fn is_closed(&self) -> bool {
+        self.control_points.first() == self.control_points.last()
+    }
+
+    /// Create a new instance that is reversed
+    ///
+    /// The reversed curve is defined within the same range of curve
+    /// coordinates.
+    #[must_use]
+    pub fn reverse(mut self) -> Self {
+        let [start, end] = self.range();
+
+        self.control_points.reverse();
+        self.weights.reverse();
+        self.knots = self
+            .knots
+            .iter()
+            .rev()
+            .map(|&knot| start + end - knot)
+            .collect();
+
+        self
+    }
+
+    /// Create a new instance that is transformed by `transform`
+    #[must_use]
+    pub fn transform(mut self, transform: &Transform) -> Self {
+        for point in &mut self.control_points {
+            *point = transform.transform_point(point);
+        }
+
+        self
+    }
+
+    /// Create a new instance with an additional knot at `t`
+    ///
+    /// The shape and parametrization of the curve are not changed.
+    #[must_use]
+    pub fn insert_knot(&self, t: Scalar) -> Self {
+        let (knots, points) = bspline::insert_knot(
+            t,
+            self.degree,
+            &self.knots,
+            &self.homogeneous(),
+        );
+
+        let (control_points, weights) =
+            points.into_iter().map(bspline::from_homogeneous).unzip();
+
+        Self {
+            degree: self.degree,
+            control_points,
+            weights,
+            knots,
+        }
+    }
+
+    /// Convert a point in model coordinates to curve coordinates
+    ///
+    /// Finds the point on the curve that is closest to the given point, using a
+    /// coarse sampling of the curve, followed by Newton iteration. The result
+    /// is always within the range of the curve.
+    pub fn point_model_to_curve(&self, point: &Point<3>) -> Point<1> {
+        let [start, end] = self.range();
+
+        let num_samples = self.control_points.len().max(2) * 8;
+        let step = (end - start) / num_samples as f64;
+
+        let distance = |t: Scalar| {
+            (self.point_curve_to_model(&Point::from([t])) - *point).magnitude()
+        };
+
+        let mut t = (0..=num_samples)
+            .map(|i| start + step * i as f64)
+            .min_by_key(|&t| distance(t))
+            // Can't panic. The range is never empty.
+            .unwrap();
+
+        for _ in 0..MAX_ITERATIONS {
+            let (position, tangent) = self.evaluate(t);
+
+            let denominator = tangent.dot(&tangent);
+            if denominator == Scalar::ZERO {
+                break;
+            }
+
+            let delta = tangent.dot(&(position - *point)) / denominator;
+            let next = (t - delta).max(start).min(end);
+
+            if (next - t).abs() <= Scalar::from(f64::EPSILON) {
+                break;
+            }
+
+            t = next;
+        }
+
+        Point::from([t])
+    }
+
+    /// Convert a point on the curve into model coordinates
+    pub fn point_curve_to_model(&self, point: &Point<1>) -> Point<3> {
+        let (point, _) = self.evaluate(point.t);
+        point
+    }
+
+    /// Convert a vector on the curve into model coordinates
+    ///
+    /// The vector is interpreted relative to the start of the parameter range,
+    /// i.e. the result is the vector from [`NurbsCurve::origin`] to the point
+    /// at the curve coordinate defined by the vector.
+    pub fn vector_curve_to_model(&self, vector: &Vector<1>) -> Vector<3> {
+        self.point_curve_to_model(&Point { coords: *vector }) - self.origin()
+    }
+
+    /// Compute the point and the tangent of the curve at `t`
+    ///
+    /// The tangent is the first derivative of the curve with respect to `t`.
+    pub fn evaluate(&self, t: Scalar) -> (Point<3>, Vector<3>) {
+        let n = self.control_points.len() - 1;
+        let span = bspline::find_span(t, self.degree, n, &self.knots);
+
+        let basis = bspline::basis_functions(span, t, self.degree, &self.knots);
+        let derivatives =
+            bspline::basis_derivatives(span, t, self.degree, &self.knots);
+
+        let homogeneous = self.homogeneous();
+
+        let mut point = Vector::from([0.; 4]);
+        let mut derivative = Vector::from([0.; 4]);
+
+        for k in 0..=self.degree {
+            let control_point = homogeneous[span - self.degree + k];
+
+            point = point + control_point * basis[k];
+            derivative = derivative + control_point * derivatives[k];
+        }
+
+        let tangent = bspline::rational_derivative(point, derivative);
+        let (point, _) = bspline::from_homogeneous(point);
+
+        (point, tangent)
+    }
+
+    fn homogeneous(&self) -> Vec<Vector<4>> {
+        self.control_points
+            .iter()
+            .zip(&self.weights)
+            .map(|(&point, &weight)| bspline::to_homogeneous(point, weight))
+            .collect()
+    }
+}
+
+/// Compute a clamped, uniform knot vector for the given number of points
+///
+/// The resulting knot vector defines a parameter range from `0.` to `1.`.
+pub fn clamped_uniform_knots(degree: usize, num_points: usize) -> Vec<Scalar> {
+    let num_spans = num_points - degree;
+
+    let mut knots = vec![Scalar::ZERO; degree];
+    knots.extend(
+        (0..=num_spans).map(|i| Scalar::from_u64(i as u64) / num_spans as f64),
+    );
+    knots.extend(vec![Scalar::ONE; degree]);
+
+    knots
+}
+
+/// The maximum number of iterations when projecting points onto the curve
+const MAX_ITERATIONS: usize = 32;
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_1_SQRT_2;
+
+    use approx::assert_abs_diff_eq;
+    use fj_math::{Point, Scalar, Transform, Vector};
+
+    use super::NurbsCurve;
+
+    #[test]
+    fn evaluate_quarter_circle() {
+        let curve = quarter_circle();
+
+        for t in [0., 0.25, 0.5, 0.75, 1.] {
+            let point = curve.point_curve_to_model(&Point::from([t]));
+            assert_abs_diff_eq!(
+                point.coords.magnitude(),
+                Scalar::ONE,
+                epsilon = 1e-12,
+            );
+        }
+
+        assert_abs_diff_eq!(
+            curve.point_curve_to_model(&Point::from([0.5])),
+            Point::from([FRAC_1_SQRT_2, FRAC_1_SQRT_2, 0.]),
+            epsilon = 1e-12,
+        );
+    }
+
+    #[test]
+    fn point_model_to_curve() {
+        let curve = NurbsCurve::clamped(
+            3,
+            vec![
+                Point::from([0., 0., 0.]),
+                Point::from([1., 2., 0.]),
+                Point::from([3., -1., 1.]),
+                Point::from([4., 1., 0.]),
+                Point::from([5., 0., 2.]),
+            ],
+        );
+
+        for t in [0., 0.1, 0.5, 0.73, 1.] {
+            let point = curve.point_curve_to_model(&Point::from([t]));
+            assert_abs_diff_eq!(
+                curve.point_model_to_curve(&point),
+                Point::from([t]),
+                epsilon = 1e-9,
+            );
+        }
+    }
+
+    #[test]
+    fn insert_knot() {
+        let curve = quarter_circle();
+        let refined = curve.insert_knot(Scalar::from(0.3));
+
+        assert_eq!(refined.knots.len(), curve.knots.len() + 1);
+        assert_eq!(
+            refined.control_points.len(),
+            curve.control_points.len() + 1
+        );
+
+        for t in [0., 0.2, 0.3, 0.6, 1.] {
+            let t = Point::from([t]);
+            assert_abs_diff_eq!(
+                refined.point_curve_to_model(&t),
+                curve.point_curve_to_model(&t),
+                epsilon = 1e-12,
+            );
+        }
+    }
+
+    #[test]
+    fn reverse() {
+        let curve = quarter_circle();
+        let reversed = curve.clone().reverse();
+
+        for t in [0., 0.2, 0.6, 1.] {
+            assert_abs_diff_eq!(
+                reversed.point_curve_to_model(&Point::from([1. - t])),
+                curve.point_curve_to_model(&Point::from([t])),
+                epsilon = 1e-12,
+            );
+        }
+    }
+
+    #[test]
+    fn transform() {
+        let curve = quarter_circle();
+        let transform = Transform::translation([1., 2., 3.]);
+        let transformed = curve.clone().transform(&transform);
+
+        let t = Point::from([0.4]);
+        assert_abs_diff_eq!(
+            transformed.point_curve_to_model(&t),
+            curve.point_curve_to_model(&t) + Vector::from([1., 2., 3.]),
+            epsilon = 1e-12,
+        );
+    }
+
+    fn quarter_circle() -> NurbsCurve {
+        NurbsCurve {
+            degree: 2,
+            control_points: vec![
+                Point::from([1., 0., 0.]),
+                Point::from([1., 1., 0.]),
+                Point::from([0., 1., 0.]),
+            ],
+            weights: vec![
+                Scalar::ONE,
+                Scalar::from(FRAC_1_SQRT_2),
+                Scalar::ONE,
+            ],
+            knots: [0., 0., 0., 1., 1., 1.].map(Scalar::from).to_vec(),
+        }
+    }
+}
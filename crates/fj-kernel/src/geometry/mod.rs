@@ -5,12 +5,15 @@
 //! [`crate::topology`], which are responsible for defining how objects are
 //! related.
 
+mod bspline;
 mod curves;
 mod points;
 mod surfaces;
 
 pub use self::{
-    curves::{Circle, Curve, Line},
+    curves::{Circle, Curve, Ellipse, Line, NurbsCurve},
     points::Point,
-    surfaces::{Surface, SweptCurve},
+    surfaces::{
+        Cone, Cylinder, NurbsSurface, Sphere, Surface, SweptCurve, Torus,
+    },
 };
@@ -0,0 +1,130 @@
+use fj_math::{Point, Transform, Vector};
+
+use super::cylinder::angle;
+
+/// A cone
+///
+/// The surface coordinate `u` is the angle around the axis, `v` is the
+/// position along the axis. The apex of the cone is at `v == 0`, and the
+/// circle defined by `a` and `b` is at `v == 1`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Cone {
+    /// The apex of the cone
+    pub apex: Point<3>,
+
+    /// A vector from the center of the circle at `v == 1` to the point at
+    /// `u == 0` on that circle
+    ///
+    /// The length of this vector defines the radius of that circle. Please also
+    /// refer to the documentation of `b`.
+    pub a: Vector<3>,
+
+    /// A second vector that defines the direction of `u`
+    ///
+    /// The vector must be of equal length to `a` and must be perpendicular to
+    /// it and to `axis`. Code working with cones might assume that these
+    /// conditions are met.
+    pub b: Vector<3>,
+
+    /// The axis of the cone, from the apex to the center of the circle at
+    /// `v == 1`
+    pub axis: Vector<3>,
+}
+
+impl Cone {
+    /// Create a new instance that is reversed
+    ///
+    /// Reversing the cone flips the direction of `u`, and with it the direction
+    /// of the surface normal.
+    #[must_use]
+    pub fn reverse(mut self) -> Self {
+        self.b = -self.b;
+        self
+    }
+
+    /// Transform the surface
+    #[must_use]
+    pub fn transform(self, transform: &Transform) -> Self {
+        Self {
+            apex: transform.transform_point(&self.apex),
+            a: transform.transform_vector(&self.a),
+            b: transform.transform_vector(&self.b),
+            axis: transform.transform_vector(&self.axis),
+        }
+    }
+
+    /// Convert a point in model coordinates to surface coordinates
+    ///
+    /// The `u` coordinate is between `0.` (inclusive) and `PI * 2.`
+    /// (exclusive). The point is projected onto the cone along the radial
+    /// direction.
+    ///
+    /// The `u` coordinate of the apex itself is not defined. `0.` is returned
+    /// in that case.
+    pub fn point_model_to_surface(&self, point: &Point<3>) -> Point<2> {
+        let d = point - self.apex;
+
+        let v = d.dot(&self.axis) / self.axis.dot(&self.axis);
+        let r = d - self.axis * v;
+        let u = angle(r.dot(&self.a), r.dot(&self.b));
+
+        Point::from([u, v])
+    }
+
+    /// Convert a point in surface coordinates to model coordinates
+    pub fn point_surface_to_model(&self, point: &Point<2>) -> Point<3> {
+        self.apex + self.offset(&point.coords)
+    }
+
+    /// Compute the offset from the apex to the point at the given coordinates
+    fn offset(&self, vector: &Vector<2>) -> Vector<3> {
+        let (sin, cos) = vector.u.sin_cos();
+        (self.a * cos + self.b * sin + self.axis) * vector.v
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use approx::assert_abs_diff_eq;
+    use fj_math::{Point, Vector};
+
+    use super::Cone;
+
+    #[test]
+    fn point_model_to_surface() {
+        let cone = cone();
+
+        verify(&cone, Point::from([0., 1.]));
+        verify(&cone, Point::from([1., 0.5]));
+        verify(&cone, Point::from([PI, 2.]));
+
+        fn verify(cone: &Cone, surface_point: Point<2>) {
+            let point = cone.point_surface_to_model(&surface_point);
+            let result = cone.point_model_to_surface(&point);
+
+            assert_abs_diff_eq!(result, surface_point, epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn point_surface_to_model() {
+        let cone = cone();
+
+        assert_abs_diff_eq!(
+            cone.point_surface_to_model(&Point::from([PI, 0.5])),
+            Point::from([-0.5, 0., 1.]),
+            epsilon = 1e-12,
+        );
+    }
+
+    fn cone() -> Cone {
+        Cone {
+            apex: Point::from([0., 0., 0.]),
+            a: Vector::from([1., 0., 0.]),
+            b: Vector::from([0., 1., 0.]),
+            axis: Vector::from([0., 0., 2.]),
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use fj_math::{Point, Scalar, Transform, Vector};
+
+/// A cylinder
+///
+/// The surface coordinate `u` is the angle around the axis, `v` is the
+/// position along the axis.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Cylinder {
+    /// The center of the circle at `v == 0`
+    pub center: Point<3>,
+
+    /// A vector from the center to the point at `u == 0`
+    ///
+    /// The length of this vector defines the radius of the cylinder. Please
+    /// also refer to the documentation of `b`.
+    pub a: Vector<3>,
+
+    /// A second vector that defines the direction of `u`
+    ///
+    /// The vector must be of equal length to `a` (the cylinder radius) and must
+    /// be perpendicular to it and to `axis`. Code working with cylinders might
+    /// assume that these conditions are met.
+    pub b: Vector<3>,
+
+    /// The axis of the cylinder
+    ///
+    /// The length of this vector defines the unit of the `v` coordinate.
+    pub axis: Vector<3>,
+}
+
+impl Cylinder {
+    /// Access the radius of the cylinder
+    pub fn radius(&self) -> Scalar {
+        self.a.magnitude()
+    }
+
+    /// Create a new instance that is reversed
+    ///
+    /// Reversing the cylinder flips the direction of `u`, and with it the
+    /// direction of the surface normal.
+    #[must_use]
+    pub fn reverse(mut self) -> Self {
+        self.b = -self.b;
+        self
+    }
+
+    /// Transform the surface
+    #[must_use]
+    pub fn transform(self, transform: &Transform) -> Self {
+        Self {
+            center: transform.transform_point(&self.center),
+            a: transform.transform_vector(&self.a),
+            b: transform.transform_vector(&self.b),
+            axis: transform.transform_vector(&self.axis),
+        }
+    }
+
+    /// Convert a point in model coordinates to surface coordinates
+    ///
+    /// The `u` coordinate is between `0.` (inclusive) and `PI * 2.`
+    /// (exclusive). The point is projected onto the cylinder, ignoring its
+    /// distance from the axis.
+    pub fn point_model_to_surface(&self, point: &Point<3>) -> Point<2> {
+        let d = point - self.center;
+
+        let v = d.dot(&self.axis) / self.axis.dot(&self.axis);
+        let r = d - self.axis * v;
+        let u = angle(r.dot(&self.a), r.dot(&self.b));
+
+        Point::from([u, v])
+    }
+
+    /// Convert a point in surface coordinates to model coordinates
+    pub fn point_surface_to_model(&self, point: &Point<2>) -> Point<3> {
+        self.center + self.offset(&point.coords)
+    }
+
+    /// Compute the offset from the center to the point at the given coordinates
+    fn offset(&self, vector: &Vector<2>) -> Vector<3> {
+        let (sin, cos) = vector.u.sin_cos();
+        self.a * cos + self.b * sin + self.axis * vector.v
+    }
+}
+
+/// Compute an angle between `0.` (inclusive) and `PI * 2.` (exclusive)
+pub(super) fn angle(x: Scalar, y: Scalar) -> Scalar {
+    let full_circle = Scalar::PI * 2.;
+    let angle = Scalar::atan2(y, x).into_f64();
+    let angle = Scalar::from_f64(angle.rem_euclid(full_circle.into_f64()));
+
+    // For tiny negative angles, the result of `rem_euclid` can be rounded up
+    // to the divisor.
+    if angle >= full_circle {
+        Scalar::ZERO
+    } else {
+        angle
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_2;
+
+    use approx::assert_abs_diff_eq;
+    use fj_math::{Point, Scalar, Vector};
+
+    use super::{angle, Cylinder};
+
+    #[test]
+    fn point_model_to_surface() {
+        let cylinder = cylinder();
+
+        verify(&cylinder, Point::from([0., 0.]));
+        verify(&cylinder, Point::from([1., 0.5]));
+        verify(&cylinder, Point::from([FRAC_PI_2 * 3., -2.]));
+
+        fn verify(cylinder: &Cylinder, surface_point: Point<2>) {
+            let point = cylinder.point_surface_to_model(&surface_point);
+            let result = cylinder.point_model_to_surface(&point);
+
+            assert_abs_diff_eq!(result, surface_point, epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn point_surface_to_model() {
+        let cylinder = cylinder();
+
+        assert_abs_diff_eq!(
+            cylinder.point_surface_to_model(&Point::from([FRAC_PI_2, 1.])),
+            Point::from([1., 2., 5.]),
+            epsilon = 1e-12,
+        );
+    }
+
+    #[test]
+    fn angle_is_below_full_circle() {
+        let angle = angle(Scalar::ONE, Scalar::from_f64(-1e-20));
+        assert!(angle >= Scalar::ZERO);
+        assert!(angle < Scalar::PI * 2.);
+    }
+
+    fn cylinder() -> Cylinder {
+        Cylinder {
+            center: Point::from([1., 0., 3.]),
+            a: Vector::from([2., 0., 0.]),
+            b: Vector::from([0., 2., 0.]),
+            axis: Vector::from([0., 0., 2.]),
+        }
+    }
+}
@@ -1,18 +1,41 @@
+pub mod cone;
+pub mod cylinder;
+pub mod nurbs;
+pub mod sphere;
 pub mod swept;
+pub mod torus;
 
-pub use self::swept::SweptCurve;
+pub use self::{
+    cone::Cone, cylinder::Cylinder, nurbs::NurbsSurface, sphere::Sphere,
+    swept::SweptCurve, torus::Torus,
+};
 
-use fj_math::{Point, Transform, Vector};
+use fj_math::{Point, Scalar, Transform, Vector};
 
 use crate::geometry;
 
 use super::Curve;
 
 /// A two-dimensional shape
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub enum Surface {
+    /// A cone
+    Cone(Cone),
+
+    /// A cylinder
+    Cylinder(Cylinder),
+
+    /// A NURBS surface
+    Nurbs(NurbsSurface),
+
+    /// A sphere
+    Sphere(Sphere),
+
     /// A swept curve
     SweptCurve(SweptCurve),
+
+    /// A torus
+    Torus(Torus),
 }
 
 impl Surface {
@@ -44,7 +67,12 @@ impl Surface {
     #[must_use]
     pub fn reverse(self) -> Self {
         match self {
+            Self::Cone(surface) => Self::Cone(surface.reverse()),
+            Self::Cylinder(surface) => Self::Cylinder(surface.reverse()),
+            Self::Nurbs(surface) => Self::Nurbs(surface.reverse()),
+            Self::Sphere(surface) => Self::Sphere(surface.reverse()),
             Self::SweptCurve(surface) => Self::SweptCurve(surface.reverse()),
+            Self::Torus(surface) => Self::Torus(surface.reverse()),
         }
     }
 
@@ -52,9 +80,77 @@ impl Surface {
     #[must_use]
     pub fn transform(self, transform: &Transform) -> Self {
         match self {
+            Self::Cone(surface) => Self::Cone(surface.transform(transform)),
+            Self::Cylinder(surface) => {
+                Self::Cylinder(surface.transform(transform))
+            }
+            Self::Nurbs(surface) => Self::Nurbs(surface.transform(transform)),
+            Self::Sphere(surface) => Self::Sphere(surface.transform(transform)),
             Self::SweptCurve(surface) => {
                 Self::SweptCurve(surface.transform(transform))
             }
+            Self::Torus(surface) => Self::Torus(surface.transform(transform)),
+        }
+    }
+
+    /// Indicate whether the surface is a plane
+    pub fn is_plane(&self) -> bool {
+        matches!(
+            self,
+            Self::SweptCurve(SweptCurve {
+                curve: Curve::Line(_),
+                ..
+            })
+        )
+    }
+
+    /// Access the periods of the surface coordinates
+    ///
+    /// Returns the period of the `u` and `v` coordinates, if the respective
+    /// coordinate wraps around. For example, the `u` coordinate of a cylinder
+    /// is an angle, so points that differ by `PI * 2.` in `u` refer to the same
+    /// point in model coordinates.
+    ///
+    /// [`Surface::point_model_to_surface`] always returns coordinates within
+    /// the first period, starting at `0.`.
+    pub fn periods(&self) -> [Option<Scalar>; 2] {
+        let full_circle = Some(Scalar::PI * 2.);
+
+        match self {
+            Self::Cone(_) | Self::Cylinder(_) | Self::Sphere(_) => {
+                [full_circle, None]
+            }
+            Self::SweptCurve(SweptCurve {
+                curve: Curve::Circle(_),
+                ..
+            }) => [full_circle, None],
+            Self::Nurbs(_) | Self::SweptCurve(_) => [None, None],
+            Self::Torus(_) => [full_circle, full_circle],
+        }
+    }
+
+    /// Access the bounds of the surface coordinates, if the surface is closed
+    ///
+    /// A closed surface, like a sphere or a torus, can be covered by a single
+    /// face without any boundary. Returns the minimum and maximum surface
+    /// coordinates of such a face. Returns `None`, if the surface is not
+    /// closed.
+    pub fn closed_bounds(&self) -> Option<[Point<2>; 2]> {
+        let two_pi = Scalar::PI * 2.;
+
+        match self {
+            Self::Sphere(_) => {
+                let half_pi = Scalar::PI / 2.;
+                Some([
+                    Point::from([Scalar::ZERO, -half_pi]),
+                    Point::from([two_pi, half_pi]),
+                ])
+            }
+            Self::Torus(_) => Some([
+                Point::from([Scalar::ZERO, Scalar::ZERO]),
+                Point::from([two_pi, two_pi]),
+            ]),
+            _ => None,
         }
     }
 
@@ -64,9 +160,16 @@ impl Surface {
         point_3d: Point<3>,
     ) -> geometry::Point<2> {
         let point_2d = match self {
+            Self::Cone(surface) => surface.point_model_to_surface(&point_3d),
+            Self::Cylinder(surface) => {
+                surface.point_model_to_surface(&point_3d)
+            }
+            Self::Nurbs(surface) => surface.point_model_to_surface(&point_3d),
+            Self::Sphere(surface) => surface.point_model_to_surface(&point_3d),
             Self::SweptCurve(surface) => {
                 surface.point_model_to_surface(&point_3d)
             }
+            Self::Torus(surface) => surface.point_model_to_surface(&point_3d),
         };
 
         geometry::Point::new(point_2d, point_3d)
@@ -75,16 +178,29 @@ impl Surface {
     /// Convert a point in surface coordinates to model coordinates
     pub fn point_surface_to_model(&self, point: &Point<2>) -> Point<3> {
         match self {
+            Self::Cone(surface) => surface.point_surface_to_model(point),
+            Self::Cylinder(surface) => surface.point_surface_to_model(point),
+            Self::Nurbs(surface) => surface.point_surface_to_model(point),
+            Self::Sphere(surface) => surface.point_surface_to_model(point),
             Self::SweptCurve(surface) => surface.point_surface_to_model(point),
+            Self::Torus(surface) => surface.point_surface_to_model(point),
         }
     }
 
     /// Convert a vector in surface coordinates to model coordinates
-    pub fn vector_surface_to_model(&self, vector: &Vector<2>) -> Vector<3> {
+    ///
+    /// Returns `None`, if the surface is not a plane. Only on a plane does a
+    /// vector in surface coordinates correspond to the same vector in model
+    /// coordinates, regardless of where it starts.
+    pub fn vector_surface_to_model(
+        &self,
+        vector: &Vector<2>,
+    ) -> Option<Vector<3>> {
         match self {
-            Self::SweptCurve(surface) => {
-                surface.vector_surface_to_model(vector)
+            Self::SweptCurve(surface) if self.is_plane() => {
+                Some(surface.vector_surface_to_model(vector))
             }
+            _ => None,
         }
     }
 }
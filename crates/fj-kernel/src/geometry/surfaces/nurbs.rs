@@ -0,0 +1,360 @@
+use fj_math::{Point, Scalar, Transform, Vector};
+
+use crate::geometry::bspline;
+
+/// A NURBS surface
+///
+/// The surface is the tensor product of two NURBS curves. The surface
+/// coordinates are the parameters of the spline in both directions. The surface
+/// is defined within the range returned by [`NurbsSurface::range`].
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct NurbsSurface {
+    /// The degree of the surface in `u` and `v` direction
+    pub degree: [usize; 2],
+
+    /// The control net of the surface
+    ///
+    /// `control_points[i][j]` is the control point with index `i` in `u`
+    /// direction and index `j` in `v` direction. All rows must have the same
+    /// length.
+    pub control_points: Vec<Vec<Point<3>>>,
+
+    /// The weights of the control points
+    ///
+    /// Must have the same layout as `control_points`. All weights must be
+    /// positive.
+    pub weights: Vec<Vec<Scalar>>,
+
+    /// The knot vectors of the surface in `u` and `v` direction
+    ///
+    /// Both must be non-decreasing, and contain the number of control points
+    /// in the respective direction, plus the degree, plus one. Code working
+    /// with NURBS surfaces might assume that these conditions are met.
+    pub knots: [Vec<Scalar>; 2],
+}
+
+impl NurbsSurface {
+    /// Access the range of surface coordinates in which the surface is defined
+    pub fn range(&self) -> [Point<2>; 2] {
+        let [u_start, u_end] =
+            bspline::parameter_range(self.degree[0], &self.knots[0]);
+        let [v_start, v_end] =
+            bspline::parameter_range(self.degree[1], &self.knots[1]);
+
+        [Point::from([u_start, v_start]), Point::from([u_end, v_end])]
+    }
+
+    /// Create a new instance that is reversed
+    ///
+    /// Reversing the surface flips the direction of `u`, and with it the
+    /// direction of the surface normal.
+    #[must_use]
+    pub fn reverse(mut self) -> Self {
+        let [start, end] =
+            bspline::parameter_range(self.degree[0], &self.knots[0]);
+
+        self.control_points.reverse();
+        self.weights.reverse();
+        self.knots[0] = self.knots[0]
+            .iter()
+            .rev()
+            .map(|&knot| start + end - knot)
+            .collect();
+
+        self
+    }
+
+    /// Transform the surface
+    #[must_use]
+    pub fn transform(mut self, transform: &Transform) -> Self {
+        for point in self.control_points.iter_mut().flatten() {
+            *point = transform.transform_point(point);
+        }
+
+        self
+    }
+
+    /// Create a new instance with an additional knot at `t`
+    ///
+    /// `axis` selects the direction of the knot: `0` for `u`, `1` for `v`. The
+    /// shape and parametrization of the surface are not changed.
+    #[must_use]
+    pub fn insert_knot(&self, axis: usize, t: Scalar) -> Self {
+        let homogeneous = self.homogeneous();
+        let degree = self.degree[axis];
+
+        let mut knots = self.knots.clone();
+        let mut rows: Vec<Vec<Vector<4>>> = Vec::new();
+
+        match axis {
+            0 => {
+                let num_columns = homogeneous[0].len();
+                let mut columns = Vec::new();
+
+                for j in 0..num_columns {
+                    let column: Vec<_> =
+                        homogeneous.iter().map(|row| row[j]).collect();
+                    let (new_knots, column) = bspline::insert_knot(
+                        t,
+                        degree,
+                        &self.knots[0],
+                        &column,
+                    );
+
+                    knots[0] = new_knots;
+                    columns.push(column);
+                }
+
+                for i in 0..columns[0].len() {
+                    rows.push(columns.iter().map(|column| column[i]).collect());
+                }
+            }
+            _ => {
+                for row in &homogeneous {
+                    let (new_knots, row) =
+                        bspline::insert_knot(t, degree, &self.knots[1], row);
+
+                    knots[1] = new_knots;
+                    rows.push(row);
+                }
+            }
+        }
+
+        let (control_points, weights) = rows
+            .into_iter()
+            .map(|row| row.into_iter().map(bspline::from_homogeneous).unzip())
+            .unzip();
+
+        Self {
+            degree: self.degree,
+            control_points,
+            weights,
+            knots,
+        }
+    }
+
+    /// Convert a point in model coordinates to surface coordinates
+    ///
+    /// Finds the point on the surface that is closest to the given point, using
+    /// a coarse sampling of the surface, followed by Gauss-Newton iteration.
+    /// The result is always within the range of the surface.
+    pub fn point_model_to_surface(&self, point: &Point<3>) -> Point<2> {
+        let [start, end] = self.range();
+
+        let num_samples = [
+            self.control_points.len().max(2) * 4,
+            self.control_points[0].len().max(2) * 4,
+        ];
+        let sample = |axis: usize, i: usize| {
+            let start = start.coords.components[axis];
+            let end = end.coords.components[axis];
+
+            start + (end - start) * (i as f64 / num_samples[axis] as f64)
+        };
+
+        let distance = |uv: Point<2>| {
+            (self.point_surface_to_model(&uv) - *point).magnitude()
+        };
+
+        let mut uv = (0..=num_samples[0])
+            .flat_map(|i| {
+                (0..=num_samples[1])
+                    .map(move |j| Point::from([sample(0, i), sample(1, j)]))
+            })
+            .min_by_key(|&uv| distance(uv))
+            // Can't panic. There's always at least one sample.
+            .unwrap();
+
+        for _ in 0..MAX_ITERATIONS {
+            let (position, [du, dv]) = self.evaluate(uv);
+            let error = *point - position;
+
+            // Solve the normal equations of the linearized problem.
+            let a = du.dot(&du);
+            let b = du.dot(&dv);
+            let c = dv.dot(&dv);
+            let determinant = a * c - b * b;
+            if determinant == Scalar::ZERO {
+                break;
+            }
+
+            let ru = du.dot(&error);
+            let rv = dv.dot(&error);
+
+            let delta_u = (c * ru - b * rv) / determinant;
+            let delta_v = (a * rv - b * ru) / determinant;
+
+            let next = Point::from([
+                (uv.u + delta_u).max(start.u).min(end.u),
+                (uv.v + delta_v).max(start.v).min(end.v),
+            ]);
+
+            let converged =
+                (next - uv).magnitude() <= Scalar::from(f64::EPSILON);
+            uv = next;
+
+            if converged {
+                break;
+            }
+        }
+
+        uv
+    }
+
+    /// Convert a point in surface coordinates to model coordinates
+    pub fn point_surface_to_model(&self, point: &Point<2>) -> Point<3> {
+        let (point, _) = self.evaluate(*point);
+        point
+    }
+
+    /// Compute the point and the partial derivatives of the surface at `point`
+    pub fn evaluate(&self, point: Point<2>) -> (Point<3>, [Vector<3>; 2]) {
+        let [p, q] = self.degree;
+
+        let span_u = bspline::find_span(
+            point.u,
+            p,
+            self.control_points.len() - 1,
+            &self.knots[0],
+        );
+        let span_v = bspline::find_span(
+            point.v,
+            q,
+            self.control_points[0].len() - 1,
+            &self.knots[1],
+        );
+
+        let basis_u =
+            bspline::basis_functions(span_u, point.u, p, &self.knots[0]);
+        let basis_v =
+            bspline::basis_functions(span_v, point.v, q, &self.knots[1]);
+        let derivatives_u =
+            bspline::basis_derivatives(span_u, point.u, p, &self.knots[0]);
+        let derivatives_v =
+            bspline::basis_derivatives(span_v, point.v, q, &self.knots[1]);
+
+        let homogeneous = self.homogeneous();
+
+        let mut position = Vector::from([0.; 4]);
+        let mut du = Vector::from([0.; 4]);
+        let mut dv = Vector::from([0.; 4]);
+
+        for k in 0..=p {
+            for l in 0..=q {
+                let control_point = homogeneous[span_u - p + k][span_v - q + l];
+
+                position = position + control_point * (basis_u[k] * basis_v[l]);
+                du = du + control_point * (derivatives_u[k] * basis_v[l]);
+                dv = dv + control_point * (basis_u[k] * derivatives_v[l]);
+            }
+        }
+
+        let derivatives = [
+            bspline::rational_derivative(position, du),
+            bspline::rational_derivative(position, dv),
+        ];
+        let (position, _) = bspline::from_homogeneous(position);
+
+        (position, derivatives)
+    }
+
+    fn homogeneous(&self) -> Vec<Vec<Vector<4>>> {
+        self.control_points
+            .iter()
+            .zip(&self.weights)
+            .map(|(points, weights)| {
+                points
+                    .iter()
+                    .zip(weights)
+                    .map(|(&point, &weight)| {
+                        bspline::to_homogeneous(point, weight)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// The maximum number of iterations when projecting points onto the surface
+const MAX_ITERATIONS: usize = 32;
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+    use fj_math::{Point, Scalar};
+
+    use super::NurbsSurface;
+
+    #[test]
+    fn point_model_to_surface() {
+        let surface = surface();
+
+        for uv in [[0., 0.], [0.3, 0.6], [1., 0.25], [0.9, 1.]] {
+            let uv = Point::from(uv);
+            let point = surface.point_surface_to_model(&uv);
+
+            assert_abs_diff_eq!(
+                surface.point_model_to_surface(&point),
+                uv,
+                epsilon = 1e-9,
+            );
+        }
+    }
+
+    #[test]
+    fn insert_knot() {
+        let surface = surface();
+
+        for axis in [0, 1] {
+            let refined = surface.insert_knot(axis, Scalar::from(0.4));
+
+            for uv in [[0., 0.], [0.3, 0.6], [0.4, 0.4], [1., 1.]] {
+                let uv = Point::from(uv);
+                assert_abs_diff_eq!(
+                    refined.point_surface_to_model(&uv),
+                    surface.point_surface_to_model(&uv),
+                    epsilon = 1e-12,
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn reverse() {
+        let surface = surface();
+        let reversed = surface.clone().reverse();
+
+        for [u, v] in [[0., 0.], [0.3, 0.6], [1., 0.25]] {
+            assert_abs_diff_eq!(
+                reversed.point_surface_to_model(&Point::from([1. - u, v])),
+                surface.point_surface_to_model(&Point::from([u, v])),
+                epsilon = 1e-12,
+            );
+        }
+    }
+
+    fn surface() -> NurbsSurface {
+        let control_points = (0..3)
+            .map(|i| {
+                (0..3)
+                    .map(|j| {
+                        let height = if i == 1 && j == 1 { 2. } else { 0. };
+                        Point::from([i as f64, j as f64, height])
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut weights = vec![vec![Scalar::ONE; 3]; 3];
+        weights[1][1] = Scalar::from(2.);
+
+        let knots = [0., 0., 0., 1., 1., 1.].map(Scalar::from).to_vec();
+
+        NurbsSurface {
+            degree: [2, 2],
+            control_points,
+            weights,
+            knots: [knots.clone(), knots],
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use fj_math::{Point, Scalar, Transform, Vector};
+
+use super::cylinder::angle;
+
+/// A sphere
+///
+/// The surface coordinate `u` is the longitude, `v` is the latitude. `v` is
+/// between `-PI / 2.` and `PI / 2.`, with the poles at the end of that range.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Sphere {
+    /// The center of the sphere
+    pub center: Point<3>,
+
+    /// A vector from the center to the point at `u == 0` and `v == 0`
+    ///
+    /// The length of this vector defines the radius of the sphere. Please also
+    /// refer to the documentation of `b` and `c`.
+    pub a: Vector<3>,
+
+    /// A second vector that defines the direction of `u`
+    ///
+    /// The vector must be of equal length to `a` (the sphere radius) and must
+    /// be perpendicular to it.
+    pub b: Vector<3>,
+
+    /// A vector from the center to the pole at `v == PI / 2.`
+    ///
+    /// The vector must be of equal length to `a` (the sphere radius) and must
+    /// be perpendicular to `a` and `b`. Code working with spheres might assume
+    /// that these conditions are met.
+    pub c: Vector<3>,
+}
+
+impl Sphere {
+    /// Access the radius of the sphere
+    pub fn radius(&self) -> Scalar {
+        self.a.magnitude()
+    }
+
+    /// Create a new instance that is reversed
+    ///
+    /// Reversing the sphere flips the direction of `u`, and with it the
+    /// direction of the surface normal.
+    #[must_use]
+    pub fn reverse(mut self) -> Self {
+        self.b = -self.b;
+        self
+    }
+
+    /// Transform the surface
+    #[must_use]
+    pub fn transform(self, transform: &Transform) -> Self {
+        Self {
+            center: transform.transform_point(&self.center),
+            a: transform.transform_vector(&self.a),
+            b: transform.transform_vector(&self.b),
+            c: transform.transform_vector(&self.c),
+        }
+    }
+
+    /// Convert a point in model coordinates to surface coordinates
+    ///
+    /// The `u` coordinate is between `0.` (inclusive) and `PI * 2.`
+    /// (exclusive). The point is projected onto the sphere along the direction
+    /// from the center, ignoring its distance from the center.
+    ///
+    /// The `u` coordinate of the poles is not defined. `0.` is returned in that
+    /// case.
+    pub fn point_model_to_surface(&self, point: &Point<3>) -> Point<2> {
+        let d = point - self.center;
+
+        let x = d.dot(&self.a);
+        let y = d.dot(&self.b);
+        let z = d.dot(&self.c);
+
+        let u = angle(x, y);
+        let v = Scalar::atan2(z, (x * x + y * y).sqrt());
+
+        Point::from([u, v])
+    }
+
+    /// Convert a point in surface coordinates to model coordinates
+    pub fn point_surface_to_model(&self, point: &Point<2>) -> Point<3> {
+        self.center + self.offset(&point.coords)
+    }
+
+    /// Compute the offset from the center to the point at the given coordinates
+    fn offset(&self, vector: &Vector<2>) -> Vector<3> {
+        let (sin_u, cos_u) = vector.u.sin_cos();
+        let (sin_v, mut cos_v) = vector.v.sin_cos();
+
+        // The cosine of `PI / 2.` is not exactly zero. Make sure all points at
+        // the poles are identical, regardless of their `u` coordinate.
+        if vector.v.abs() == Scalar::PI / 2. {
+            cos_v = Scalar::ZERO;
+        }
+
+        (self.a * cos_u + self.b * sin_u) * cos_v + self.c * sin_v
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+    use approx::assert_abs_diff_eq;
+    use fj_math::{Point, Vector};
+
+    use super::Sphere;
+
+    #[test]
+    fn point_model_to_surface() {
+        let sphere = sphere();
+
+        verify(&sphere, Point::from([0., 0.]));
+        verify(&sphere, Point::from([1., FRAC_PI_4]));
+        verify(&sphere, Point::from([PI, -1.]));
+
+        fn verify(sphere: &Sphere, surface_point: Point<2>) {
+            let point = sphere.point_surface_to_model(&surface_point);
+            let result = sphere.point_model_to_surface(&point);
+
+            assert_abs_diff_eq!(result, surface_point, epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn point_surface_to_model() {
+        let sphere = sphere();
+
+        assert_abs_diff_eq!(
+            sphere.point_surface_to_model(&Point::from([FRAC_PI_2, 0.])),
+            Point::from([1., 3., 3.]),
+            epsilon = 1e-12,
+        );
+        assert_abs_diff_eq!(
+            sphere.point_surface_to_model(&Point::from([0., FRAC_PI_2])),
+            Point::from([1., 1., 5.]),
+            epsilon = 1e-12,
+        );
+    }
+
+    #[test]
+    fn poles_are_exact() {
+        let sphere = sphere();
+
+        for v in [-FRAC_PI_2, FRAC_PI_2] {
+            let a = sphere.point_surface_to_model(&Point::from([0., v]));
+            let b = sphere.point_surface_to_model(&Point::from([1., v]));
+
+            assert_eq!(a, b);
+        }
+    }
+
+    fn sphere() -> Sphere {
+        Sphere {
+            center: Point::from([1., 1., 3.]),
+            a: Vector::from([2., 0., 0.]),
+            b: Vector::from([0., 2., 0.]),
+            c: Vector::from([0., 0., 2.]),
+        }
+    }
+}
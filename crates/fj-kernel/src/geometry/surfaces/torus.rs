@@ -0,0 +1,150 @@
+use fj_math::{Point, Scalar, Transform, Vector};
+
+use super::cylinder::angle;
+
+/// A torus
+///
+/// The surface coordinate `u` is the angle around the axis of the torus, `v`
+/// is the angle around the tube.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Torus {
+    /// The center of the torus
+    pub center: Point<3>,
+
+    /// A vector from the center to the center of the tube at `u == 0`
+    ///
+    /// The length of this vector defines the major radius of the torus. Please
+    /// also refer to the documentation of `b`.
+    pub a: Vector<3>,
+
+    /// A second vector that defines the direction of `u`
+    ///
+    /// The vector must be of equal length to `a` (the major radius) and must be
+    /// perpendicular to it.
+    pub b: Vector<3>,
+
+    /// The axis of the torus
+    ///
+    /// The length of this vector defines the minor radius of the torus (the
+    /// radius of the tube). The vector must be perpendicular to `a` and `b`.
+    /// Code working with tori might assume that these conditions are met.
+    pub c: Vector<3>,
+}
+
+impl Torus {
+    /// Access the major radius of the torus
+    pub fn major_radius(&self) -> Scalar {
+        self.a.magnitude()
+    }
+
+    /// Access the minor radius of the torus
+    pub fn minor_radius(&self) -> Scalar {
+        self.c.magnitude()
+    }
+
+    /// Create a new instance that is reversed
+    ///
+    /// Reversing the torus flips the direction of `u`, and with it the
+    /// direction of the surface normal.
+    #[must_use]
+    pub fn reverse(mut self) -> Self {
+        self.b = -self.b;
+        self
+    }
+
+    /// Transform the surface
+    #[must_use]
+    pub fn transform(self, transform: &Transform) -> Self {
+        Self {
+            center: transform.transform_point(&self.center),
+            a: transform.transform_vector(&self.a),
+            b: transform.transform_vector(&self.b),
+            c: transform.transform_vector(&self.c),
+        }
+    }
+
+    /// Convert a point in model coordinates to surface coordinates
+    ///
+    /// Both coordinates are between `0.` (inclusive) and `PI * 2.`
+    /// (exclusive). The point is projected onto the torus, ignoring its
+    /// distance from the center of the tube.
+    pub fn point_model_to_surface(&self, point: &Point<3>) -> Point<2> {
+        let d = point - self.center;
+
+        let x = d.dot(&self.a) / self.major_radius();
+        let y = d.dot(&self.b) / self.major_radius();
+        let z = d.dot(&self.c) / self.minor_radius();
+
+        let u = angle(x, y);
+        let v = angle((x * x + y * y).sqrt() - self.major_radius(), z);
+
+        Point::from([u, v])
+    }
+
+    /// Convert a point in surface coordinates to model coordinates
+    pub fn point_surface_to_model(&self, point: &Point<2>) -> Point<3> {
+        self.center + self.offset(&point.coords)
+    }
+
+    /// Compute the offset from the center to the point at the given coordinates
+    fn offset(&self, vector: &Vector<2>) -> Vector<3> {
+        let (sin_u, cos_u) = vector.u.sin_cos();
+        let (sin_v, cos_v) = vector.v.sin_cos();
+
+        let radial = self.a * cos_u + self.b * sin_u;
+        let tube = radial.normalize() * self.minor_radius();
+
+        radial + tube * cos_v + self.c * sin_v
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_PI_2, PI};
+
+    use approx::assert_abs_diff_eq;
+    use fj_math::{Point, Vector};
+
+    use super::Torus;
+
+    #[test]
+    fn point_model_to_surface() {
+        let torus = torus();
+
+        verify(&torus, Point::from([0., 0.]));
+        verify(&torus, Point::from([1., 2.]));
+        verify(&torus, Point::from([PI, FRAC_PI_2 * 3.]));
+
+        fn verify(torus: &Torus, surface_point: Point<2>) {
+            let point = torus.point_surface_to_model(&surface_point);
+            let result = torus.point_model_to_surface(&point);
+
+            assert_abs_diff_eq!(result, surface_point, epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn point_surface_to_model() {
+        let torus = torus();
+
+        assert_abs_diff_eq!(
+            torus.point_surface_to_model(&Point::from([0., 0.])),
+            Point::from([4., 0., 0.]),
+            epsilon = 1e-12,
+        );
+        assert_abs_diff_eq!(
+            torus.point_surface_to_model(&Point::from([FRAC_PI_2, FRAC_PI_2])),
+            Point::from([0., 3., 1.]),
+            epsilon = 1e-12,
+        );
+    }
+
+    fn torus() -> Torus {
+        Torus {
+            center: Point::from([0., 0., 0.]),
+            a: Vector::from([3., 0., 0.]),
+            b: Vector::from([0., 3., 0.]),
+            c: Vector::from([0., 0., 1.]),
+        }
+    }
+}
@@ -1,13 +1,16 @@
-use fj_math::{Point, Scalar, Transform};
+use fj_math::{Aabb, Point, Scalar, Transform};
 
 use crate::{
     geometry::{Curve, Surface},
-    topology::{Cycle, Edge, Face, Vertex},
+    topology::{Cycle, Edge, Face, Shell, Solid, Vertex},
 };
 
 use super::{
+    solid::{self, SolidReport},
+    spatial,
     stores::{
-        Curves, Cycles, Edges, Faces, Points, Stores, Surfaces, Vertices,
+        Curves, Cycles, Edges, Faces, Points, Shells, Solids, Stores, Surfaces,
+        Vertices,
     },
     Handle, Iter, Object, ValidationResult,
 };
@@ -37,6 +40,8 @@ impl Shape {
                 edges: Edges::new(),
                 cycles: Cycles::new(),
                 faces: Faces::new(),
+                shells: Shells::new(),
+                solids: Solids::new(),
             },
         }
     }
@@ -44,12 +49,6 @@ impl Shape {
     /// Override the minimum distance for this shape
     ///
     /// Used for vertex validation, to determine whether vertices are unique.
-    ///
-    /// # Implementation note
-    ///
-    /// This functionality should be exposed to models, eventually. For now it's
-    /// just used in unit tests.
-    #[cfg(test)]
     pub fn with_min_distance(
         mut self,
         min_distance: impl Into<Scalar>,
@@ -58,6 +57,13 @@ impl Shape {
         self
     }
 
+    /// Access the minimum distance for this shape
+    ///
+    /// See [`Shape::with_min_distance`].
+    pub fn min_distance(&self) -> Scalar {
+        self.min_distance
+    }
+
     /// Insert an object into the shape
     ///
     /// Validates the object, and returns an error if it is not valid. See the
@@ -76,23 +82,20 @@ impl Shape {
     /// Returns the handle that refers to the given object, if it is part of the
     /// shape. Returns `None`, if it isn't.
     ///
+    /// Objects are looked up by their hash, so this doesn't need to look at
+    /// every object in the shape.
+    ///
     /// # Implementation note
     ///
     /// If `object` is present multiple times, the handle of the first that is
     /// found is returned. This is weird. It would be better, if objects were
-    /// unique, but currently they are stored in `Vec`s.
-    ///
-    /// This probably isn't worth thinking too much about right now. At some
-    /// point, we need smarter and probably more performant object storage
-    /// anyway.
+    /// unique, but currently nothing prevents the same object from being
+    /// inserted multiple times.
     pub fn get_handle<T>(&self, object: &T) -> Option<Handle<T>>
     where
         T: Object,
     {
-        self.stores
-            .get::<T>()
-            .iter()
-            .find(|obj| &obj.get() == object)
+        self.stores.get::<T>().find(object)
     }
 
     /// Get handle of an identical object, if it exists, or add the object
@@ -120,21 +123,76 @@ impl Shape {
             .update(|point| *point = transform.transform_point(point));
         self.stores
             .curves
-            .update(|curve| *curve = curve.transform(transform));
+            .update(|curve| *curve = curve.clone().transform(transform));
         self.stores
             .surfaces
-            .update(|surface| *surface = surface.transform(transform));
-
-        // While some faces use triangle representation, we need this weird
-        // workaround here.
-        self.stores.faces.update(|mut face| {
-            use std::ops::DerefMut as _;
-            if let Face::Triangles(triangles) = face.deref_mut() {
-                for (triangle, _) in triangles {
-                    *triangle = transform.transform_triangle(triangle);
-                }
-            }
-        });
+            .update(|surface| *surface = surface.clone().transform(transform));
+
+        // The hashes and bounding volumes of topological objects depend on the
+        // geometry, which has just changed.
+        self.stores.vertices.reindex();
+        self.stores.edges.reindex();
+        self.stores.cycles.reindex();
+        self.stores.faces.reindex();
+        self.stores.shells.reindex();
+        self.stores.solids.reindex();
+    }
+
+    /// Validate that the shape forms a closed, consistently oriented solid
+    ///
+    /// Unlike the validation that happens when inserting objects, this looks
+    /// at the shape as a whole. It checks that every edge is shared by exactly
+    /// two faces that use it in opposite directions, groups the faces into
+    /// shells, and computes the Euler characteristic of each shell.
+    ///
+    /// The direction in which a face uses an edge is the one that the cycle
+    /// stores for the edge. Since the face is on the left of its cycles, with
+    /// respect to the normal of its surface, faces that use a shared edge in
+    /// the same direction are facing opposite ways.
+    ///
+    /// Doesn't return an error, as the result is meant as a diagnostic. Use
+    /// [`SolidReport::is_valid`] to check the result.
+    pub fn validate_solid(&self) -> SolidReport {
+        solid::validate_solid(self)
+    }
+
+    /// Find all vertices within a distance of a point
+    ///
+    /// The caller must not make any assumptions about the order of vertices.
+    pub fn vertices_near(
+        &self,
+        point: Point<3>,
+        distance: impl Into<Scalar>,
+    ) -> Vec<Handle<Vertex>> {
+        let distance = distance.into();
+        let aabb = Aabb::<3>::from_points([point]).loosened(distance);
+
+        self.stores
+            .vertices
+            .query(&aabb)
+            .into_iter()
+            .filter(|vertex| {
+                (vertex.get().point() - point).magnitude() <= distance
+            })
+            .collect()
+    }
+
+    /// Find the vertex that is nearest to a point
+    ///
+    /// Returns `None`, if the shape has no vertices.
+    pub fn nearest_vertex(&self, point: Point<3>) -> Option<Handle<Vertex>> {
+        self.stores.vertices.nearest(&point, |vertex, point| {
+            (vertex.point() - *point).magnitude()
+        })
+    }
+
+    /// Find the edge that is nearest to a point
+    ///
+    /// Returns `None`, if the shape has no edges.
+    pub fn nearest_edge(&self, point: Point<3>) -> Option<Handle<Edge>> {
+        self.stores.edges.nearest(&point, |edge, point| {
+            spatial::distance_to_edge(edge, point)
+        })
     }
 
     /// Access an iterator over all points
@@ -185,6 +243,20 @@ impl Shape {
     pub fn faces(&self) -> Iter<Face> {
         self.stores.faces.iter()
     }
+
+    /// Access an iterator over all shells
+    ///
+    /// The caller must not make any assumptions about the order of shells.
+    pub fn shells(&self) -> Iter<Shell> {
+        self.stores.shells.iter()
+    }
+
+    /// Access an iterator over all solids
+    ///
+    /// The caller must not make any assumptions about the order of solids.
+    pub fn solids(&self) -> Iter<Solid> {
+        self.stores.solids.iter()
+    }
 }
 
 impl Default for Shape {
@@ -197,12 +269,12 @@ impl Default for Shape {
 mod tests {
     use std::ops::{Deref, DerefMut};
 
-    use fj_math::{Point, Scalar};
+    use fj_math::{Point, Scalar, Transform, Vector};
 
     use crate::{
         geometry::{Curve, Surface},
         shape::{Handle, Shape, ValidationError},
-        topology::{Cycle, Edge, Face, Vertex},
+        topology::{Cycle, Edge, Face, Shell, Solid, Vertex},
     };
 
     const MIN_DISTANCE: f64 = 5e-7;
@@ -243,13 +315,13 @@ mod tests {
         assert!(shape.get_handle(&vertex.get()).as_ref() == Some(&vertex));
         assert!(shape.get_handle(&edge.get()).as_ref() == Some(&edge));
 
-        let cycle = Cycle { edges: vec![edge] };
+        let cycle = Cycle::from_edges(vec![edge]);
         assert!(shape.get_handle(&cycle).is_none());
 
         let cycle = shape.insert(cycle)?;
         assert!(shape.get_handle(&cycle.get()).as_ref() == Some(&cycle));
 
-        let face = Face::Face {
+        let face = Face {
             surface,
             exteriors: Vec::new(),
             interiors: Vec::new(),
@@ -263,6 +335,55 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn get_handle_after_transform() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+
+        let point = shape.insert(Point::from([1., 0., 0.]))?;
+        let vertex = shape.insert(Vertex { point })?;
+
+        shape.transform(&Transform::translation([0., 1., 0.]));
+
+        let point = Point::from([1., 1., 0.]);
+        let vertex_value = Vertex {
+            point: shape.get_handle(&point).unwrap(),
+        };
+        assert!(shape.get_handle(&vertex_value).as_ref() == Some(&vertex));
+
+        let old_point = Point::from([1., 0., 0.]);
+        assert!(shape.get_handle(&old_point).is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn nearest() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+
+        let a = Edge::builder(&mut shape)
+            .build_line_segment_from_points([[0., 0., 0.], [1., 0., 0.]])?;
+        let b = Edge::builder(&mut shape)
+            .build_line_segment_from_points([[0., 1., 0.], [1., 1., 0.]])?;
+
+        let point = Point::from([0.5, 0.2, 0.]);
+        assert!(shape.nearest_edge(point).as_ref() == Some(&a));
+
+        let point = Point::from([0.9, 0.8, 0.]);
+        assert!(shape.nearest_edge(point).as_ref() == Some(&b));
+
+        let vertex = shape.nearest_vertex(point).unwrap();
+        assert_eq!(vertex.get().point(), Point::from([1., 1., 0.]));
+
+        assert_eq!(shape.vertices_near(point, 0.1).len(), 0);
+        assert_eq!(shape.vertices_near(point, 0.3).len(), 1);
+        assert_eq!(shape.vertices_near(point, 0.85).len(), 2);
+
+        shape.transform(&Transform::translation([0., 0., 1.]));
+        assert_eq!(shape.vertices_near(point, 1.).len(), 0);
+
+        Ok(())
+    }
+
     #[test]
     fn add_vertex() -> anyhow::Result<()> {
         let mut shape = Shape::new().with_min_distance(MIN_DISTANCE);
@@ -331,15 +452,13 @@ mod tests {
         // Trying to refer to edge that is not from the same shape. Should fail.
         let edge = other.add_edge()?;
         let err = shape
-            .insert(Cycle {
-                edges: vec![edge.clone()],
-            })
+            .insert(Cycle::from_edges(vec![edge.clone()]))
             .unwrap_err();
         assert!(err.missing_edge(&edge));
 
-        // Referring to edge that *is* from the same shape. Should work.
-        let edge = shape.add_edge()?;
-        shape.insert(Cycle { edges: vec![edge] })?;
+        // Referring to edges that *are* from the same shape. Should work.
+        let edges = shape.add_triangle()?;
+        shape.insert(Cycle::from_edges(edges))?;
 
         Ok(())
     }
@@ -354,7 +473,7 @@ mod tests {
 
         // Nothing has been added to `shape`. Should fail.
         let err = shape
-            .insert(Face::Face {
+            .insert(Face {
                 surface: surface.clone(),
                 exteriors: vec![cycle.clone()],
                 interiors: Vec::new(),
@@ -368,7 +487,7 @@ mod tests {
         let cycle = shape.add_cycle()?;
 
         // Everything has been added to `shape` now. Should work!
-        shape.insert(Face::Face {
+        shape.insert(Face {
             surface,
             exteriors: vec![cycle],
             interiors: Vec::new(),
@@ -378,6 +497,135 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn add_shell_and_solid() -> anyhow::Result<()> {
+        let mut shape = TestShape::new();
+        let mut other = TestShape::new();
+
+        let face = other.add_face()?;
+
+        // Nothing has been added to `shape`. Should fail.
+        let err = shape
+            .insert(Shell {
+                faces: vec![face.clone()],
+            })
+            .unwrap_err();
+        assert!(err.missing_face(&face));
+
+        let shell = other.insert(Shell { faces: vec![face] })?;
+        let err = shape
+            .insert(Solid {
+                exterior: shell.clone(),
+                interiors: Vec::new(),
+            })
+            .unwrap_err();
+        assert!(err.missing_shell(&shell));
+
+        let face = shape.add_face()?;
+        let shell = shape.insert(Shell { faces: vec![face] })?;
+
+        // Everything has been added to `shape` now. Should work!
+        shape.insert(Solid {
+            exterior: shell,
+            interiors: Vec::new(),
+        })?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn add_cycle_open() -> anyhow::Result<()> {
+        let mut shape = TestShape::new();
+
+        let edges = shape.add_triangle()?;
+        let err = shape
+            .insert(Cycle::from_edges(edges[..2].to_vec()))
+            .unwrap_err();
+        assert!(
+            matches!(err, ValidationError::OpenCycle(open) if open.len() == 2)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn add_cycle_misdirected() -> anyhow::Result<()> {
+        let mut shape = TestShape::new();
+
+        let edges = shape.add_triangle()?;
+        let mut cycle = Cycle::from_edges(edges);
+        cycle.reversed[1] = true;
+
+        let err = shape.insert(cycle).unwrap_err();
+        assert!(
+            matches!(err, ValidationError::OpenCycle(open) if open.len() == 2)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn add_cycle_self_overlapping() -> anyhow::Result<()> {
+        let mut shape = TestShape::new();
+
+        // The edges from `a` to `b`, and from `c` to `d` cross each other.
+        let err = Cycle::builder(&mut shape)
+            .build_polygon([
+                [0., 0., 0.],
+                [1., 1., 0.],
+                [1., 0., 0.],
+                [0., 1., 0.],
+            ])
+            .unwrap_err();
+        assert!(matches!(err, ValidationError::SelfOverlappingCycle(_)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn add_cycle_duplicate() -> anyhow::Result<()> {
+        let mut shape = TestShape::new();
+
+        let mut edges = shape.add_triangle()?;
+        let cycle = shape.insert(Cycle::from_edges(edges.clone()))?;
+
+        edges.rotate_left(1);
+        let err = shape.insert(Cycle::from_edges(edges)).unwrap_err();
+        assert!(
+            matches!(err, ValidationError::DuplicateCycle(existing) if existing == cycle)
+        );
+
+        // Running through the same edges in the other direction makes it a
+        // different cycle.
+        shape.insert(cycle.get().reverse())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn add_face_edge_not_in_surface() -> anyhow::Result<()> {
+        let mut shape = TestShape::new();
+
+        // The cycle lies in the xy-plane.
+        let cycle = shape.add_cycle()?;
+        let surface = shape.insert(Surface::xz_plane())?;
+
+        let err = shape
+            .insert(Face {
+                surface: surface.clone(),
+                exteriors: vec![cycle],
+                interiors: Vec::new(),
+                color: [255, 0, 0, 255],
+            })
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            ValidationError::EdgeNotInSurface { surface: s, .. } if s == surface
+        ));
+
+        Ok(())
+    }
+
     struct TestShape {
         inner: Shape,
         next_point: Point<3>,
@@ -411,11 +659,39 @@ mod tests {
             Ok(edge)
         }
 
+        fn add_triangle(&mut self) -> anyhow::Result<Vec<Handle<Edge>>> {
+            let a = self.next_point;
+            let b = a + Vector::from([1., 0., 0.]);
+            let c = a + Vector::from([0., 1., 0.]);
+            self.next_point.x += Scalar::TWO;
+
+            let mut edges = Vec::new();
+            for points in [[a, b], [b, c], [c, a]] {
+                let edge = Edge::builder(&mut self.inner)
+                    .build_line_segment_from_points(points)?;
+                edges.push(edge);
+            }
+
+            Ok(edges)
+        }
+
         fn add_cycle(&mut self) -> anyhow::Result<Handle<Cycle>> {
-            let edge = self.add_edge()?;
-            let cycle = self.insert(Cycle { edges: vec![edge] })?;
+            let edges = self.add_triangle()?;
+            let cycle = self.insert(Cycle::from_edges(edges))?;
             Ok(cycle)
         }
+
+        fn add_face(&mut self) -> anyhow::Result<Handle<Face>> {
+            let surface = self.add_surface();
+            let cycle = self.add_cycle()?;
+            let face = self.insert(Face {
+                surface,
+                exteriors: vec![cycle],
+                interiors: Vec::new(),
+                color: [255, 0, 0, 255],
+            })?;
+            Ok(face)
+        }
     }
 
     impl Deref for TestShape {
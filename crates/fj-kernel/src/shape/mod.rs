@@ -4,12 +4,15 @@
 
 mod api;
 mod object;
+mod solid;
+mod spatial;
 mod stores;
 mod validate;
 
 pub use self::{
     api::Shape,
     object::Object,
+    solid::{ShellReport, SolidReport},
     stores::{Handle, Iter},
     validate::{StructuralIssues, ValidationError, ValidationResult},
 };
@@ -1,15 +1,17 @@
+use std::hash::Hash;
+
 use fj_math::Point;
 
 use crate::{
     geometry::{Curve, Surface},
-    topology::{Cycle, Edge, Face, Vertex},
+    topology::{Cycle, Edge, Face, Shell, Solid, Vertex},
 };
 
-use super::validate::Validate;
+use super::{spatial::BoundingVolume, validate::Validate};
 
 /// Marker trait for geometric and topological objects
 pub trait Object:
-    'static + Clone + PartialEq + Validate + private::Sealed
+    'static + Clone + PartialEq + Hash + BoundingVolume + Validate + private::Sealed
 {
 }
 
@@ -21,6 +23,8 @@ impl private::Sealed for Vertex {}
 impl private::Sealed for Edge {}
 impl private::Sealed for Cycle {}
 impl private::Sealed for Face {}
+impl private::Sealed for Shell {}
+impl private::Sealed for Solid {}
 
 impl Object for Point<3> {}
 impl Object for Curve {}
@@ -30,6 +34,8 @@ impl Object for Vertex {}
 impl Object for Edge {}
 impl Object for Cycle {}
 impl Object for Face {}
+impl Object for Shell {}
+impl Object for Solid {}
 
 mod private {
     pub trait Sealed {}
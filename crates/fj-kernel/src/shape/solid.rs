@@ -0,0 +1,388 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+use crate::topology::{Edge, Face, HalfEdges, Vertex};
+
+use super::{Handle, Shape};
+
+/// The result of [`Shape::validate_solid`]
+#[derive(Debug)]
+pub struct SolidReport {
+    /// The shells of the shape
+    ///
+    /// A shell is a set of faces that are connected via shared edges.
+    pub shells: Vec<ShellReport>,
+
+    /// Edges that are shared by more than two faces
+    pub non_manifold_edges: Vec<Handle<Edge>>,
+
+    /// Edges whose two faces are oriented inconsistently
+    ///
+    /// The two faces that share an edge must use it in opposite directions,
+    /// as stored in their cycles. Otherwise, one of them is facing the wrong
+    /// way, either because its surface is flipped, or because its cycles run
+    /// in the wrong direction.
+    pub misoriented_edges: Vec<Handle<Edge>>,
+}
+
+impl SolidReport {
+    /// Indicate whether the shape is a closed, consistently oriented solid
+    pub fn is_valid(&self) -> bool {
+        self.non_manifold_edges.is_empty()
+            && self.misoriented_edges.is_empty()
+            && self.shells.iter().all(ShellReport::is_valid)
+    }
+}
+
+impl fmt::Display for SolidReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_valid() {
+            write!(f, "Shape is a valid solid")?;
+        } else {
+            write!(f, "Shape is not a valid solid")?;
+        }
+
+        for (i, shell) in self.shells.iter().enumerate() {
+            write!(
+                f,
+                "\n- Shell {i}: {} faces, Euler characteristic {}",
+                shell.faces.len(),
+                shell.euler_characteristic,
+            )?;
+
+            if !shell.is_closed() {
+                write!(
+                    f,
+                    ", open ({} boundary edges)",
+                    shell.boundary_edges.len()
+                )?;
+            } else if shell.genus().is_none() {
+                write!(f, ", inconsistent topology")?;
+            }
+        }
+
+        if !self.non_manifold_edges.is_empty() {
+            write!(
+                f,
+                "\n- {} non-manifold edges",
+                self.non_manifold_edges.len()
+            )?;
+        }
+        if !self.misoriented_edges.is_empty() {
+            write!(
+                f,
+                "\n- {} edges with inconsistently oriented faces",
+                self.misoriented_edges.len()
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A shell of a shape, as found by [`Shape::validate_solid`]
+#[derive(Debug)]
+pub struct ShellReport {
+    /// The faces that make up the shell
+    pub faces: Vec<Handle<Face>>,
+
+    /// Edges of the shell that are only part of a single face
+    ///
+    /// If there are any, the shell is open.
+    pub boundary_edges: Vec<Handle<Edge>>,
+
+    /// The Euler characteristic of the shell
+    ///
+    /// Computed using the Euler-Poincaré formula `V - E + F - (L - F)`, where
+    /// `L` is the number of cycles. Faces with holes have more than one cycle.
+    /// Edges without vertices count as having one vertex.
+    ///
+    /// For a closed shell, this is `2 - 2 * genus`.
+    pub euler_characteristic: i64,
+}
+
+impl ShellReport {
+    /// Indicate whether the shell is closed
+    pub fn is_closed(&self) -> bool {
+        self.boundary_edges.is_empty()
+    }
+
+    /// Access the genus of the shell
+    ///
+    /// The genus is the number of handles of the shell. A sphere has genus 0,
+    /// a torus has genus 1.
+    ///
+    /// Returns `None`, if the shell is open, or if its Euler characteristic is
+    /// not consistent with a closed shell.
+    pub fn genus(&self) -> Option<i64> {
+        let euler = self.euler_characteristic;
+
+        if !self.is_closed() || euler > 2 || euler % 2 != 0 {
+            return None;
+        }
+
+        Some((2 - euler) / 2)
+    }
+
+    /// Indicate whether the shell is closed and topologically consistent
+    pub fn is_valid(&self) -> bool {
+        self.genus().is_some()
+    }
+}
+
+pub(super) fn validate_solid(shape: &Shape) -> SolidReport {
+    let faces: Vec<_> = shape.faces().collect();
+    let face_indices: HashMap<_, _> = faces
+        .iter()
+        .enumerate()
+        .map(|(i, face)| (face.clone(), i))
+        .collect();
+
+    let half_edges = HalfEdges::new(shape);
+
+    let mut non_manifold_edges = Vec::new();
+    let mut misoriented_edges = Vec::new();
+    let mut boundary_edges = Vec::new();
+
+    // Faces that share an edge belong to the same shell.
+    let mut shell_of_face: Vec<_> = (0..faces.len()).collect();
+
+    for edge in half_edges.edges() {
+        let uses: Vec<_> = half_edges
+            .of_edge(edge)
+            .iter()
+            .map(|&id| {
+                let half_edge = half_edges.get(id);
+                (face_indices[&half_edge.face], half_edge.reversed)
+            })
+            .collect();
+
+        match uses.as_slice() {
+            [(face, _)] => boundary_edges.push((*face, edge.clone())),
+            [(a, direction_a), (b, direction_b)] => {
+                merge(&mut shell_of_face, *a, *b);
+
+                if direction_a == direction_b {
+                    misoriented_edges.push(edge.clone());
+                }
+            }
+            uses => {
+                for window in uses.windows(2) {
+                    merge(&mut shell_of_face, window[0].0, window[1].0);
+                }
+                non_manifold_edges.push(edge.clone());
+            }
+        }
+    }
+
+    let mut faces_by_shell: HashMap<usize, Vec<usize>> = HashMap::new();
+    for i in 0..faces.len() {
+        let shell = find(&mut shell_of_face, i);
+        faces_by_shell.entry(shell).or_default().push(i);
+    }
+
+    let mut shells: Vec<_> = faces_by_shell
+        .into_iter()
+        .map(|(shell, face_indices)| {
+            let boundary_edges = boundary_edges
+                .iter()
+                .filter(|(face, _)| find(&mut shell_of_face, *face) == shell)
+                .map(|(_, edge)| edge.clone())
+                .collect();
+
+            let shell_faces: Vec<_> =
+                face_indices.iter().map(|&i| faces[i].clone()).collect();
+            let euler_characteristic = euler_characteristic(&shell_faces);
+
+            ShellReport {
+                faces: shell_faces,
+                boundary_edges,
+                euler_characteristic,
+            }
+        })
+        .collect();
+
+    // The order of shells depends on the iteration order of a hash map. Sort
+    // them, to make the result deterministic.
+    shells.sort_by(|a, b| a.faces.cmp(&b.faces));
+
+    SolidReport {
+        shells,
+        non_manifold_edges,
+        misoriented_edges,
+    }
+}
+
+/// Compute the Euler characteristic of a set of faces
+fn euler_characteristic(faces: &[Handle<Face>]) -> i64 {
+    let mut vertices: HashSet<Handle<Vertex>> = HashSet::new();
+    let mut edges = HashSet::new();
+    let mut num_continuous_edges = 0;
+    let mut num_cycles = 0;
+
+    for face in faces {
+        for cycle in face.get().all_cycles() {
+            num_cycles += 1;
+
+            for edge in cycle.edges {
+                match edge.get().vertices {
+                    Some(edge_vertices) => vertices.extend(edge_vertices),
+                    None => {
+                        if !edges.contains(&edge) {
+                            num_continuous_edges += 1;
+                        }
+                    }
+                }
+                edges.insert(edge);
+            }
+        }
+    }
+
+    let v = (vertices.len() + num_continuous_edges) as i64;
+    let e = edges.len() as i64;
+    let f = faces.len() as i64;
+    let l = num_cycles as i64;
+
+    v - e + f - (l - f)
+}
+
+/// Find the representative of a set, in a union-find structure
+fn find(sets: &mut [usize], i: usize) -> usize {
+    let mut root = i;
+    while sets[root] != root {
+        root = sets[root];
+    }
+
+    // Compress the path, so future lookups are faster.
+    let mut i = i;
+    while sets[i] != root {
+        let next = sets[i];
+        sets[i] = root;
+        i = next;
+    }
+
+    root
+}
+
+/// Merge two sets, in a union-find structure
+fn merge(sets: &mut [usize], a: usize, b: usize) {
+    let [a, b] = [find(sets, a), find(sets, b)];
+    sets[a] = b;
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Point, Scalar, Vector};
+
+    use crate::{
+        algorithms::sweep_shape,
+        geometry::{Circle, Curve, Surface},
+        shape::Shape,
+        topology::{Cycle, Edge, Face},
+    };
+
+    #[test]
+    fn cube() -> anyhow::Result<()> {
+        let mut sketch = Shape::new();
+        Face::builder(Surface::xy_plane(), &mut sketch)
+            .with_exterior_polygon([
+                [0., 0., 0.],
+                [1., 0., 0.],
+                [1., 1., 0.],
+                [0., 1., 0.],
+            ])
+            .build()?;
+        let shape =
+            sweep_shape(sketch, Vector::from([0., 0., 1.]), [255, 0, 0, 255]);
+
+        let report = shape.validate_solid();
+        assert!(report.is_valid(), "{report}");
+        assert_eq!(report.shells.len(), 1);
+        assert_eq!(report.shells[0].faces.len(), 6);
+        assert_eq!(report.shells[0].euler_characteristic, 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn cylinder_with_hole() -> anyhow::Result<()> {
+        let mut sketch = Shape::new();
+
+        let exterior = Edge::builder(&mut sketch).build_circle(Scalar::TWO)?;
+
+        // Interior cycles go around the other way.
+        let curve = sketch.insert(Curve::Circle(Circle {
+            center: Point::origin(),
+            a: Vector::from([1., 0., 0.]),
+            b: Vector::from([0., -1., 0.]),
+        }))?;
+        let interior = sketch.insert(Edge {
+            curve,
+            vertices: None,
+        })?;
+        let exterior = sketch.insert(Cycle::from_edges(vec![exterior]))?;
+        let interior = sketch.insert(Cycle::from_edges(vec![interior]))?;
+        let surface = sketch.insert(Surface::xy_plane())?;
+        sketch.insert(Face {
+            surface,
+            exteriors: vec![exterior],
+            interiors: vec![interior],
+            color: [255, 0, 0, 255],
+        })?;
+
+        let shape =
+            sweep_shape(sketch, Vector::from([0., 0., 1.]), [255, 0, 0, 255]);
+
+        let report = shape.validate_solid();
+        assert!(report.is_valid(), "{report}");
+        assert_eq!(report.shells[0].genus(), Some(1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn open_shell() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+        Face::builder(Surface::xy_plane(), &mut shape)
+            .with_exterior_polygon([[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]])
+            .build()?;
+
+        let report = shape.validate_solid();
+        assert!(!report.is_valid());
+        assert_eq!(report.shells.len(), 1);
+        assert_eq!(report.shells[0].boundary_edges.len(), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn misoriented() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+
+        // Two triangles that share all their edges, but face the same way.
+        // Together, they form a closed shell that is inconsistently oriented.
+        let cycle = Cycle::builder(&mut shape).build_polygon([
+            [0., 0., 0.],
+            [1., 0., 0.],
+            [0., 1., 0.],
+        ])?;
+        for _ in 0..2 {
+            let surface = shape.insert(Surface::xy_plane())?;
+            shape.insert(Face {
+                surface,
+                exteriors: vec![cycle.clone()],
+                interiors: Vec::new(),
+                color: [255, 0, 0, 255],
+            })?;
+        }
+
+        let report = shape.validate_solid();
+        assert!(!report.is_valid());
+        assert!(report.shells[0].is_closed());
+        assert_eq!(report.misoriented_edges.len(), 3);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,410 @@
+//! Spatial index for the objects in a shape
+//!
+//! Used for finding vertices that are too close to each other when validating,
+//! and for finding objects near a point.
+
+use fj_math::{Aabb, Point, Scalar};
+
+use crate::{
+    geometry::{Curve, Surface},
+    topology::{Cycle, Edge, Face, Shell, Solid, Vertex},
+};
+
+use super::validate::in_range;
+
+/// Implemented by all objects, to define how they are spatially indexed
+pub trait BoundingVolume {
+    /// Compute an AABB that contains the object
+    ///
+    /// Returns `None`, if the object should not be part of the spatial index.
+    fn bounding_volume(&self) -> Option<Aabb<3>>;
+}
+
+impl BoundingVolume for Point<3> {
+    fn bounding_volume(&self) -> Option<Aabb<3>> {
+        // Points are only looked up through their vertices.
+        None
+    }
+}
+
+impl BoundingVolume for Curve {
+    fn bounding_volume(&self) -> Option<Aabb<3>> {
+        None
+    }
+}
+
+impl BoundingVolume for Surface {
+    fn bounding_volume(&self) -> Option<Aabb<3>> {
+        None
+    }
+}
+
+impl BoundingVolume for Vertex {
+    fn bounding_volume(&self) -> Option<Aabb<3>> {
+        Some(Aabb::<3>::from_points([self.point()]))
+    }
+}
+
+impl BoundingVolume for Edge {
+    fn bounding_volume(&self) -> Option<Aabb<3>> {
+        let vertices = self.vertices().map(|vertices| {
+            Aabb::<3>::from_points(vertices.map(|vertex| vertex.point()))
+        });
+
+        // The AABBs of curved edges are computed from the whole curve. That's
+        // larger than necessary, but never too small.
+        let curve = match self.curve() {
+            Curve::Circle(curve) => Some(ellipse_aabb(
+                curve.center,
+                curve.a.components,
+                curve.b.components,
+            )),
+            Curve::Ellipse(curve) => Some(ellipse_aabb(
+                curve.center,
+                curve.a.components,
+                curve.b.components,
+            )),
+            // Line segments are fully described by their vertices.
+            Curve::Line(_) => None,
+            // NURBS curves lie within the convex hull of their control points.
+            Curve::Nurbs(curve) => {
+                Some(Aabb::<3>::from_points(curve.control_points))
+            }
+        };
+
+        match (vertices, curve) {
+            (Some(vertices), Some(curve)) => Some(vertices.merged(&curve)),
+            (vertices, curve) => vertices.or(curve),
+        }
+    }
+}
+
+impl BoundingVolume for Cycle {
+    fn bounding_volume(&self) -> Option<Aabb<3>> {
+        None
+    }
+}
+
+impl BoundingVolume for Face {
+    fn bounding_volume(&self) -> Option<Aabb<3>> {
+        None
+    }
+}
+
+impl BoundingVolume for Shell {
+    fn bounding_volume(&self) -> Option<Aabb<3>> {
+        None
+    }
+}
+
+impl BoundingVolume for Solid {
+    fn bounding_volume(&self) -> Option<Aabb<3>> {
+        None
+    }
+}
+
+fn ellipse_aabb(center: Point<3>, a: [Scalar; 3], b: [Scalar; 3]) -> Aabb<3> {
+    let mut min = center;
+    let mut max = center;
+
+    for i in 0..3 {
+        let extent = (a[i] * a[i] + b[i] * b[i]).sqrt();
+
+        min.coords.components[i] = min.coords.components[i] - extent;
+        max.coords.components[i] += extent;
+    }
+
+    Aabb { min, max }
+}
+
+/// Compute the distance between an edge and a point
+pub fn distance_to_edge(edge: &Edge, point: &Point<3>) -> Scalar {
+    let curve = edge.curve();
+    let t = curve.point_model_to_curve(point);
+
+    let vertices = match edge.vertices() {
+        Some(vertices) => vertices.map(|vertex| vertex.point()),
+        None => return (curve.point_curve_to_model(&t) - *point).magnitude(),
+    };
+
+    let range = curve.range_between(vertices);
+    let mut distance = vertices
+        .iter()
+        .map(|vertex| (*vertex - *point).magnitude())
+        .min()
+        // Can't panic. There are two vertices.
+        .unwrap();
+
+    if in_range(t.t, range, &curve) {
+        distance =
+            distance.min((curve.point_curve_to_model(&t) - *point).magnitude());
+    }
+
+    distance
+}
+
+/// A bounding volume hierarchy
+///
+/// Supports insertion of items, by using the logarithmic method: The items are
+/// distributed over multiple static trees, whose sizes are distinct powers of
+/// two. When an item is inserted, the smaller trees are merged, until the
+/// number of items matches an unused power of two.
+///
+/// This keeps all trees balanced, and makes insertion and queries take
+/// logarithmic time (amortized, in the case of insertion).
+#[derive(Clone, Debug)]
+pub struct Bvh<T> {
+    trees: Vec<Option<Tree<T>>>,
+}
+
+impl<T: Copy> Bvh<T> {
+    /// Construct an empty BVH
+    pub fn new() -> Self {
+        Self { trees: Vec::new() }
+    }
+
+    /// Construct a BVH from a list of items
+    pub fn from_items(items: impl IntoIterator<Item = (Aabb<3>, T)>) -> Self {
+        let mut bvh = Self::new();
+        for (aabb, item) in items {
+            bvh.insert(aabb, item);
+        }
+        bvh
+    }
+
+    /// Insert an item
+    pub fn insert(&mut self, aabb: Aabb<3>, item: T) {
+        let mut items = vec![(aabb, item)];
+
+        for slot in &mut self.trees {
+            match slot.take() {
+                Some(tree) => items.extend(tree.items),
+                None => {
+                    *slot = Some(Tree::build(items));
+                    return;
+                }
+            }
+        }
+
+        self.trees.push(Some(Tree::build(items)));
+    }
+
+    /// Find all items whose AABB intersects the provided one
+    pub fn query(&self, aabb: &Aabb<3>) -> Vec<T> {
+        let mut result = Vec::new();
+
+        for tree in self.trees.iter().flatten() {
+            let mut stack = vec![0];
+
+            while let Some(i) = stack.pop() {
+                let node = &tree.nodes[i];
+                if !node.aabb.intersects(aabb) {
+                    continue;
+                }
+
+                match node.kind {
+                    NodeKind::Leaf { start, end } => {
+                        result.extend(
+                            tree.items[start..end]
+                                .iter()
+                                .filter(|(item_aabb, _)| {
+                                    item_aabb.intersects(aabb)
+                                })
+                                .map(|(_, item)| *item),
+                        );
+                    }
+                    NodeKind::Inner { children } => stack.extend(children),
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Find the item that is nearest to a point
+    ///
+    /// `distance` computes the distance between an item and the point. It must
+    /// never be smaller than the distance between the item's AABB and the
+    /// point.
+    pub fn nearest(
+        &self,
+        point: &Point<3>,
+        mut distance: impl FnMut(T) -> Scalar,
+    ) -> Option<(T, Scalar)> {
+        let mut nearest: Option<(T, Scalar)> = None;
+
+        for tree in self.trees.iter().flatten() {
+            let mut stack = vec![0];
+
+            while let Some(i) = stack.pop() {
+                let node = &tree.nodes[i];
+                if let Some((_, nearest)) = nearest {
+                    if node.aabb.distance_to_point(point) >= nearest {
+                        continue;
+                    }
+                }
+
+                match node.kind {
+                    NodeKind::Leaf { start, end } => {
+                        for &(_, item) in &tree.items[start..end] {
+                            let distance = distance(item);
+
+                            let is_nearer = match nearest {
+                                Some((_, nearest)) => distance < nearest,
+                                None => true,
+                            };
+                            if is_nearer {
+                                nearest = Some((item, distance));
+                            }
+                        }
+                    }
+                    NodeKind::Inner { children } => {
+                        // Visit the closer child first, as that is more likely
+                        // to contain the nearest item, making it possible to
+                        // skip the other one.
+                        let [a, b] = children;
+                        let [distance_a, distance_b] = [a, b].map(|child| {
+                            tree.nodes[child].aabb.distance_to_point(point)
+                        });
+
+                        if distance_a < distance_b {
+                            stack.extend([b, a]);
+                        } else {
+                            stack.extend([a, b]);
+                        }
+                    }
+                }
+            }
+        }
+
+        nearest
+    }
+}
+
+impl<T: Copy> Default for Bvh<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Tree<T> {
+    nodes: Vec<Node>,
+    items: Vec<(Aabb<3>, T)>,
+}
+
+impl<T> Tree<T> {
+    /// The maximum number of items in a leaf node
+    const LEAF_SIZE: usize = 4;
+
+    fn build(mut items: Vec<(Aabb<3>, T)>) -> Self {
+        let mut nodes = Vec::new();
+        build_node(&mut nodes, &mut items, 0);
+
+        Self { nodes, items }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Node {
+    aabb: Aabb<3>,
+    kind: NodeKind,
+}
+
+#[derive(Clone, Debug)]
+enum NodeKind {
+    Leaf { start: usize, end: usize },
+    Inner { children: [usize; 2] },
+}
+
+/// Build the node for the given items, returning its index
+///
+/// Splits the items at the median of their centers, along the longest axis of
+/// their AABB. `offset` is the index of the first of the items in the tree.
+fn build_node<T>(
+    nodes: &mut Vec<Node>,
+    items: &mut [(Aabb<3>, T)],
+    offset: usize,
+) -> usize {
+    let aabb = items
+        .iter()
+        .map(|(aabb, _)| *aabb)
+        .reduce(|a, b| a.merged(&b))
+        // Can't panic. Trees are never built from an empty list of items, and
+        // the items are never split into an empty half.
+        .unwrap();
+
+    let index = nodes.len();
+
+    if items.len() <= Tree::<T>::LEAF_SIZE {
+        nodes.push(Node {
+            aabb,
+            kind: NodeKind::Leaf {
+                start: offset,
+                end: offset + items.len(),
+            },
+        });
+        return index;
+    }
+
+    // Push a placeholder, which is replaced once the children are built.
+    nodes.push(Node {
+        aabb,
+        kind: NodeKind::Inner { children: [0, 0] },
+    });
+
+    let size = aabb.size().components;
+    let axis = (0..3)
+        .max_by_key(|&axis| size[axis])
+        // Can't panic. The range is not empty.
+        .unwrap();
+
+    let mid = items.len() / 2;
+    items.select_nth_unstable_by_key(mid, |(aabb, _)| {
+        aabb.center().coords.components[axis]
+    });
+
+    let (a, b) = items.split_at_mut(mid);
+    let a = build_node(nodes, a, offset);
+    let b = build_node(nodes, b, offset + mid);
+
+    nodes[index].kind = NodeKind::Inner { children: [a, b] };
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Aabb, Point, Scalar};
+
+    use super::Bvh;
+
+    #[test]
+    fn query_and_nearest() {
+        let points: Vec<_> = (0..100)
+            .map(|i| Point::from([(i % 10) as f64, (i / 10) as f64, 0.]))
+            .collect();
+
+        let bvh = Bvh::from_items(
+            points
+                .iter()
+                .enumerate()
+                .map(|(i, point)| (Aabb::<3>::from_points([*point]), i)),
+        );
+
+        let query = Aabb {
+            min: Point::from([1.5, 1.5, -1.]),
+            max: Point::from([3.5, 2.5, 1.]),
+        };
+        let mut found = bvh.query(&query);
+        found.sort();
+        assert_eq!(found, vec![22, 23]);
+
+        let point = Point::from([4.2, 6.9, 1.]);
+        let distance = |i: usize| (points[i] - point).magnitude();
+        let (nearest, _) = bvh.nearest(&point, distance).unwrap();
+        assert_eq!(nearest, 74);
+
+        let empty = Bvh::<usize>::new();
+        assert!(empty.nearest(&point, |_| Scalar::ZERO).is_none());
+    }
+}
@@ -1,19 +1,20 @@
 use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
     hash::{Hash, Hasher},
     sync::Arc,
 };
 
 use anymap::AnyMap;
-use fj_math::Point;
-use parking_lot::{RwLock, RwLockReadGuard};
+use fj_math::{Aabb, Point, Scalar};
+use parking_lot::{MappedRwLockReadGuard, RwLock, RwLockReadGuard};
 use slotmap::{DefaultKey, SlotMap};
 
 use crate::{
     geometry::{Curve, Surface},
-    topology::{Cycle, Edge, Face, Vertex},
+    topology::{Cycle, Edge, Face, Shell, Solid, Vertex},
 };
 
-use super::Object;
+use super::{spatial::Bvh, Object};
 
 #[derive(Clone, Debug)]
 pub struct Stores {
@@ -25,6 +26,8 @@ pub struct Stores {
     pub edges: Edges,
     pub cycles: Cycles,
     pub faces: Faces,
+    pub shells: Shells,
+    pub solids: Solids,
 }
 
 impl Stores {
@@ -42,6 +45,8 @@ impl Stores {
         stores.insert(self.edges.clone());
         stores.insert(self.cycles.clone());
         stores.insert(self.faces.clone());
+        stores.insert(self.shells.clone());
+        stores.insert(self.solids.clone());
 
         stores
             .remove::<Store<T>>()
@@ -59,58 +64,157 @@ pub type Vertices = Store<Vertex>;
 pub type Edges = Store<Edge>;
 pub type Cycles = Store<Cycle>;
 pub type Faces = Store<Face>;
+pub type Shells = Store<Shell>;
+pub type Solids = Store<Solid>;
 
 #[derive(Debug)]
 pub struct Store<T> {
-    objects: Arc<RwLock<Objects<T>>>,
+    inner: Arc<RwLock<StoreInner<T>>>,
 }
 
-impl<T> Store<T> {
+impl<T: Object> Store<T> {
     pub fn new() -> Self {
         Self {
-            objects: Arc::new(RwLock::new(SlotMap::new())),
+            inner: Arc::new(RwLock::new(StoreInner {
+                objects: SlotMap::new(),
+                keys: Vec::new(),
+                index: HashMap::new(),
+                bvh: Bvh::new(),
+            })),
         }
     }
 
     pub fn insert(&mut self, object: T) -> Handle<T> {
-        let key = self.objects.write().insert(object);
+        // Compute the hash and bounding volume before locking. For topological
+        // objects, that requires access to the other stores.
+        let hash = hash(&object);
+        let aabb = object.bounding_volume();
+
+        let mut inner = self.inner.write();
+        let key = inner.objects.insert(object);
+        inner.keys.push(key);
+        inner.index.entry(hash).or_default().push(key);
+        if let Some(aabb) = aabb {
+            inner.bvh.insert(aabb, key);
+        }
+        drop(inner);
+
         Handle::new(key, self.clone())
     }
 
-    pub fn contains(&self, object: &Handle<T>) -> bool {
-        object.store() == self && self.objects.read().contains_key(object.key())
+    /// Find an object that is equal to the provided one
+    pub fn find(&self, object: &T) -> Option<Handle<T>> {
+        self.find_by_hash(object)
+            .into_iter()
+            .find(|handle| &handle.get() == object)
     }
 
-    pub fn read(&self) -> RwLockReadGuard<Objects<T>> {
-        self.objects.read()
+    /// Find all objects that have the same hash as the provided one
+    ///
+    /// The result includes all objects that are equal to the provided one, but
+    /// might contain others.
+    pub fn find_by_hash(&self, object: &T) -> Vec<Handle<T>> {
+        let hash = hash(object);
+
+        self.inner
+            .read()
+            .index
+            .get(&hash)
+            .into_iter()
+            .flatten()
+            .map(|&key| Handle::new(key, self.clone()))
+            .collect()
     }
 
     pub fn iter(&self) -> Iter<T> {
-        // The allocation here is unfortunate, but I think it's fine for now. If
-        // this turns into a performance issue, it should be possible to avoid
-        // it by adding methods to `Store`, that are geared towards implementing
-        // iterators.
         Iter {
-            elements: self
-                .objects
-                .read()
-                .iter()
-                .map(|(key, _)| Handle::new(key, self.clone()))
-                .collect(),
+            store: self.clone(),
+            next: 0,
+            end: self.inner.read().keys.len(),
         }
     }
 
+    /// Update all objects in the store
+    ///
+    /// Rebuilds the index of the store afterwards, as the update is likely to
+    /// change the hashes of the objects.
     pub fn update<F>(&mut self, mut f: F)
     where
         F: FnMut(&mut T),
     {
-        for (_, object) in self.objects.write().iter_mut() {
+        for (_, object) in self.inner.write().objects.iter_mut() {
             f(object);
         }
+
+        self.reindex();
+    }
+
+    /// Rebuild the indices of the store
+    ///
+    /// The hashes and bounding volumes of topological objects depend on the
+    /// geometry they refer to. This must be called, whenever that geometry
+    /// changes.
+    pub fn reindex(&mut self) {
+        let mut inner = self.inner.write();
+
+        let mut index: HashMap<_, Vec<_>> = HashMap::new();
+        for (key, object) in &inner.objects {
+            index.entry(hash(object)).or_default().push(key);
+        }
+
+        let bvh = Bvh::from_items(inner.objects.iter().filter_map(
+            |(key, object)| Some((object.bounding_volume()?, key)),
+        ));
+
+        inner.index = index;
+        inner.bvh = bvh;
+    }
+}
+
+impl<T> Store<T> {
+    pub fn contains(&self, object: &Handle<T>) -> bool {
+        object.store() == self
+            && self.inner.read().objects.contains_key(object.key())
+    }
+
+    /// Find all objects whose bounding volume intersects the provided AABB
+    ///
+    /// Only returns objects that are part of the spatial index. See
+    /// [`BoundingVolume`].
+    pub fn query(&self, aabb: &Aabb<3>) -> Vec<Handle<T>> {
+        self.inner
+            .read()
+            .bvh
+            .query(aabb)
+            .into_iter()
+            .map(|key| Handle::new(key, self.clone()))
+            .collect()
+    }
+
+    /// Find the object that is nearest to the provided point
+    ///
+    /// Only considers objects that are part of the spatial index. See
+    /// [`BoundingVolume`] and [`Bvh::nearest`].
+    pub fn nearest(
+        &self,
+        point: &Point<3>,
+        distance: impl Fn(&T, &Point<3>) -> Scalar,
+    ) -> Option<Handle<T>> {
+        let inner = self.inner.read();
+
+        let (key, _) = inner
+            .bvh
+            .nearest(point, |key| distance(&inner.objects[key], point))?;
+
+        Some(Handle::new(key, self.clone()))
+    }
+
+    pub fn read(&self) -> MappedRwLockReadGuard<Objects<T>> {
+        RwLockReadGuard::map(self.inner.read(), |inner| &inner.objects)
     }
 
     fn ptr(&self) -> *const () {
-        Arc::as_ptr(&self.objects) as _
+        Arc::as_ptr(&self.inner) as _
     }
 }
 
@@ -120,12 +224,12 @@ impl<T> Store<T> {
 impl<T> Clone for Store<T> {
     fn clone(&self) -> Self {
         Self {
-            objects: self.objects.clone(),
+            inner: self.inner.clone(),
         }
     }
 }
 
-impl<T> Default for Store<T> {
+impl<T: Object> Default for Store<T> {
     fn default() -> Self {
         Self::new()
     }
@@ -159,6 +263,29 @@ impl<T> Hash for Store<T> {
 
 pub type Objects<T> = SlotMap<DefaultKey, T>;
 
+#[derive(Debug)]
+struct StoreInner<T> {
+    objects: Objects<T>,
+
+    /// The keys of all objects, in the order they were inserted
+    ///
+    /// Used for iterating over the objects. Objects are never removed, so
+    /// positions in this list stay valid.
+    keys: Vec<DefaultKey>,
+
+    /// The keys of all objects, by the hash of the object
+    index: HashMap<u64, Vec<DefaultKey>>,
+
+    /// The keys of all objects that have a bounding volume
+    bvh: Bvh<DefaultKey>,
+}
+
+fn hash<T: Hash>(object: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    object.hash(&mut hasher);
+    hasher.finish()
+}
+
 /// A handle to an object stored within [`Shape`]
 ///
 /// If an object of type `T` (this could be `Curve`, `Vertex`, etc.) is added to
@@ -220,8 +347,13 @@ impl<T> Handle<T> {
 /// An iterator over geometric or topological objects
 ///
 /// Returned by various methods of the [`Shape`] API.
+///
+/// Only yields the objects that were part of the shape when the iterator was
+/// created.
 pub struct Iter<T> {
-    elements: Vec<Handle<T>>,
+    store: Store<T>,
+    next: usize,
+    end: usize,
 }
 
 impl<T> Iter<T> {
@@ -232,7 +364,7 @@ impl<T> Iter<T> {
     where
         T: Clone,
     {
-        self.elements.into_iter().map(|handle| handle.get())
+        self.map(|handle| handle.get())
     }
 }
 
@@ -240,6 +372,20 @@ impl<T> Iterator for Iter<T> {
     type Item = Handle<T>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.elements.pop()
+        if self.next >= self.end {
+            return None;
+        }
+
+        let key = self.store.inner.read().keys[self.next];
+        self.next += 1;
+
+        Some(Handle::new(key, self.store.clone()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.next;
+        (len, Some(len))
     }
 }
+
+impl<T> ExactSizeIterator for Iter<T> {}
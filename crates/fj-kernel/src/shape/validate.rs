@@ -1,10 +1,14 @@
 use std::collections::HashSet;
 
-use fj_math::{Point, Scalar};
+use fj_math::{Aabb, Point, Scalar};
 
 use crate::{
+    algorithms::{
+        intersection::{curve_curve, CurveCurveIntersection},
+        Tolerance,
+    },
     geometry::{Curve, Surface},
-    topology::{Cycle, Edge, Face, Vertex},
+    topology::{Cycle, Edge, Face, Shell, Solid, Vertex},
 };
 
 use super::{stores::Stores, Handle};
@@ -50,8 +54,10 @@ impl Validate for Vertex {
         if !stores.points.contains(&self.point) {
             return Err(StructuralIssues::default().into());
         }
-        for existing in stores.vertices.iter() {
-            let distance = (existing.get().point() - self.point()).magnitude();
+        let point = self.point();
+        let nearby = Aabb::<3>::from_points([point]).loosened(min_distance);
+        for existing in stores.vertices.query(&nearby) {
+            let distance = (existing.get().point() - point).magnitude();
 
             if distance < min_distance {
                 return Err(ValidationError::Uniqueness);
@@ -98,15 +104,12 @@ impl Validate for Edge {
 impl Validate for Cycle {
     /// Validate the cycle
     ///
-    /// # Implementation note
-    ///
-    /// The validation of the cycle should be extended to cover more cases:
-    /// - That those edges form a cycle.
-    /// - That the cycle is not self-overlapping.
-    /// - That there exists no duplicate cycle, with the same edges.
+    /// Checks, in addition to the structural validation, that the edges form a
+    /// single closed loop, that the cycle doesn't overlap itself, and that no
+    /// cycle with the same edges in the same directions already exists.
     fn validate(
         &self,
-        _: Scalar,
+        min_distance: Scalar,
         stores: &Stores,
     ) -> Result<(), ValidationError> {
         let mut missing_edges = HashSet::new();
@@ -124,42 +127,282 @@ impl Validate for Cycle {
             .into());
         }
 
+        validate_cycle_is_closed(self)?;
+        validate_cycle_is_not_self_overlapping(self, min_distance)?;
+
+        // Cycles that run through the same edges in the same directions are
+        // the same cycle, regardless of which edge they start with.
+        let sorted_uses = |cycle: &Cycle| {
+            let mut uses: Vec<_> = cycle
+                .edges
+                .iter()
+                .cloned()
+                .zip(cycle.reversed.iter().copied())
+                .collect();
+            uses.sort();
+            uses
+        };
+
+        let edges = sorted_uses(self);
+        for existing in stores.cycles.find_by_hash(self) {
+            let existing_edges = sorted_uses(&existing.get());
+
+            if existing_edges == edges {
+                return Err(ValidationError::DuplicateCycle(existing));
+            }
+        }
+
         Ok(())
     }
 }
 
+/// Validate that the edges of a cycle form a single closed loop
+///
+/// Every edge must begin where the previous one ends, in the direction in
+/// which the cycle runs through them. The last edge must end where the first
+/// one begins.
+fn validate_cycle_is_closed(cycle: &Cycle) -> Result<(), ValidationError> {
+    if cycle.edges.len() != cycle.reversed.len() {
+        return Err(ValidationError::MissingEdgeDirections);
+    }
+    if cycle.edges.is_empty() {
+        return Err(ValidationError::OpenCycle(Vec::new()));
+    }
+
+    // An edge without vertices connects to itself. It forms a cycle on its
+    // own, and can't be part of a larger one.
+    let continuous: Vec<_> = cycle
+        .edges
+        .iter()
+        .filter(|edge| edge.get().vertices.is_none())
+        .cloned()
+        .collect();
+    if !continuous.is_empty() {
+        if cycle.edges.len() == 1 {
+            return Ok(());
+        }
+
+        return Err(ValidationError::OpenCycle(continuous));
+    }
+
+    // Can't panic. We just checked that all edges have vertices.
+    let vertices: Vec<_> = cycle
+        .edges
+        .iter()
+        .zip(&cycle.reversed)
+        .map(|(edge, &reversed)| {
+            let mut vertices = edge.get().vertices.unwrap();
+            if reversed {
+                vertices.reverse();
+            }
+            vertices
+        })
+        .collect();
+
+    for (i, [_, end]) in vertices.iter().enumerate() {
+        let j = (i + 1) % vertices.len();
+        let [start, _] = &vertices[j];
+
+        if start != end {
+            return Err(ValidationError::OpenCycle(vec![
+                cycle.edges[i].clone(),
+                cycle.edges[j].clone(),
+            ]));
+        }
+    }
+
+    Ok(())
+}
+
+/// Validate that no two edges of a cycle overlap
+///
+/// Edges may only touch at the vertices they share.
+fn validate_cycle_is_not_self_overlapping(
+    cycle: &Cycle,
+    min_distance: Scalar,
+) -> Result<(), ValidationError> {
+    // Can't panic. The minimum distance of a shape is always positive.
+    let tolerance = Tolerance::from_scalar(min_distance).unwrap();
+
+    for (i, a) in cycle.edges.iter().enumerate() {
+        for b in &cycle.edges[i + 1..] {
+            let [edge_a, edge_b] = [a.get(), b.get()];
+
+            // Edges without vertices are only valid on their own, so we can
+            // expect all edges to have vertices here.
+            let (vertices_a, vertices_b) =
+                match (edge_a.vertices(), edge_b.vertices()) {
+                    (Some(a), Some(b)) => (a, b),
+                    _ => continue,
+                };
+
+            let shared: Vec<_> = vertices_a
+                .iter()
+                .filter(|vertex| vertices_b.contains(vertex))
+                .map(|vertex| vertex.point())
+                .collect();
+            let is_shared = |point: Point<3>| {
+                shared
+                    .iter()
+                    .any(|shared| (*shared - point).magnitude() <= min_distance)
+            };
+
+            let [curve_a, curve_b] = [edge_a.curve(), edge_b.curve()];
+            let range_a = curve_a
+                .range_between(vertices_a.clone().map(|vertex| vertex.point()));
+            let range_b = curve_b
+                .range_between(vertices_b.clone().map(|vertex| vertex.point()));
+
+            let on_edge = |point: Point<3>,
+                           curve: &Curve,
+                           range: [Point<1>; 2],
+                           vertices: &[Vertex; 2]| {
+                let near_vertex = vertices.iter().any(|vertex| {
+                    (vertex.point() - point).magnitude() <= min_distance
+                });
+                near_vertex
+                    || in_range(
+                        curve.point_model_to_curve(&point).t,
+                        range,
+                        curve,
+                    )
+            };
+
+            for intersection in curve_curve(&curve_a, &curve_b, tolerance) {
+                let point = match intersection {
+                    CurveCurveIntersection::Point { point, .. } => point,
+                    CurveCurveIntersection::Coincident { on_a, .. } => {
+                        // The curves overlap. Check whether the edges overlap
+                        // too, by looking at the middle of the part of the
+                        // overlap that lies within the first edge.
+                        match overlap(
+                            on_a.map(|point| point.t),
+                            range_a.map(|point| point.t),
+                            &curve_a,
+                        ) {
+                            Some([start, end]) if start < end => curve_a
+                                .point_curve_to_model(&Point::from([(start
+                                    + end)
+                                    / 2.])),
+                            _ => continue,
+                        }
+                    }
+                };
+
+                if is_shared(point) {
+                    continue;
+                }
+
+                if on_edge(point, &curve_a, range_a, &vertices_a)
+                    && on_edge(point, &curve_b, range_b, &vertices_b)
+                {
+                    return Err(ValidationError::SelfOverlappingCycle([
+                        a.clone(),
+                        b.clone(),
+                    ]));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Determine whether a curve coordinate lies within a range
+///
+/// Takes the period of the curve into account, if it's periodic.
+pub(super) fn in_range(
+    t: Scalar,
+    [start, end]: [Point<1>; 2],
+    curve: &Curve,
+) -> bool {
+    let [start, end] = [start.t, end.t];
+
+    match curve.period() {
+        Some(period) => {
+            let mut t = (t - start) % period;
+            if t < Scalar::ZERO {
+                t += period;
+            }
+
+            start + t <= end
+        }
+        None => start.min(end) <= t && t <= start.max(end),
+    }
+}
+
+/// Compute the overlap between two ranges of curve coordinates
+///
+/// Takes the period of the curve into account, if it's periodic.
+fn overlap(
+    a: [Scalar; 2],
+    b: [Scalar; 2],
+    curve: &Curve,
+) -> Option<[Scalar; 2]> {
+    let sort = |[start, end]: [Scalar; 2]| [start.min(end), start.max(end)];
+    let [a, b] = [sort(a), sort(b)];
+
+    let shifts = match curve.period() {
+        Some(period) => vec![-period, Scalar::ZERO, period],
+        None => vec![Scalar::ZERO],
+    };
+
+    shifts
+        .into_iter()
+        .map(|shift| [a[0].max(b[0] + shift), a[1].min(b[1] + shift)])
+        .filter(|[start, end]| start <= end)
+        .max_by_key(|[start, end]| *end - *start)
+}
+
 impl Validate for Face {
+    /// Validate the face
+    ///
+    /// Checks, in addition to the structural validation, that the edges of all
+    /// cycles lie in the surface of the face.
     fn validate(
         &self,
-        _: Scalar,
+        min_distance: Scalar,
         stores: &Stores,
     ) -> Result<(), ValidationError> {
-        if let Face::Face {
+        let Face {
             surface,
             exteriors,
             interiors,
             ..
-        } = self
-        {
-            let mut missing_surface = None;
-            let mut missing_cycles = HashSet::new();
+        } = self;
+        let mut missing_surface = None;
+        let mut missing_cycles = HashSet::new();
 
-            if !stores.surfaces.contains(surface) {
-                missing_surface = Some(surface.clone());
+        if !stores.surfaces.contains(surface) {
+            missing_surface = Some(surface.clone());
+        }
+        for cycle in exteriors.iter().chain(interiors) {
+            if !stores.cycles.contains(cycle) {
+                missing_cycles.insert(cycle.clone());
             }
-            for cycle in exteriors.iter().chain(interiors) {
-                if !stores.cycles.contains(cycle) {
-                    missing_cycles.insert(cycle.clone());
-                }
+        }
+
+        if missing_surface.is_some() || !missing_cycles.is_empty() {
+            return Err(StructuralIssues {
+                missing_surface,
+                missing_cycles,
+                ..StructuralIssues::default()
             }
+            .into());
+        }
 
-            if missing_surface.is_some() || !missing_cycles.is_empty() {
-                return Err(StructuralIssues {
-                    missing_surface,
-                    missing_cycles,
-                    ..StructuralIssues::default()
+        for cycle in exteriors.iter().chain(interiors) {
+            for edge in cycle.get().edges {
+                if !edge_lies_in_surface(
+                    &edge.get(),
+                    &surface.get(),
+                    min_distance,
+                ) {
+                    return Err(ValidationError::EdgeNotInSurface {
+                        edge,
+                        surface: surface.clone(),
+                    });
                 }
-                .into());
             }
         }
 
@@ -167,6 +410,115 @@ impl Validate for Face {
     }
 }
 
+impl Validate for Shell {
+    fn validate(
+        &self,
+        _: Scalar,
+        stores: &Stores,
+    ) -> Result<(), ValidationError> {
+        let mut missing_faces = HashSet::new();
+        for face in &self.faces {
+            if !stores.faces.contains(face) {
+                missing_faces.insert(face.clone());
+            }
+        }
+
+        if !missing_faces.is_empty() {
+            return Err(StructuralIssues {
+                missing_faces,
+                ..StructuralIssues::default()
+            }
+            .into());
+        }
+
+        Ok(())
+    }
+}
+
+impl Validate for Solid {
+    fn validate(
+        &self,
+        _: Scalar,
+        stores: &Stores,
+    ) -> Result<(), ValidationError> {
+        let mut missing_shells = HashSet::new();
+        for shell in [&self.exterior].into_iter().chain(&self.interiors) {
+            if !stores.shells.contains(shell) {
+                missing_shells.insert(shell.clone());
+            }
+        }
+
+        if !missing_shells.is_empty() {
+            return Err(StructuralIssues {
+                missing_shells,
+                ..StructuralIssues::default()
+            }
+            .into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Determine whether an edge lies in a surface
+///
+/// Checks the vertices of the edge, as well as a number of points sampled from
+/// its curve.
+fn edge_lies_in_surface(
+    edge: &Edge,
+    surface: &Surface,
+    min_distance: Scalar,
+) -> bool {
+    let curve = edge.curve();
+
+    let range = match edge.vertices() {
+        Some(vertices) => {
+            curve.range_between(vertices.map(|vertex| vertex.point()))
+        }
+        None => match curve.closed_range() {
+            Some(range) => range,
+            None => {
+                // A continuous edge on a curve that doesn't connect to itself
+                // doesn't have a range we could check. Only look at its
+                // origin.
+                let origin = curve.point_curve_to_model(&Point::from([0.]));
+                return point_lies_in_surface(origin, surface, min_distance);
+            }
+        },
+    };
+
+    let [start, end] = range.map(|point| point.t);
+    let samples = (0..=NUM_EDGE_SAMPLES).map(|i| {
+        let t = start + (end - start) * (i as f64 / NUM_EDGE_SAMPLES as f64);
+        curve.point_curve_to_model(&Point::from([t]))
+    });
+
+    let vertices = edge
+        .vertices()
+        .into_iter()
+        .flatten()
+        .map(|vertex| vertex.point());
+
+    vertices
+        .chain(samples)
+        .all(|point| point_lies_in_surface(point, surface, min_distance))
+}
+
+fn point_lies_in_surface(
+    point: Point<3>,
+    surface: &Surface,
+    min_distance: Scalar,
+) -> bool {
+    let on_surface = surface.point_surface_to_model(
+        &surface.point_model_to_surface(point).native(),
+    );
+    (on_surface - point).magnitude() <= min_distance
+}
+
+/// The number of segments an edge is divided into, when checking whether it
+/// lies in a surface
+const NUM_EDGE_SAMPLES: usize = 16;
+
 /// Returned by the various `add_` methods of the [`Shape`] API
 pub type ValidationResult<T> = Result<Handle<T>, ValidationError>;
 
@@ -178,7 +530,7 @@ pub enum ValidationError {
     /// Structural validation verifies, that all the object that an object
     /// refers to are already part of the shape.
     #[error("Structural validation failed")]
-    Structural(StructuralIssues),
+    Structural(Box<StructuralIssues>),
 
     /// Uniqueness validation failed
     ///
@@ -197,14 +549,48 @@ pub enum ValidationError {
     #[error("Geometric validation failed")]
     #[allow(unused)]
     Geometric,
+
+    /// The edges of a cycle don't form a single closed loop
+    ///
+    /// Carries the edges at which the cycle is broken: Either two consecutive
+    /// edges, the first of which doesn't end where the second one begins, or
+    /// edges without vertices, which can't connect to any other edge.
+    #[error("Edges of cycle don't form a closed loop")]
+    OpenCycle(Vec<Handle<Edge>>),
+
+    /// A cycle doesn't store a direction for each of its edges
+    #[error("Cycle doesn't have a direction for each of its edges")]
+    MissingEdgeDirections,
+
+    /// Two edges of a cycle overlap
+    ///
+    /// Edges of a cycle may only touch at the vertices they share.
+    #[error("Cycle overlaps itself")]
+    SelfOverlappingCycle([Handle<Edge>; 2]),
+
+    /// A cycle with the same edges in the same directions already exists
+    ///
+    /// Carries the existing cycle.
+    #[error("Cycle with the same edges already exists")]
+    DuplicateCycle(Handle<Cycle>),
+
+    /// An edge that bounds a face doesn't lie in the face's surface
+    #[error("Edge of face doesn't lie in the surface")]
+    EdgeNotInSurface {
+        /// The edge that doesn't lie in the surface
+        edge: Handle<Edge>,
+
+        /// The surface of the face
+        surface: Handle<Surface>,
+    },
 }
 
 impl ValidationError {
     /// Indicate whether validation found a missing curve
     #[cfg(test)]
     pub fn missing_curve(&self, curve: &Handle<Curve>) -> bool {
-        if let Self::Structural(StructuralIssues { missing_curve, .. }) = self {
-            return missing_curve.as_ref() == Some(curve);
+        if let Self::Structural(issues) = self {
+            return issues.missing_curve.as_ref() == Some(curve);
         }
 
         false
@@ -213,11 +599,8 @@ impl ValidationError {
     /// Indicate whether validation found a missing vertex
     #[cfg(test)]
     pub fn missing_vertex(&self, vertex: &Handle<Vertex>) -> bool {
-        if let Self::Structural(StructuralIssues {
-            missing_vertices, ..
-        }) = self
-        {
-            return missing_vertices.contains(vertex);
+        if let Self::Structural(issues) = self {
+            return issues.missing_vertices.contains(vertex);
         }
 
         false
@@ -226,8 +609,8 @@ impl ValidationError {
     /// Indicate whether validation found a missing edge
     #[cfg(test)]
     pub fn missing_edge(&self, edge: &Handle<Edge>) -> bool {
-        if let Self::Structural(StructuralIssues { missing_edges, .. }) = self {
-            return missing_edges.contains(edge);
+        if let Self::Structural(issues) = self {
+            return issues.missing_edges.contains(edge);
         }
 
         false
@@ -236,11 +619,8 @@ impl ValidationError {
     /// Indicate whether validation found a missing surface
     #[cfg(test)]
     pub fn missing_surface(&self, surface: &Handle<Surface>) -> bool {
-        if let Self::Structural(StructuralIssues {
-            missing_surface, ..
-        }) = self
-        {
-            return missing_surface.as_ref() == Some(surface);
+        if let Self::Structural(issues) = self {
+            return issues.missing_surface.as_ref() == Some(surface);
         }
 
         false
@@ -249,9 +629,28 @@ impl ValidationError {
     /// Indicate whether validation found a missing cycle
     #[cfg(test)]
     pub fn missing_cycle(&self, cycle: &Handle<Cycle>) -> bool {
-        if let Self::Structural(StructuralIssues { missing_cycles, .. }) = self
-        {
-            return missing_cycles.contains(cycle);
+        if let Self::Structural(issues) = self {
+            return issues.missing_cycles.contains(cycle);
+        }
+
+        false
+    }
+
+    /// Indicate whether validation found a missing face
+    #[cfg(test)]
+    pub fn missing_face(&self, face: &Handle<Face>) -> bool {
+        if let Self::Structural(issues) = self {
+            return issues.missing_faces.contains(face);
+        }
+
+        false
+    }
+
+    /// Indicate whether validation found a missing shell
+    #[cfg(test)]
+    pub fn missing_shell(&self, shell: &Handle<Shell>) -> bool {
+        if let Self::Structural(issues) = self {
+            return issues.missing_shells.contains(shell);
         }
 
         false
@@ -260,7 +659,7 @@ impl ValidationError {
 
 impl From<StructuralIssues> for ValidationError {
     fn from(issues: StructuralIssues) -> Self {
-        Self::Structural(issues)
+        Self::Structural(Box::new(issues))
     }
 }
 
@@ -283,4 +682,10 @@ pub struct StructuralIssues {
 
     /// Missing cycles found in face validation
     pub missing_cycles: HashSet<Handle<Cycle>>,
+
+    /// Missing faces found in shell validation
+    pub missing_faces: HashSet<Handle<Face>>,
+
+    /// Missing shells found in solid validation
+    pub missing_shells: HashSet<Handle<Shell>>,
 }
@@ -1,7 +1,7 @@
 use fj_math::{Point, Scalar, Vector};
 
 use crate::{
-    geometry::{Circle, Curve, Line, Surface},
+    geometry::{Circle, Curve, Ellipse, Line, Surface},
     shape::{Handle, Shape, ValidationResult},
 };
 
@@ -61,6 +61,58 @@ impl<'r> EdgeBuilder<'r> {
         Ok(edge)
     }
 
+    /// Build an arc of a circle from a radius and two angles
+    ///
+    /// The arc starts at the first angle and goes counter-clockwise (as seen
+    /// from above the xy-plane) to the second one.
+    pub fn build_arc(
+        self,
+        radius: Scalar,
+        angles: [Scalar; 2],
+    ) -> ValidationResult<Edge> {
+        let circle = Circle {
+            center: Point::origin(),
+            a: Vector::from([radius, Scalar::ZERO, Scalar::ZERO]),
+            b: Vector::from([Scalar::ZERO, radius, Scalar::ZERO]),
+        };
+
+        let vertices = angles.map(|angle| {
+            let point = circle.point_curve_to_model(&Point::from([angle]));
+            Vertex::builder(self.shape).build_from_point(point)
+        });
+        let vertices = match vertices {
+            [Ok(a), Ok(b)] => Ok([a, b]),
+            [Err(err), _] | [_, Err(err)] => Err(err),
+        }?;
+
+        let curve = self.shape.insert(Curve::Circle(circle))?;
+        let edge = self.shape.insert(Edge {
+            curve,
+            vertices: Some(vertices),
+        })?;
+
+        Ok(edge)
+    }
+
+    /// Build an ellipse from its two radii
+    ///
+    /// The first radius is along the x-axis, the second along the y-axis.
+    pub fn build_ellipse(self, radii: [Scalar; 2]) -> ValidationResult<Edge> {
+        let [a, b] = radii;
+
+        let curve = self.shape.insert(Curve::Ellipse(Ellipse {
+            center: Point::origin(),
+            a: Vector::from([a, Scalar::ZERO, Scalar::ZERO]),
+            b: Vector::from([Scalar::ZERO, b, Scalar::ZERO]),
+        }))?;
+        let edge = self.shape.insert(Edge {
+            curve,
+            vertices: None,
+        })?;
+
+        Ok(edge)
+    }
+
     /// Build a line segment from two points
     pub fn build_line_segment_from_points(
         self,
@@ -133,7 +185,7 @@ impl<'r> CycleBuilder<'r> {
             edges.push(edge);
         }
 
-        self.shape.insert(Cycle { edges })
+        self.shape.insert(Cycle::from_edges(edges))
     }
 }
 
@@ -160,6 +212,9 @@ impl<'r> FaceBuilder<'r> {
     }
 
     /// Make the exterior or the face a polygon
+    ///
+    /// The polygon is made to run counter-clockwise in surface coordinates,
+    /// regardless of the order in which its points are given.
     pub fn with_exterior_polygon(
         self,
         points: impl IntoIterator<Item = impl Into<Point<3>>>,
@@ -173,6 +228,9 @@ impl<'r> FaceBuilder<'r> {
     }
 
     /// Add an interior polygon to the face
+    ///
+    /// The polygon is made to run clockwise in surface coordinates, regardless
+    /// of the order in which its points are given.
     pub fn with_interior_polygon(
         self,
         points: impl IntoIterator<Item = impl Into<Point<3>>>,
@@ -187,10 +245,9 @@ impl<'r> FaceBuilder<'r> {
 
     /// Build the face
     pub fn build(self) -> ValidationResult<Face> {
-        let surface = self.shape.insert(self.surface)?;
-
         let exteriors = match self.exterior {
             Some(points) => {
+                let points = orient_polygon(&self.surface, points, true);
                 let cycle = Cycle::builder(self.shape).build_polygon(points)?;
                 vec![cycle]
             }
@@ -199,11 +256,14 @@ impl<'r> FaceBuilder<'r> {
 
         let mut interiors = Vec::new();
         for points in self.interiors {
+            let points = orient_polygon(&self.surface, points, false);
             let cycle = Cycle::builder(self.shape).build_polygon(points)?;
             interiors.push(cycle);
         }
 
-        self.shape.insert(Face::Face {
+        let surface = self.shape.insert(self.surface)?;
+
+        self.shape.insert(Face {
             surface,
             exteriors,
             interiors,
@@ -211,3 +271,27 @@ impl<'r> FaceBuilder<'r> {
         })
     }
 }
+
+/// Orient a polygon, so it runs in the given direction in surface coordinates
+fn orient_polygon(
+    surface: &Surface,
+    mut points: Vec<Point<3>>,
+    counter_clockwise: bool,
+) -> Vec<Point<3>> {
+    let surface_points: Vec<_> = points
+        .iter()
+        .map(|&point| surface.point_model_to_surface(point).native())
+        .collect();
+
+    let mut area = Scalar::ZERO;
+    for (i, a) in surface_points.iter().enumerate() {
+        let b = surface_points[(i + 1) % surface_points.len()];
+        area += a.u * b.v - b.u * a.v;
+    }
+
+    if (area > Scalar::ZERO) != counter_clockwise {
+        points.reverse();
+    }
+
+    points
+}
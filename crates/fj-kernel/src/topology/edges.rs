@@ -1,4 +1,7 @@
-use std::hash::{Hash, Hasher};
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
 
 use crate::{
     geometry::Curve,
@@ -11,7 +14,12 @@ use super::{builder::CycleBuilder, vertices::Vertex, EdgeBuilder};
 ///
 /// The end of each edge in the cycle must connect to the beginning of the next
 /// edge. The end of the last edge must connect to the beginning of the first
-/// one.
+/// one. Where an edge begins and ends depends on the direction in which the
+/// cycle runs through it, which the cycle stores for each of its edges.
+///
+/// A cycle that bounds a face runs around it, such that the face is on its
+/// left, when looking at the surface from the side its normal points to. This
+/// means exterior cycles run counter-clockwise, interior cycles clockwise.
 ///
 /// # Equality
 ///
@@ -24,8 +32,15 @@ use super::{builder::CycleBuilder, vertices::Vertex, EdgeBuilder};
 /// the edges it refers to, must be part of the same shape.
 #[derive(Clone, Debug, Eq, Ord, PartialOrd)]
 pub struct Cycle {
-    /// The edges that make up the cycle
+    /// The edges that make up the cycle, in the order the cycle runs through
     pub edges: Vec<Handle<Edge>>,
+
+    /// Whether the cycle runs through each edge against its direction
+    ///
+    /// Contains one entry for each edge in `edges`. The direction of an edge is
+    /// the direction of its curve, from the first to the second of its
+    /// vertices.
+    pub reversed: Vec<bool>,
 }
 
 impl Cycle {
@@ -34,6 +49,49 @@ impl Cycle {
         CycleBuilder::new(shape)
     }
 
+    /// Create a cycle that runs through the edges in the order they are given
+    ///
+    /// The direction in which the cycle runs through each edge is determined
+    /// by the vertices it shares with the previous and the next edge. The
+    /// first edge is used in its own direction, unless only its first vertex
+    /// connects to the second edge. A single edge is used in its own direction.
+    pub fn from_edges(edges: Vec<Handle<Edge>>) -> Self {
+        let mut reversed = Vec::new();
+        let mut end: Option<Handle<Vertex>> = None;
+
+        for (i, edge) in edges.iter().enumerate() {
+            let vertices = edge.get().vertices;
+
+            let is_reversed = match (&vertices, &end) {
+                (Some([start, _]), Some(end)) => start != end,
+                (Some([_, end]), None) => match edges.get(i + 1) {
+                    Some(next) => match next.get().vertices {
+                        Some(next) => !next.contains(end),
+                        None => false,
+                    },
+                    None => false,
+                },
+                (None, _) => false,
+            };
+
+            end = vertices.map(|[a, b]| if is_reversed { a } else { b });
+            reversed.push(is_reversed);
+        }
+
+        Self { edges, reversed }
+    }
+
+    /// Create a cycle that runs through the same edges in the other direction
+    pub fn reverse(mut self) -> Self {
+        self.edges.reverse();
+        self.reversed.reverse();
+        for reversed in &mut self.reversed {
+            *reversed = !*reversed;
+        }
+
+        self
+    }
+
     /// Access the edges that this cycle refers to
     ///
     /// This is a convenience method that saves the caller from dealing with the
@@ -45,15 +103,26 @@ impl Cycle {
 
 impl PartialEq for Cycle {
     fn eq(&self, other: &Self) -> bool {
-        self.edges().eq(other.edges())
+        self.edges().eq(other.edges()) && self.reversed == other.reversed
     }
 }
 
 impl Hash for Cycle {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        for edge in self.edges() {
-            edge.hash(state);
-        }
+        // The hash doesn't depend on the order or direction of the edges.
+        // Cycles that have the same edges in a different order are considered
+        // duplicates, and this makes it possible to find them using the hash.
+        let mut hashes: Vec<_> = self
+            .edges()
+            .map(|edge| {
+                let mut hasher = DefaultHasher::new();
+                edge.hash(&mut hasher);
+                hasher.finish()
+            })
+            .collect();
+        hashes.sort_unstable();
+
+        hashes.hash(state);
     }
 }
 
@@ -0,0 +1,343 @@
+use std::collections::HashMap;
+
+use crate::shape::{Handle, Shape};
+
+use super::{Cycle, Edge, Face, Vertex};
+
+/// The use of an edge by a face, in a specific direction
+///
+/// Edges don't have an orientation on their own, and a single edge is usually
+/// shared by two faces. Each of those faces uses the edge in a specific
+/// direction, and this direction is what a half-edge adds.
+///
+/// Half-edges are not stored in a [`Shape`]. They are derived from it, by
+/// [`HalfEdges::new`].
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct HalfEdge {
+    /// The edge that is used
+    pub edge: Handle<Edge>,
+
+    /// The cycle that uses the edge
+    pub cycle: Handle<Cycle>,
+
+    /// The face that the cycle bounds
+    pub face: Handle<Face>,
+
+    /// Whether the face uses the edge against the direction of its curve
+    ///
+    /// This is the direction that the cycle stores for the edge.
+    pub reversed: bool,
+}
+
+impl HalfEdge {
+    /// Access the vertex that the half-edge starts at
+    ///
+    /// Returns `None`, if the edge has no vertices.
+    pub fn start(&self) -> Option<Handle<Vertex>> {
+        self.vertices().map(|[start, _]| start)
+    }
+
+    /// Access the vertex that the half-edge ends at
+    ///
+    /// Returns `None`, if the edge has no vertices.
+    pub fn end(&self) -> Option<Handle<Vertex>> {
+        self.vertices().map(|[_, end]| end)
+    }
+
+    /// Access the vertices of the half-edge, in the order it runs through them
+    ///
+    /// Returns `None`, if the edge has no vertices.
+    pub fn vertices(&self) -> Option<[Handle<Vertex>; 2]> {
+        let mut vertices = self.edge.get().vertices?;
+
+        if self.reversed {
+            vertices.reverse();
+        }
+
+        Some(vertices)
+    }
+}
+
+/// Identifies a half-edge within [`HalfEdges`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct HalfEdgeId(usize);
+
+/// The half-edges of a shape, and how they are connected
+///
+/// Provides the adjacency information that the topological objects don't
+/// store themselves: The faces around an edge, the edges around a vertex, and
+/// for every half-edge, the half-edge that follows it in its cycle (`next`)
+/// and the half-edge of the neighboring face that uses the same edge (`twin`).
+///
+/// This is a snapshot of the shape at the time of construction. It doesn't
+/// reflect any later changes to the shape.
+#[derive(Clone, Debug)]
+pub struct HalfEdges {
+    half_edges: Vec<HalfEdge>,
+    next: Vec<HalfEdgeId>,
+    twin: Vec<Option<HalfEdgeId>>,
+
+    edges: Vec<Handle<Edge>>,
+    by_edge: HashMap<Handle<Edge>, Vec<HalfEdgeId>>,
+    by_vertex: HashMap<Handle<Vertex>, Vec<Handle<Edge>>>,
+}
+
+impl HalfEdges {
+    /// Compute the half-edges of a shape
+    ///
+    /// Every cycle of every face uses its edges in the directions that the
+    /// cycle stores for them. The half-edge that follows another is the one
+    /// that uses the next edge of the same cycle.
+    pub fn new(shape: &Shape) -> Self {
+        let mut half_edges = Vec::new();
+        let mut next = Vec::new();
+
+        for face in shape.faces() {
+            let Face {
+                exteriors,
+                interiors,
+                ..
+            } = face.get();
+
+            for cycle in exteriors.into_iter().chain(interiors) {
+                let Cycle { edges, reversed } = cycle.get();
+                let start = half_edges.len();
+                let len = edges.len();
+
+                for (edge, reversed) in edges.into_iter().zip(reversed) {
+                    half_edges.push(HalfEdge {
+                        edge,
+                        cycle: cycle.clone(),
+                        face: face.clone(),
+                        reversed,
+                    });
+                }
+
+                next.extend(
+                    (0..len).map(|i| HalfEdgeId(start + (i + 1) % len)),
+                );
+            }
+        }
+
+        let mut edges = Vec::new();
+        let mut by_edge: HashMap<_, Vec<_>> = HashMap::new();
+        for (i, half_edge) in half_edges.iter().enumerate() {
+            let uses =
+                by_edge.entry(half_edge.edge.clone()).or_insert_with(|| {
+                    edges.push(half_edge.edge.clone());
+                    Vec::new()
+                });
+            uses.push(HalfEdgeId(i));
+        }
+
+        let mut twin = vec![None; half_edges.len()];
+        for uses in by_edge.values() {
+            if let [a, b] = uses.as_slice() {
+                twin[a.0] = Some(*b);
+                twin[b.0] = Some(*a);
+            }
+        }
+
+        let mut by_vertex: HashMap<_, Vec<_>> = HashMap::new();
+        for edge in &edges {
+            for vertex in edge.get().vertices.into_iter().flatten() {
+                by_vertex.entry(vertex).or_default().push(edge.clone());
+            }
+        }
+
+        Self {
+            half_edges,
+            next,
+            twin,
+            edges,
+            by_edge,
+            by_vertex,
+        }
+    }
+
+    /// Access the half-edge with the given id
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `id` was returned by another instance of `HalfEdges`, that
+    /// has more half-edges than this one.
+    pub fn get(&self, id: HalfEdgeId) -> &HalfEdge {
+        &self.half_edges[id.0]
+    }
+
+    /// Iterate over the ids of all half-edges
+    pub fn ids(&self) -> impl Iterator<Item = HalfEdgeId> {
+        (0..self.half_edges.len()).map(HalfEdgeId)
+    }
+
+    /// Access the half-edge that follows the given one in its cycle
+    ///
+    /// The single half-edge of a cycle made from an edge without vertices is
+    /// its own `next`.
+    pub fn next(&self, id: HalfEdgeId) -> HalfEdgeId {
+        self.next[id.0]
+    }
+
+    /// Access the half-edge of the neighboring face that uses the same edge
+    ///
+    /// Returns `None`, if the edge is not used by exactly two half-edges. That
+    /// is the case for edges on the boundary of an open shell, and for
+    /// non-manifold edges.
+    ///
+    /// In a consistently oriented shell, a half-edge and its twin run in
+    /// opposite directions. This is not checked here; use
+    /// [`Shape::validate_solid`] for that.
+    pub fn twin(&self, id: HalfEdgeId) -> Option<HalfEdgeId> {
+        self.twin[id.0]
+    }
+
+    /// Iterate over all edges that are used by any face
+    ///
+    /// Edges are returned in the order in which they are first used.
+    pub fn edges(&self) -> impl Iterator<Item = &Handle<Edge>> {
+        self.edges.iter()
+    }
+
+    /// Access the half-edges that use an edge
+    ///
+    /// Returns an empty slice, if the edge isn't used by any face.
+    pub fn of_edge(&self, edge: &Handle<Edge>) -> &[HalfEdgeId] {
+        self.by_edge
+            .get(edge)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Iterate over the faces around an edge
+    ///
+    /// A face that uses an edge more than once is returned for every use.
+    pub fn faces_of_edge<'r>(
+        &'r self,
+        edge: &Handle<Edge>,
+    ) -> impl Iterator<Item = &'r Handle<Face>> {
+        self.of_edge(edge).iter().map(|id| &self.get(*id).face)
+    }
+
+    /// Access the edges around a vertex
+    ///
+    /// Only edges that are used by any face are considered. The caller must
+    /// not make any assumptions about the order of the edges.
+    pub fn edges_of_vertex(&self, vertex: &Handle<Vertex>) -> &[Handle<Edge>] {
+        self.by_vertex
+            .get(vertex)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Scalar, Vector};
+
+    use crate::{
+        algorithms::sweep_shape,
+        geometry::Surface,
+        shape::Shape,
+        topology::{Edge, Face},
+    };
+
+    use super::HalfEdges;
+
+    #[test]
+    fn cube() -> anyhow::Result<()> {
+        let mut sketch = Shape::new();
+        Face::builder(Surface::xy_plane(), &mut sketch)
+            .with_exterior_polygon([
+                [0., 0., 0.],
+                [1., 0., 0.],
+                [1., 1., 0.],
+                [0., 1., 0.],
+            ])
+            .build()?;
+        let shape =
+            sweep_shape(sketch, Vector::from([0., 0., 1.]), [255, 0, 0, 255]);
+
+        let half_edges = HalfEdges::new(&shape);
+        assert_eq!(half_edges.ids().count(), 24);
+        assert_eq!(half_edges.edges().count(), 12);
+
+        for edge in half_edges.edges() {
+            let faces: Vec<_> = half_edges.faces_of_edge(edge).collect();
+            assert_eq!(faces.len(), 2);
+            assert_ne!(faces[0], faces[1]);
+        }
+
+        for id in half_edges.ids() {
+            let half_edge = half_edges.get(id);
+
+            // The twin uses the same edge in the opposite direction.
+            let twin = half_edges.get(half_edges.twin(id).unwrap());
+            assert_eq!(twin.edge, half_edge.edge);
+            assert_eq!(twin.start(), half_edge.end());
+            assert_ne!(twin.face, half_edge.face);
+
+            // Following `next` goes around the face, and back to the start.
+            let mut current = id;
+            for _ in 0..4 {
+                let next = half_edges.next(current);
+                assert_eq!(
+                    half_edges.get(next).start(),
+                    half_edges.get(current).end()
+                );
+                assert_eq!(half_edges.get(next).face, half_edge.face);
+                current = next;
+            }
+            assert_eq!(current, id);
+        }
+
+        for vertex in shape.vertices() {
+            assert_eq!(half_edges.edges_of_vertex(&vertex).len(), 3);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn single_face() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+        let edge = Edge::builder(&mut shape).build_circle(Scalar::ONE)?;
+        Face::builder(Surface::xy_plane(), &mut shape)
+            .with_exterior_polygon([[2., 0., 0.], [3., 0., 0.], [3., 1., 0.]])
+            .build()?;
+
+        let half_edges = HalfEdges::new(&shape);
+
+        // The circle is not part of any face.
+        assert_eq!(half_edges.of_edge(&edge), &[]);
+        assert_eq!(half_edges.edges().count(), 3);
+
+        for id in half_edges.ids() {
+            // The triangle is the only face, so there are no twins.
+            assert!(half_edges.twin(id).is_none());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn clockwise_exterior() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+        Face::builder(Surface::xy_plane(), &mut shape)
+            .with_exterior_polygon([[0., 0., 0.], [0., 1., 0.], [1., 0., 0.]])
+            .build()?;
+
+        let half_edges = HalfEdges::new(&shape);
+
+        // The exterior runs counter-clockwise, regardless of the order in
+        // which its vertices were specified.
+        let mut area = 0.;
+        for id in half_edges.ids() {
+            let [a, b] = half_edges.get(id).vertices().unwrap();
+            let (a, b) = (a.get().point(), b.get().point());
+            area += (a.x * b.y - b.x * a.y).into_f64();
+        }
+        assert!(area > 0.);
+
+        Ok(())
+    }
+}
@@ -19,11 +19,15 @@
 mod builder;
 mod edges;
 mod faces;
+mod half_edges;
+mod solids;
 mod vertices;
 
 pub use self::{
     builder::{EdgeBuilder, VertexBuilder},
     edges::{Cycle, Edge},
     faces::Face,
+    half_edges::{HalfEdge, HalfEdgeId, HalfEdges},
+    solids::{Shell, Solid},
     vertices::Vertex,
 };
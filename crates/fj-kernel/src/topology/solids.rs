@@ -0,0 +1,126 @@
+use std::hash::{Hash, Hasher};
+
+use crate::shape::Handle;
+
+use super::faces::Face;
+
+/// A shell of a shape
+///
+/// A shell is a set of faces that are connected via shared edges. If it is
+/// closed, it bounds a volume.
+///
+/// # Equality
+///
+/// Please refer to [`crate::kernel::topology`] for documentation on the
+/// equality of topological objects.
+///
+/// # Validation
+///
+/// A shell that is part of a [`Shape`](crate::shape::Shape) must be
+/// structurally sound. That means the faces it refers to, must be part of the
+/// same shape.
+#[derive(Clone, Debug, Eq, Ord, PartialOrd)]
+pub struct Shell {
+    /// The faces that make up the shell
+    pub faces: Vec<Handle<Face>>,
+}
+
+impl Shell {
+    /// Access the faces that this shell refers to
+    ///
+    /// This is a convenience method that saves the caller from dealing with the
+    /// [`Handle`]s.
+    pub fn faces(&self) -> impl Iterator<Item = Face> + '_ {
+        self.faces.iter().map(|handle| handle.get())
+    }
+}
+
+impl PartialEq for Shell {
+    fn eq(&self, other: &Self) -> bool {
+        self.faces().eq(other.faces())
+    }
+}
+
+impl Hash for Shell {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for face in self.faces() {
+            face.hash(state);
+        }
+    }
+}
+
+/// A solid of a shape
+///
+/// A solid is the volume that is bounded by a shell on the outside, minus any
+/// cavities, which are bounded by shells on the inside.
+///
+/// A shape can contain multiple solids. They are separate bodies, which is
+/// information that would otherwise get lost, as all faces of a shape are
+/// stored together.
+///
+/// # Equality
+///
+/// Please refer to [`crate::kernel::topology`] for documentation on the
+/// equality of topological objects.
+///
+/// # Validation
+///
+/// A solid that is part of a [`Shape`](crate::shape::Shape) must be
+/// structurally sound. That means the shells it refers to, must be part of the
+/// same shape.
+#[derive(Clone, Debug, Eq, Ord, PartialOrd)]
+pub struct Solid {
+    /// The shell that bounds the solid on the outside
+    pub exterior: Handle<Shell>,
+
+    /// The shells that bound the solid on the inside
+    ///
+    /// Each of these shells defines a cavity in the solid.
+    pub interiors: Vec<Handle<Shell>>,
+}
+
+impl Solid {
+    /// Access the exterior shell that the solid refers to
+    ///
+    /// This is a convenience method that saves the caller from dealing with the
+    /// [`Handle`].
+    pub fn exterior(&self) -> Shell {
+        self.exterior.get()
+    }
+
+    /// Access the interior shells that the solid refers to
+    ///
+    /// This is a convenience method that saves the caller from dealing with the
+    /// [`Handle`]s.
+    pub fn interiors(&self) -> impl Iterator<Item = Shell> + '_ {
+        self.interiors.iter().map(|handle| handle.get())
+    }
+
+    /// Access all shells that the solid refers to
+    ///
+    /// This is equivalent to chaining [`Solid::exterior`] and the iterator
+    /// returned by [`Solid::interiors`].
+    pub fn all_shells(&self) -> impl Iterator<Item = Shell> + '_ {
+        [self.exterior()].into_iter().chain(self.interiors())
+    }
+
+    /// Access all faces of all shells of the solid
+    pub fn faces(&self) -> impl Iterator<Item = Handle<Face>> + '_ {
+        self.all_shells().flat_map(|shell| shell.faces)
+    }
+}
+
+impl PartialEq for Solid {
+    fn eq(&self, other: &Self) -> bool {
+        self.exterior() == other.exterior()
+            && self.interiors().eq(other.interiors())
+    }
+}
+
+impl Hash for Solid {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for shell in self.all_shells() {
+            shell.hash(state);
+        }
+    }
+}
@@ -1,6 +1,8 @@
-use parry3d_f64::bounding_volume::BoundingVolume as _;
+use parry3d_f64::{
+    bounding_volume::BoundingVolume as _, query::PointQuery as _,
+};
 
-use super::{Point, Vector};
+use super::{Point, Scalar, Vector};
 
 /// An axis-aligned bounding box (AABB)
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
@@ -85,6 +87,27 @@ impl Aabb<3> {
     pub fn merged(&self, other: &Self) -> Self {
         self.to_parry().merged(&other.to_parry()).into()
     }
+
+    /// Compute an AABB that is larger by `amount` in every direction
+    pub fn loosened(&self, amount: impl Into<Scalar>) -> Self {
+        self.to_parry().loosened(amount.into().into_f64()).into()
+    }
+
+    /// Determine whether this AABB intersects another
+    ///
+    /// AABBs that touch each other are considered to intersect.
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.to_parry().intersects(&other.to_parry())
+    }
+
+    /// Compute the distance from the AABB to a point
+    ///
+    /// Returns zero, if the point is inside of the AABB.
+    pub fn distance_to_point(&self, point: &Point<3>) -> Scalar {
+        self.to_parry()
+            .distance_to_local_point(&point.to_na(), true)
+            .into()
+    }
 }
 
 impl From<parry2d_f64::bounding_volume::AABB> for Aabb<2> {
@@ -98,3 +121,42 @@ impl From<parry3d_f64::bounding_volume::AABB> for Aabb<3> {
         Self::from_parry(aabb)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{Point, Scalar};
+
+    use super::Aabb;
+
+    #[test]
+    fn intersects() {
+        let a = aabb([0., 0., 0.], [1., 1., 1.]);
+        let b = aabb([1., 1., 1.], [2., 2., 2.]);
+        let c = aabb([1.5, 0., 0.], [2., 1., 1.]);
+
+        assert!(a.intersects(&b));
+        assert!(!a.intersects(&c));
+        assert!(a.loosened(0.5).intersects(&c));
+    }
+
+    #[test]
+    fn distance_to_point() {
+        let a = aabb([0., 0., 0.], [1., 1., 1.]);
+
+        assert_eq!(
+            a.distance_to_point(&Point::from([0.5, 0.5, 0.5])),
+            Scalar::ZERO
+        );
+        assert_eq!(
+            a.distance_to_point(&Point::from([3., 0.5, 0.5])),
+            Scalar::from(2.)
+        );
+    }
+
+    fn aabb(min: [f64; 3], max: [f64; 3]) -> Aabb<3> {
+        Aabb {
+            min: Point::from(min),
+            max: Point::from(max),
+        }
+    }
+}
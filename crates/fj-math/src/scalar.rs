@@ -71,11 +71,30 @@ impl Scalar {
         self.0.max(other.0).into()
     }
 
+    /// Compute the minimum of this and another scalar
+    pub fn min(self, other: Self) -> Self {
+        self.0.min(other.0).into()
+    }
+
+    /// Compute the square root of the scalar
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the scalar is negative, as the result would be NaN.
+    pub fn sqrt(self) -> Self {
+        self.0.sqrt().into()
+    }
+
     /// Compute the smallest integer larger than or equal to this scalar
     pub fn ceil(self) -> Self {
         self.0.ceil().into()
     }
 
+    /// Compute the largest integer smaller than or equal to this scalar
+    pub fn floor(self) -> Self {
+        self.0.floor().into()
+    }
+
     /// Round the scalar
     pub fn round(self) -> Self {
         self.0.round().into()
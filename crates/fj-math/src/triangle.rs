@@ -1,7 +1,10 @@
 use parry2d_f64::utils::point_in_triangle::{corner_direction, Orientation};
-use parry3d_f64::query::{Ray, RayCast as _};
+use parry3d_f64::{
+    math::Isometry,
+    query::{PointQuery as _, Ray, RayCast as _},
+};
 
-use crate::Vector;
+use crate::{Segment, Vector};
 
 use super::{Point, Scalar};
 
@@ -87,6 +90,46 @@ impl Triangle<3> {
             .cast_local_ray(&ray, max_toi, solid)
             .map(|f| f.into())
     }
+
+    /// Compute the point on the triangle that is closest to the given point
+    pub fn closest_point(&self, point: Point<3>) -> Point<3> {
+        let projection =
+            self.to_parry().project_local_point(&point.to_na(), true);
+        Point::from_na(projection.point)
+    }
+
+    /// Determine whether the triangle intersects another triangle
+    ///
+    /// Triangles that merely touch count as intersecting.
+    pub fn intersects(&self, other: &Self) -> bool {
+        let identity = Isometry::identity();
+
+        // Can't fail. Parry supports intersection tests between triangles.
+        parry3d_f64::query::intersection_test(
+            &identity,
+            &self.to_parry(),
+            &identity,
+            &other.to_parry(),
+        )
+        .unwrap()
+    }
+
+    /// Determine whether the triangle intersects a line segment
+    ///
+    /// Segments that merely touch the triangle count as intersecting.
+    pub fn intersects_segment(&self, segment: &Segment<3>) -> bool {
+        let identity = Isometry::identity();
+
+        // Can't fail. Parry supports intersection tests between triangles and
+        // segments.
+        parry3d_f64::query::intersection_test(
+            &identity,
+            &self.to_parry(),
+            &identity,
+            &segment.to_parry(),
+        )
+        .unwrap()
+    }
 }
 
 impl<P, const D: usize> From<[P; 3]> for Triangle<D>
@@ -155,4 +198,35 @@ mod tests {
         let c = Point::from([2.0, 2.0, 2.0]);
         let _triangle = Triangle::from([a, b, c]);
     }
+
+    #[test]
+    fn closest_point() {
+        let triangle =
+            Triangle::from([[0.0, 0.0, 0.0], [2.0, 0.0, 0.0], [0.0, 2.0, 0.0]]);
+
+        assert_eq!(
+            triangle.closest_point(Point::from([0.5, 0.5, 1.0])),
+            Point::from([0.5, 0.5, 0.0])
+        );
+        assert_eq!(
+            triangle.closest_point(Point::from([3.0, -1.0, 0.0])),
+            Point::from([2.0, 0.0, 0.0])
+        );
+    }
+
+    #[test]
+    fn intersects() {
+        let a =
+            Triangle::from([[0.0, 0.0, 0.0], [2.0, 0.0, 0.0], [0.0, 2.0, 0.0]]);
+        let b = Triangle::from([
+            [0.5, 0.5, -1.0],
+            [0.5, 0.5, 1.0],
+            [3.0, 3.0, 0.0],
+        ]);
+        let c =
+            Triangle::from([[0.0, 0.0, 1.0], [2.0, 0.0, 1.0], [0.0, 2.0, 1.0]]);
+
+        assert!(a.intersects(&b));
+        assert!(!a.intersects(&c));
+    }
 }
@@ -2,7 +2,7 @@ use fj_interop::debug::DebugInfo;
 use fj_kernel::{
     algorithms::Tolerance,
     geometry::Surface,
-    shape::Shape,
+    shape::{Shape, ValidationError},
     topology::{Cycle, Edge, Face},
 };
 use fj_math::{Aabb, Point, Scalar};
@@ -10,29 +10,31 @@ use fj_math::{Aabb, Point, Scalar};
 use super::ToShape;
 
 impl ToShape for fj::Circle {
-    fn to_shape(&self, _: Tolerance, _: &mut DebugInfo) -> Shape {
-        let mut shape = Shape::new();
+    fn to_shape(
+        &self,
+        _: Tolerance,
+        min_distance: Scalar,
+        _: &mut DebugInfo,
+    ) -> Result<Shape, ValidationError> {
+        let mut shape = Shape::new().with_min_distance(min_distance);
 
         // Circles have just a single round edge with no vertices. So none need
         // to be added here.
 
         let edge = Edge::builder(&mut shape)
-            .build_circle(Scalar::from_f64(self.radius()))
-            .unwrap();
-        shape.insert(Cycle { edges: vec![edge] }).unwrap();
+            .build_circle(Scalar::from_f64(self.radius()))?;
+        shape.insert(Cycle::from_edges(vec![edge]))?;
 
         let cycles = shape.cycles().collect();
-        let surface = shape.insert(Surface::xy_plane()).unwrap();
-        shape
-            .insert(Face::Face {
-                exteriors: cycles,
-                interiors: Vec::new(),
-                surface,
-                color: self.color(),
-            })
-            .unwrap();
+        let surface = shape.insert(Surface::xy_plane())?;
+        shape.insert(Face {
+            exteriors: cycles,
+            interiors: Vec::new(),
+            surface,
+            color: self.color(),
+        })?;
 
-        shape
+        Ok(shape)
     }
 
     fn bounding_volume(&self) -> Aabb<3> {
@@ -3,10 +3,10 @@ use std::collections::HashMap;
 use fj_interop::debug::DebugInfo;
 use fj_kernel::{
     algorithms::Tolerance,
-    shape::{Handle, Shape},
+    shape::{Handle, Shape, ValidationError, ValidationResult},
     topology::{Cycle, Edge, Face, Vertex},
 };
-use fj_math::Aabb;
+use fj_math::{Aabb, Scalar};
 
 use super::ToShape;
 
@@ -14,18 +14,20 @@ impl ToShape for fj::Difference2d {
     fn to_shape(
         &self,
         tolerance: Tolerance,
+        min_distance: Scalar,
         debug_info: &mut DebugInfo,
-    ) -> Shape {
+    ) -> Result<Shape, ValidationError> {
         // This method assumes that `b` is fully contained within `a`:
         // https://github.com/hannobraun/Fornjot/issues/92
 
-        let mut shape = Shape::new();
+        let mut shape = Shape::new().with_min_distance(min_distance);
 
         // Can be cleaned up, once `each_ref` is stable:
         // https://doc.rust-lang.org/std/primitive.array.html#method.each_ref
         let [a, b] = self.shapes();
-        let [mut a, mut b] =
-            [a, b].map(|shape| shape.to_shape(tolerance, debug_info));
+        let [a, b] = [a, b]
+            .map(|shape| shape.to_shape(tolerance, min_distance, debug_info));
+        let [mut a, mut b] = [a?, b?];
 
         // Check preconditions.
         //
@@ -52,8 +54,8 @@ impl ToShape for fj::Difference2d {
 
         let mut vertices = HashMap::new();
 
-        let cycle_a = add_cycle(cycle_a, &mut vertices, &mut shape, false);
-        let cycle_b = add_cycle(cycle_b, &mut vertices, &mut shape, true);
+        let cycle_a = add_cycle(cycle_a, &mut vertices, &mut shape, false)?;
+        let cycle_b = add_cycle(cycle_b, &mut vertices, &mut shape, true)?;
 
         let mut exteriors = Vec::new();
         let mut interiors = Vec::new();
@@ -69,18 +71,16 @@ impl ToShape for fj::Difference2d {
             face_a.surface() == face_b.surface(),
             "Trying to subtract sketches with different surfaces."
         );
-        let surface = shape.insert(face_a.surface()).unwrap();
-
-        shape
-            .insert(Face::Face {
-                surface,
-                exteriors,
-                interiors,
-                color: self.color(),
-            })
-            .unwrap();
-
-        shape
+        let surface = shape.insert(face_a.surface())?;
+
+        shape.insert(Face {
+            surface,
+            exteriors,
+            interiors,
+            color: self.color(),
+        })?;
+
+        Ok(shape)
     }
 
     fn bounding_volume(&self) -> Aabb<3> {
@@ -91,43 +91,58 @@ impl ToShape for fj::Difference2d {
     }
 }
 
+/// Add a copy of a cycle to `shape`, optionally reversing it
+///
+/// The face is on the left of its cycles, so a hole needs to run the other way
+/// than the exterior cycle it was copied from. The edges themselves are copied
+/// as they are; only the direction in which the cycle uses them changes.
 fn add_cycle(
     cycle: Handle<Cycle>,
     vertices: &mut HashMap<Vertex, Handle<Vertex>>,
     shape: &mut Shape,
     reverse: bool,
-) -> Handle<Cycle> {
+) -> ValidationResult<Cycle> {
+    let cycle = cycle.get();
+
     let mut edges = Vec::new();
-    for edge in cycle.get().edges() {
-        let curve = edge.curve();
-        let curve = if reverse { curve.reverse() } else { curve };
-        let curve = shape.insert(curve).unwrap();
-
-        let vertices = edge.vertices().clone().map(|vs| {
-            let mut vs = vs.map(|vertex| {
-                vertices
-                    .entry(vertex.clone())
-                    .or_insert_with(|| {
-                        let point = shape.insert(vertex.point()).unwrap();
-                        shape.insert(Vertex { point }).unwrap()
-                    })
-                    .clone()
-            });
-
-            if reverse {
-                vs.reverse();
+    for edge in cycle.edges() {
+        let curve = shape.insert(edge.curve())?;
+
+        let vertices = match edge.vertices() {
+            Some([a, b]) => {
+                let a = add_vertex(a, vertices, shape)?;
+                let b = add_vertex(b, vertices, shape)?;
+                Some([a, b])
             }
+            None => None,
+        };
 
-            vs
-        });
-
-        let edge = shape.insert(Edge { curve, vertices }).unwrap();
+        let edge = shape.insert(Edge { curve, vertices })?;
         edges.push(edge);
     }
 
-    if reverse {
-        edges.reverse();
+    let cycle = Cycle {
+        edges,
+        reversed: cycle.reversed,
+    };
+    let cycle = if reverse { cycle.reverse() } else { cycle };
+
+    shape.insert(cycle)
+}
+
+/// Add a copy of a vertex to `shape`, unless it has already been added
+fn add_vertex(
+    vertex: Vertex,
+    vertices: &mut HashMap<Vertex, Handle<Vertex>>,
+    shape: &mut Shape,
+) -> ValidationResult<Vertex> {
+    if let Some(vertex) = vertices.get(&vertex) {
+        return Ok(vertex.clone());
     }
 
-    shape.insert(Cycle { edges }).unwrap()
+    let point = shape.insert(vertex.point())?;
+    let handle = shape.insert(Vertex { point })?;
+    vertices.insert(vertex, handle.clone());
+
+    Ok(handle)
 }
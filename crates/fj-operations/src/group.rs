@@ -3,10 +3,10 @@ use std::collections::HashMap;
 use fj_interop::debug::DebugInfo;
 use fj_kernel::{
     algorithms::Tolerance,
-    shape::Shape,
-    topology::{Cycle, Edge, Face, Vertex},
+    shape::{Shape, ValidationError},
+    topology::{Cycle, Edge, Face, Shell, Solid, Vertex},
 };
-use fj_math::Aabb;
+use fj_math::{Aabb, Scalar};
 
 use super::ToShape;
 
@@ -14,17 +14,18 @@ impl ToShape for fj::Group {
     fn to_shape(
         &self,
         tolerance: Tolerance,
+        min_distance: Scalar,
         debug_info: &mut DebugInfo,
-    ) -> Shape {
-        let mut shape = Shape::new();
+    ) -> Result<Shape, ValidationError> {
+        let mut shape = Shape::new().with_min_distance(min_distance);
 
-        let a = self.a.to_shape(tolerance, debug_info);
-        let b = self.b.to_shape(tolerance, debug_info);
+        let a = self.a.to_shape(tolerance, min_distance, debug_info)?;
+        let b = self.b.to_shape(tolerance, min_distance, debug_info)?;
 
-        copy_shape(a, &mut shape);
-        copy_shape(b, &mut shape);
+        copy_shape(a, &mut shape)?;
+        copy_shape(b, &mut shape)?;
 
-        shape
+        Ok(shape)
     }
 
     fn bounding_volume(&self) -> Aabb<3> {
@@ -35,7 +36,7 @@ impl ToShape for fj::Group {
     }
 }
 
-fn copy_shape(orig: Shape, target: &mut Shape) {
+fn copy_shape(orig: Shape, target: &mut Shape) -> Result<(), ValidationError> {
     let mut points = HashMap::new();
     let mut curves = HashMap::new();
     let mut surfaces = HashMap::new();
@@ -43,79 +44,99 @@ fn copy_shape(orig: Shape, target: &mut Shape) {
     let mut vertices = HashMap::new();
     let mut edges = HashMap::new();
     let mut cycles = HashMap::new();
+    let mut faces = HashMap::new();
+    let mut shells = HashMap::new();
 
     for point_orig in orig.points() {
-        let point = target.insert(point_orig.get()).unwrap();
+        let point = target.insert(point_orig.get())?;
         points.insert(point_orig, point);
     }
     for curve_orig in orig.curves() {
-        let curve = target.insert(curve_orig.get()).unwrap();
+        let curve = target.insert(curve_orig.get())?;
         curves.insert(curve_orig, curve);
     }
     for surface_orig in orig.surfaces() {
-        let surface = target.insert(surface_orig.get()).unwrap();
+        let surface = target.insert(surface_orig.get())?;
         surfaces.insert(surface_orig, surface);
     }
 
     for vertex_orig in orig.vertices() {
-        let vertex = target
-            .insert(Vertex {
-                point: points[&vertex_orig.get().point].clone(),
-            })
-            .unwrap();
+        let vertex = target.insert(Vertex {
+            point: points[&vertex_orig.get().point].clone(),
+        })?;
         vertices.insert(vertex_orig, vertex);
     }
     for edge_orig in orig.edges() {
-        let edge = target
-            .insert(Edge {
+        let edge =
+            target.insert(Edge {
                 curve: curves[&edge_orig.get().curve].clone(),
                 vertices: edge_orig.get().vertices.as_ref().map(|vs| {
                     vs.clone().map(|vertex| vertices[&vertex].clone())
                 }),
-            })
-            .unwrap();
+            })?;
         edges.insert(edge_orig, edge);
     }
     for cycle_orig in orig.cycles() {
-        let cycle = target
-            .insert(Cycle {
-                edges: cycle_orig
-                    .get()
-                    .edges
-                    .iter()
-                    .map(|edge| edges[edge].clone())
-                    .collect(),
-            })
-            .unwrap();
+        let Cycle {
+            edges: edges_orig,
+            reversed,
+        } = cycle_orig.get();
+
+        let cycle = target.insert(Cycle {
+            edges: edges_orig.iter().map(|edge| edges[edge].clone()).collect(),
+            reversed,
+        })?;
         cycles.insert(cycle_orig, cycle);
     }
 
     for face_orig in orig.faces() {
-        match face_orig.get() {
-            Face::Face {
-                surface,
-                exteriors,
-                interiors,
-                color,
-            } => {
-                target
-                    .insert(Face::Face {
-                        surface: surfaces[&surface].clone(),
-                        exteriors: exteriors
-                            .iter()
-                            .map(|cycle| cycles[cycle].clone())
-                            .collect(),
-                        interiors: interiors
-                            .iter()
-                            .map(|cycle| cycles[cycle].clone())
-                            .collect(),
-                        color,
-                    })
-                    .unwrap();
-            }
-            face @ Face::Triangles(_) => {
-                target.insert(face.clone()).unwrap();
-            }
-        }
+        let Face {
+            surface,
+            exteriors,
+            interiors,
+            color,
+        } = face_orig.get();
+
+        let face = target.insert(Face {
+            surface: surfaces[&surface].clone(),
+            exteriors: exteriors
+                .iter()
+                .map(|cycle| cycles[cycle].clone())
+                .collect(),
+            interiors: interiors
+                .iter()
+                .map(|cycle| cycles[cycle].clone())
+                .collect(),
+            color,
+        })?;
+        faces.insert(face_orig, face);
+    }
+
+    for shell_orig in orig.shells() {
+        let shell = target.insert(Shell {
+            faces: shell_orig
+                .get()
+                .faces
+                .iter()
+                .map(|face| faces[face].clone())
+                .collect(),
+        })?;
+        shells.insert(shell_orig, shell);
     }
+    for solid_orig in orig.solids() {
+        let Solid {
+            exterior,
+            interiors,
+        } = solid_orig.get();
+
+        target.insert(Solid {
+            exterior: shells[&exterior].clone(),
+            interiors: interiors
+                .iter()
+                .map(|shell| shells[shell].clone())
+                .collect(),
+        })?;
+    }
+
+    Ok(())
 }
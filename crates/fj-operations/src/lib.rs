@@ -26,17 +26,27 @@ mod sweep;
 mod transform;
 
 use fj_interop::debug::DebugInfo;
-use fj_kernel::{algorithms::Tolerance, shape::Shape};
-use fj_math::Aabb;
+use fj_kernel::{
+    algorithms::Tolerance,
+    shape::{Shape, ValidationError},
+};
+use fj_math::{Aabb, Scalar};
 
 /// Implemented for all operations from the [`fj`] crate
 pub trait ToShape {
     /// Compute the boundary representation of the shape
+    ///
+    /// `min_distance` is passed on to [`Shape::with_min_distance`], for every
+    /// shape that is created.
+    ///
+    /// Returns an error, if any of the objects that make up the shape fail
+    /// validation when they are inserted.
     fn to_shape(
         &self,
         tolerance: Tolerance,
+        min_distance: Scalar,
         debug_info: &mut DebugInfo,
-    ) -> Shape;
+    ) -> Result<Shape, ValidationError>;
 
     /// Access the axis-aligned bounding box of a shape
     ///
@@ -87,7 +97,8 @@ macro_rules! dispatch {
 dispatch! {
     to_shape(
         tolerance: Tolerance,
+        min_distance: Scalar,
         debug_info: &mut DebugInfo,
-    ) -> Shape;
+    ) -> Result<Shape, ValidationError>;
     bounding_volume() -> Aabb<3>;
 }
@@ -1,7 +1,10 @@
 //! API for processing shapes
 
 use fj_interop::{debug::DebugInfo, mesh::Mesh};
-use fj_kernel::algorithms::{triangulate, Tolerance};
+use fj_kernel::{
+    algorithms::{triangulate, MassProperties, Tolerance, TriangulationError},
+    shape::{SolidReport, ValidationError},
+};
 use fj_math::{Aabb, Point, Scalar};
 
 use crate::ToShape as _;
@@ -14,10 +17,76 @@ pub struct ShapeProcessor {
 
 impl ShapeProcessor {
     /// Process an [`fj::Shape`] into [`ProcessedShape`]
-    pub fn process(&self, shape: &fj::Shape) -> ProcessedShape {
+    ///
+    /// The `settings` are the ones the model provided along with the shape.
+    /// Returns an error, if the shape is invalid or can't be triangulated.
+    pub fn process(
+        &self,
+        shape: &fj::Shape,
+        settings: &fj::Settings,
+    ) -> Result<ProcessedShape, Error> {
         let aabb = shape.bounding_volume();
+        let min_distance = Scalar::from_f64(settings.min_distance());
+        let tolerance = self.tolerance(&aabb, min_distance);
 
-        let tolerance = match self.tolerance {
+        let mut debug_info = DebugInfo::new();
+        let mesh = triangulate(
+            shape.to_shape(tolerance, min_distance, &mut debug_info)?,
+            tolerance,
+            &mut debug_info,
+        )?;
+
+        Ok(ProcessedShape {
+            aabb,
+            mesh,
+            units: settings.units(),
+            debug_info,
+        })
+    }
+
+    /// Validate that an [`fj::Shape`] forms a closed, oriented solid
+    ///
+    /// See [`Shape::validate_solid`]. Returns an error, if the shape is
+    /// invalid in a way that prevents it from being built in the first place.
+    ///
+    /// [`Shape::validate_solid`]: fj_kernel::shape::Shape::validate_solid
+    pub fn validate_solid(
+        &self,
+        shape: &fj::Shape,
+        settings: &fj::Settings,
+    ) -> Result<SolidReport, ValidationError> {
+        let min_distance = Scalar::from_f64(settings.min_distance());
+        let tolerance = self.tolerance(&shape.bounding_volume(), min_distance);
+
+        let mut debug_info = DebugInfo::new();
+        let report = shape
+            .to_shape(tolerance, min_distance, &mut debug_info)?
+            .validate_solid();
+
+        Ok(report)
+    }
+
+    /// Compute the mass properties of an [`fj::Shape`]
+    ///
+    /// See [`MassProperties::from_shape`]. `density` is the mass per unit of
+    /// volume of the shape's material.
+    pub fn mass_properties(
+        &self,
+        shape: &fj::Shape,
+        settings: &fj::Settings,
+        density: Scalar,
+    ) -> Result<MassProperties, Error> {
+        let min_distance = Scalar::from_f64(settings.min_distance());
+        let tolerance = self.tolerance(&shape.bounding_volume(), min_distance);
+
+        let mut debug_info = DebugInfo::new();
+        let shape = shape.to_shape(tolerance, min_distance, &mut debug_info)?;
+
+        Ok(MassProperties::from_shape(&shape, tolerance, density)?)
+    }
+
+    fn tolerance(&self, aabb: &Aabb<3>, min_distance: Scalar) -> Tolerance {
+        match self.tolerance {
             None => {
                 // Compute a reasonable default for the tolerance value. To do
                 // this, we just look at the smallest non-zero extent of the
@@ -29,23 +98,13 @@ impl ShapeProcessor {
                     }
                 }
 
-                let tolerance = min_extent / Scalar::from_f64(1000.);
+                // Approximating the shape more finely than the minimum
+                // distance between distinct points doesn't make sense.
+                let tolerance =
+                    (min_extent / Scalar::from_f64(1000.)).max(min_distance);
                 Tolerance::from_scalar(tolerance).unwrap()
             }
             Some(user_defined_tolerance) => user_defined_tolerance,
-        };
-
-        let mut debug_info = DebugInfo::new();
-        let mesh = triangulate(
-            shape.to_shape(tolerance, &mut debug_info),
-            tolerance,
-            &mut debug_info,
-        );
-
-        ProcessedShape {
-            aabb,
-            mesh,
-            debug_info,
         }
     }
 }
@@ -60,6 +119,21 @@ pub struct ProcessedShape {
     /// The triangle mesh that approximates the original shape
     pub mesh: Mesh<Point<3>>,
 
+    /// The units that the mesh's coordinates are in
+    pub units: fj::Units,
+
     /// The debug info generated while processing the shape
     pub debug_info: DebugInfo,
 }
+
+/// An error that can occur while processing a shape
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// The shape is invalid
+    #[error("Shape is invalid: {0}")]
+    Validation(#[from] ValidationError),
+
+    /// The shape couldn't be triangulated
+    #[error("Error triangulating shape: {0}")]
+    Triangulation(#[from] TriangulationError),
+}
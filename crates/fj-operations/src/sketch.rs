@@ -1,61 +1,98 @@
 use fj_interop::debug::DebugInfo;
 use fj_kernel::{
-    algorithms::Tolerance,
-    geometry::Surface,
-    shape::Shape,
+    algorithms::{CycleApprox, Tolerance},
+    geometry::{Curve, NurbsCurve, Surface},
+    shape::{Handle, Shape, ValidationError, ValidationResult},
     topology::{Cycle, Edge, Face, Vertex},
 };
-use fj_math::{Aabb, Point};
+use fj_math::{Aabb, Point, Scalar};
 
 use super::ToShape;
 
 impl ToShape for fj::Sketch {
-    fn to_shape(&self, _: Tolerance, _: &mut DebugInfo) -> Shape {
-        let mut shape = Shape::new();
-        let mut vertices = Vec::new();
-
-        for [x, y] in self.to_points() {
-            let point = shape.insert(Point::from([x, y, 0.])).unwrap();
-            let vertex = shape.insert(Vertex { point }).unwrap();
-            vertices.push(vertex);
-        }
+    fn to_shape(
+        &self,
+        tolerance: Tolerance,
+        min_distance: Scalar,
+        _: &mut DebugInfo,
+    ) -> Result<Shape, ValidationError> {
+        let mut shape = Shape::new().with_min_distance(min_distance);
 
-        {
-            if !vertices.is_empty() {
-                // Add the first vertex at the end again, to close the loop.
-                //
-                // This can't panic. We just checked that `vertices` is not
-                // empty.
-                vertices.push(vertices[0].clone());
-            }
+        let segments = self.to_segments();
+
+        let points: Vec<_> = segments
+            .iter()
+            .map(|segment| {
+                let [x, y] = match segment {
+                    fj::SketchSegment::LineTo(point) => *point,
+                    // Can't panic. `fj::Spline` makes sure that there's at
+                    // least one control point, besides the implicit one.
+                    fj::SketchSegment::SplineTo(spline) => {
+                        *spline.to_control_points().last().unwrap()
+                    }
+                };
 
+                Point::from([x, y, 0.])
+            })
+            .collect();
+
+        {
             let mut edges = Vec::new();
-            for window in vertices.windows(2) {
-                // Can't panic, we passed `2` to `windows`.
-                //
-                // Can be cleaned up, once `array_windows` is stable.
-                let a = window[0].clone();
-                let b = window[1].clone();
-
-                let edge = Edge::builder(&mut shape)
-                    .build_line_segment_from_vertices([a, b])
-                    .unwrap();
+
+            if let [fj::SketchSegment::SplineTo(spline)] = segments.as_slice() {
+                // A single spline ends where it starts. It connects to itself,
+                // which means the resulting edge has no vertices.
+                let curve = shape.insert(spline_to_curve(spline, points[0]))?;
+
+                let edge = shape.insert(Edge {
+                    curve,
+                    vertices: None,
+                })?;
                 edges.push(edge);
+            } else {
+                let mut vertices = Vec::new();
+                for point in points {
+                    let point = shape.insert(point)?;
+                    let vertex = shape.insert(Vertex { point })?;
+                    vertices.push(vertex);
+                }
+
+                // Each segment starts at the end of the previous one. Build
+                // the edges in the order of the vertices, which means the
+                // first segment, which starts at the last vertex, comes last.
+                for i in (1..segments.len()).chain([0]).take(segments.len()) {
+                    let a = vertices[(i + vertices.len() - 1) % vertices.len()]
+                        .clone();
+                    let b = vertices[i].clone();
+
+                    let edge = build_edge(&mut shape, &segments[i], [a, b])?;
+                    edges.push(edge);
+                }
             }
 
-            shape.insert(Cycle { edges }).unwrap();
+            // The face is on the left of its exterior cycle, so that cycle
+            // needs to run counter-clockwise. Sketches can be defined in
+            // either direction.
+            let cycle = Cycle::from_edges(edges);
+            let cycle = if is_counter_clockwise(&cycle, tolerance) {
+                cycle
+            } else {
+                cycle.reverse()
+            };
+
+            shape.insert(cycle)?;
         };
 
-        let surface = shape.insert(Surface::xy_plane()).unwrap();
-        let face = Face::Face {
+        let surface = shape.insert(Surface::xy_plane())?;
+        let face = Face {
             exteriors: shape.cycles().collect(),
             interiors: Vec::new(),
             surface,
             color: self.color(),
         };
-        shape.insert(face).unwrap();
+        shape.insert(face)?;
 
-        shape
+        Ok(shape)
     }
 
     fn bounding_volume(&self) -> Aabb<3> {
@@ -67,3 +104,62 @@ impl ToShape for fj::Sketch {
         )
     }
 }
+
+fn build_edge(
+    shape: &mut Shape,
+    segment: &fj::SketchSegment,
+    [a, b]: [Handle<Vertex>; 2],
+) -> ValidationResult<Edge> {
+    match segment {
+        fj::SketchSegment::LineTo(_) => {
+            Edge::builder(shape).build_line_segment_from_vertices([a, b])
+        }
+        fj::SketchSegment::SplineTo(spline) => {
+            let curve = spline_to_curve(spline, a.get().point());
+            let curve = shape.insert(curve)?;
+
+            shape.insert(Edge {
+                curve,
+                vertices: Some([a, b]),
+            })
+        }
+    }
+}
+
+/// Indicate whether a cycle in the xy-plane runs counter-clockwise
+fn is_counter_clockwise(cycle: &Cycle, tolerance: Tolerance) -> bool {
+    let points = CycleApprox::new(cycle, tolerance).points;
+
+    let mut area = Scalar::ZERO;
+    for (i, a) in points.iter().enumerate() {
+        let b = points[(i + 1) % points.len()];
+        area += a.x * b.y - b.x * a.y;
+    }
+
+    area > Scalar::ZERO
+}
+
+/// Convert a spline into a curve
+///
+/// `fj::Spline` is validated when it is built, so the number of control points,
+/// weights, and knots always fit together.
+fn spline_to_curve(spline: &fj::Spline, start: Point<3>) -> Curve {
+    let mut control_points = vec![start];
+    control_points.extend(
+        spline
+            .to_control_points()
+            .into_iter()
+            .map(|[x, y]| Point::from([x, y, 0.])),
+    );
+
+    let mut curve = NurbsCurve::clamped(spline.degree(), control_points);
+
+    if let Some(weights) = spline.to_weights() {
+        curve.weights = weights.into_iter().map(Scalar::from).collect();
+    }
+    if let Some(knots) = spline.to_knots() {
+        curve.knots = knots.into_iter().map(Scalar::from).collect();
+    }
+
+    Curve::Nurbs(curve)
+}
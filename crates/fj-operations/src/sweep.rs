@@ -1,9 +1,9 @@
 use fj_interop::debug::DebugInfo;
 use fj_kernel::{
     algorithms::{sweep_shape, Tolerance},
-    shape::Shape,
+    shape::{Shape, ValidationError},
 };
-use fj_math::{Aabb, Point, Vector};
+use fj_math::{Aabb, Point, Scalar, Vector};
 
 use super::ToShape;
 
@@ -11,14 +11,16 @@ impl ToShape for fj::Sweep {
     fn to_shape(
         &self,
         tolerance: Tolerance,
+        min_distance: Scalar,
         debug_info: &mut DebugInfo,
-    ) -> Shape {
-        sweep_shape(
-            self.shape().to_shape(tolerance, debug_info),
+    ) -> Result<Shape, ValidationError> {
+        let shape = sweep_shape(
+            self.shape().to_shape(tolerance, min_distance, debug_info)?,
             Vector::from(self.path()),
-            tolerance,
             self.shape().color(),
-        )
+        );
+
+        Ok(shape)
     }
 
     fn bounding_volume(&self) -> Aabb<3> {
@@ -1,6 +1,9 @@
 use fj_interop::debug::DebugInfo;
-use fj_kernel::{algorithms::Tolerance, shape::Shape};
-use fj_math::{Aabb, Transform, Vector};
+use fj_kernel::{
+    algorithms::Tolerance,
+    shape::{Shape, ValidationError},
+};
+use fj_math::{Aabb, Scalar, Transform, Vector};
 
 use super::ToShape;
 
@@ -8,14 +11,16 @@ impl ToShape for fj::Transform {
     fn to_shape(
         &self,
         tolerance: Tolerance,
+        min_distance: Scalar,
         debug_info: &mut DebugInfo,
-    ) -> Shape {
-        let mut shape = self.shape.to_shape(tolerance, debug_info);
+    ) -> Result<Shape, ValidationError> {
+        let mut shape =
+            self.shape.to_shape(tolerance, min_distance, debug_info)?;
         let transform = transform(self);
 
         shape.transform(&transform);
 
-        shape
+        Ok(shape)
     }
 
     fn bounding_volume(&self) -> Aabb<3> {
@@ -0,0 +1,211 @@
+//! # Fornjot Procedural Macros
+//!
+//! This library is part of the [Fornjot] ecosystem. Fornjot is an open-source,
+//! code-first CAD application; and collection of libraries that make up the CAD
+//! application, but can be used independently.
+//!
+//! This library is an internal component of Fornjot. It provides the
+//! procedural macros that are re-exported by the `fj` crate. Please use them
+//! from there, as `fj::model` and `fj::settings`.
+//!
+//! [Fornjot]: https://www.fornjot.app/
+
+#![deny(missing_docs)]
+
+mod parameters;
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{parse_macro_input, FnArg, Ident, ItemFn, Type};
+
+use self::parameters::Parameter;
+
+/// Define the entry point of a model
+///
+/// Please refer to the documentation of `fj::model`.
+#[proc_macro_attribute]
+pub fn model(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        return syn::Error::new(
+            Span::call_site(),
+            "`fj::model` doesn't accept any arguments",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let function = parse_macro_input!(item as ItemFn);
+
+    let expanded = if takes_raw_arguments(&function) {
+        Ok(expand_raw(function))
+    } else {
+        expand_typed(function)
+    };
+
+    expanded.unwrap_or_else(|err| err.to_compile_error()).into()
+}
+
+/// Mark the function that provides a model's settings
+///
+/// Please refer to the documentation of `fj::settings`.
+#[proc_macro_attribute]
+pub fn settings(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        return syn::Error::new(
+            Span::call_site(),
+            "`fj::settings` doesn't accept any arguments",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let function = parse_macro_input!(item as ItemFn);
+    let name = &function.sig.ident;
+
+    let expanded = quote! {
+        #function
+
+        #[cfg(target_arch = "wasm32")]
+        #[no_mangle]
+        pub extern "C" fn fj_wasm_settings() -> u64 {
+            fj::abi::wasm::encode(&#name())
+        }
+    };
+
+    expanded.into()
+}
+
+/// Indicate whether the function receives the raw arguments
+///
+/// That's a single argument of reference type, which is expected to be a
+/// `&HashMap<String, String>`.
+fn takes_raw_arguments(function: &ItemFn) -> bool {
+    let inputs = &function.sig.inputs;
+
+    inputs.len() == 1
+        && matches!(
+            inputs.first(),
+            Some(FnArg::Typed(input)) if matches!(*input.ty, Type::Reference(_))
+        )
+}
+
+/// Expand a function that receives the raw arguments
+///
+/// The function is responsible for parsing the arguments itself. No parameter
+/// metadata is generated.
+fn expand_raw(function: ItemFn) -> TokenStream2 {
+    let name = &function.sig.ident;
+    let wasm_exports = wasm_exports(false);
+
+    quote! {
+        #function
+
+        #[no_mangle]
+        pub extern "C" fn fj_model(
+            args: &std::collections::HashMap<String, String>,
+        ) -> fj::abi::ModelResult {
+            fj::abi::catch_panic(|| #name(args))
+        }
+
+        #[no_mangle]
+        pub extern "C" fn fj_abi_version() -> u32 {
+            fj::abi::VERSION
+        }
+
+        #wasm_exports
+    }
+}
+
+/// Expand a function that receives typed arguments
+///
+/// Each argument of the function becomes a parameter of the model.
+fn expand_typed(mut function: ItemFn) -> syn::Result<TokenStream2> {
+    let parameters = function
+        .sig
+        .inputs
+        .iter_mut()
+        .map(Parameter::from_input)
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    // The raw arguments must not clash with the names of any parameters.
+    let args = Ident::new("args", Span::mixed_site());
+
+    let name = &function.sig.ident;
+    let idents = parameters.iter().map(|parameter| &parameter.ident);
+    let arguments =
+        parameters.iter().map(|parameter| parameter.argument(&args));
+    let declarations = parameters.iter().map(Parameter::declaration);
+    let wasm_exports = wasm_exports(true);
+
+    Ok(quote! {
+        #function
+
+        #[no_mangle]
+        pub extern "C" fn fj_model(
+            #args: &std::collections::HashMap<String, String>,
+        ) -> fj::abi::ModelResult {
+            fj::abi::catch_panic(|| {
+                #(#arguments)*
+                #name(#(#idents),*)
+            })
+        }
+
+        #[no_mangle]
+        pub extern "C" fn parameters() -> fj::ParameterSchema {
+            fj::ParameterSchema::new()
+                #(.with(#declarations))*
+        }
+
+        #[no_mangle]
+        pub extern "C" fn fj_abi_version() -> u32 {
+            fj::abi::VERSION
+        }
+
+        #wasm_exports
+    })
+}
+
+/// Generate the functions that the host calls, if the model is WebAssembly
+///
+/// These wrap the other generated functions, exchanging data with the host in
+/// serialized form. See `fj::abi::wasm`.
+fn wasm_exports(parameters: bool) -> TokenStream2 {
+    let parameters = parameters.then(|| {
+        quote! {
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn fj_wasm_parameters() -> u64 {
+                fj::abi::wasm::encode(&parameters())
+            }
+        }
+    });
+
+    quote! {
+        #[cfg(target_arch = "wasm32")]
+        #[no_mangle]
+        pub extern "C" fn fj_wasm_alloc(len: usize) -> *mut u8 {
+            fj::abi::wasm::alloc(len)
+        }
+
+        /// # Safety
+        ///
+        /// See `fj::abi::wasm::free`.
+        #[cfg(target_arch = "wasm32")]
+        #[no_mangle]
+        pub unsafe extern "C" fn fj_wasm_free(ptr: *mut u8, len: usize) {
+            fj::abi::wasm::free(ptr, len)
+        }
+
+        /// # Safety
+        ///
+        /// See `fj::abi::wasm::model`.
+        #[cfg(target_arch = "wasm32")]
+        #[no_mangle]
+        pub unsafe extern "C" fn fj_wasm_model(ptr: *mut u8, len: usize) -> u64 {
+            fj::abi::wasm::model(ptr, len, |args| fj_model(args))
+        }
+
+        #parameters
+    }
+}
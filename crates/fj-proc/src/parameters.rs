@@ -0,0 +1,391 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    spanned::Spanned,
+    Attribute, Expr, FnArg, Ident, Lit, Meta, Pat, Token, Type,
+};
+
+/// A parameter of a model, as defined by an argument of the model function
+pub struct Parameter {
+    pub ident: Ident,
+    ty: Type,
+    kind: Kind,
+    description: String,
+    default: Option<Expr>,
+    min: Option<Expr>,
+    max: Option<Expr>,
+}
+
+impl Parameter {
+    /// Parse a parameter from an argument of the model function
+    ///
+    /// Removes the parameter's `#[param]` attribute and its doc comments from
+    /// the argument, as neither is allowed there, once the macro has expanded.
+    pub fn from_input(input: &mut FnArg) -> syn::Result<Self> {
+        let input = match input {
+            FnArg::Typed(input) => input,
+            FnArg::Receiver(receiver) => {
+                return Err(syn::Error::new_spanned(
+                    receiver,
+                    "A model function can't take `self`",
+                ))
+            }
+        };
+
+        let ident = match &*input.pat {
+            Pat::Ident(pat) => pat.ident.clone(),
+            pat => {
+                return Err(syn::Error::new_spanned(
+                    pat,
+                    "Expected a plain identifier for a model parameter",
+                ))
+            }
+        };
+
+        let ty = (*input.ty).clone();
+        let kind = Kind::from_type(&ty)?;
+
+        let mut description = Vec::new();
+        let mut args = Args::default();
+
+        let mut attrs = Vec::new();
+        for attr in input.attrs.drain(..) {
+            if attr.path.is_ident("doc") {
+                description.push(doc_line(&attr)?);
+            } else if attr.path.is_ident("param") {
+                args.merge(attr.parse_args()?, &attr)?;
+            } else {
+                attrs.push(attr);
+            }
+        }
+        input.attrs = attrs;
+
+        // Integer types can't represent values outside of their range, so
+        // there's no need to make the user specify that.
+        let (min, max) = if kind.is_integer() {
+            (
+                args.min.or_else(|| Some(syn::parse_quote!(<#ty>::MIN))),
+                args.max.or_else(|| Some(syn::parse_quote!(<#ty>::MAX))),
+            )
+        } else {
+            (args.min, args.max)
+        };
+
+        Ok(Self {
+            ident,
+            ty,
+            kind,
+            description: summary(&description),
+            default: args.default,
+            min,
+            max,
+        })
+    }
+
+    /// Generate the code that extracts the argument from the raw arguments
+    ///
+    /// `args` is the identifier of the raw arguments.
+    pub fn argument(&self, args: &Ident) -> TokenStream {
+        let Self { ident, ty, .. } = self;
+        let name = ident.to_string();
+
+        quote! {
+            let #ident: #ty = fj::abi::argument(#args, #name);
+        }
+    }
+
+    /// Generate the declaration of the parameter
+    pub fn declaration(&self) -> TokenStream {
+        let name = self.ident.to_string();
+
+        let mut declaration = match self.kind {
+            Kind::Integer => quote! { fj::Parameter::integer(#name) },
+            Kind::Float => quote! { fj::Parameter::float(#name) },
+        };
+
+        if let Some(default) = &self.default {
+            declaration =
+                quote! { #declaration.with_default((#default) as f64) };
+        }
+        if let Some(min) = &self.min {
+            declaration = quote! { #declaration.with_min((#min) as f64) };
+        }
+        if let Some(max) = &self.max {
+            declaration = quote! { #declaration.with_max((#max) as f64) };
+        }
+        if !self.description.is_empty() {
+            let description = &self.description;
+            declaration =
+                quote! { #declaration.with_description(#description) };
+        }
+
+        declaration
+    }
+}
+
+/// The kind of a parameter, as derived from the argument's type
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Kind {
+    Integer,
+    Float,
+}
+
+impl Kind {
+    fn from_type(ty: &Type) -> syn::Result<Self> {
+        let ident = match ty {
+            Type::Path(path) if path.qself.is_none() => path.path.get_ident(),
+            _ => None,
+        };
+
+        let kind = match ident.map(|ident| ident.to_string()).as_deref() {
+            Some(
+                "i8" | "i16" | "i32" | "i64" | "isize" | "u8" | "u16" | "u32"
+                | "u64" | "usize",
+            ) => Self::Integer,
+            Some("f32" | "f64") => Self::Float,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    ty,
+                    "Unsupported type for model parameter; expected an \
+                    integer or floating-point type",
+                ))
+            }
+        };
+
+        Ok(kind)
+    }
+
+    fn is_integer(&self) -> bool {
+        matches!(self, Self::Integer)
+    }
+}
+
+/// The arguments of a `#[param]` attribute
+#[derive(Default)]
+struct Args {
+    default: Option<Expr>,
+    min: Option<Expr>,
+    max: Option<Expr>,
+}
+
+impl Args {
+    fn merge(&mut self, args: ArgList, attr: &Attribute) -> syn::Result<()> {
+        for arg in args.0 {
+            let slot =
+                match arg.name.to_string().as_str() {
+                    "default" => &mut self.default,
+                    "min" => &mut self.min,
+                    "max" => &mut self.max,
+                    _ => return Err(syn::Error::new_spanned(
+                        &arg.name,
+                        "Unknown argument; expected `default`, `min`, or `max`",
+                    )),
+                };
+
+            if slot.is_some() {
+                return Err(syn::Error::new(
+                    attr.span(),
+                    format!("`{}` is specified more than once", arg.name),
+                ));
+            }
+
+            *slot = Some(arg.value);
+        }
+
+        Ok(())
+    }
+}
+
+struct ArgList(Punctuated<Arg, Token![,]>);
+
+impl Parse for ArgList {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        Punctuated::parse_terminated(input).map(Self)
+    }
+}
+
+/// An argument of a `#[param]` attribute, like `default = 5`
+struct Arg {
+    name: Ident,
+    value: Expr,
+}
+
+impl Parse for Arg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let value = input.parse()?;
+
+        Ok(Self { name, value })
+    }
+}
+
+/// Use the first paragraph of a doc comment as the description
+fn summary(lines: &[String]) -> String {
+    lines
+        .iter()
+        .skip_while(|line| line.is_empty())
+        .take_while(|line| !line.is_empty())
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Extract the text of a doc comment
+fn doc_line(attr: &Attribute) -> syn::Result<String> {
+    match attr.parse_meta()? {
+        Meta::NameValue(meta) => match meta.lit {
+            Lit::Str(text) => Ok(text.value().trim().to_owned()),
+            lit => Err(syn::Error::new_spanned(lit, "Expected a string")),
+        },
+        meta => Err(syn::Error::new_spanned(meta, "Expected `doc = \"...\"`")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::quote;
+    use syn::{parse_quote, FnArg, ItemFn};
+
+    use super::{Kind, Parameter};
+
+    #[test]
+    fn integer_range() {
+        let parameter = parse(parse_quote! { count: u8 });
+        assert_eq!(parameter.kind, Kind::Integer);
+        assert_tokens(
+            parameter.declaration(),
+            quote! {
+                fj::Parameter::integer("count")
+                    .with_min((<u8>::MIN) as f64)
+                    .with_max((<u8>::MAX) as f64)
+            },
+        );
+
+        let parameter = parse(parse_quote! {
+            #[param(min = -5)]
+            offset: i32
+        });
+        assert_tokens(
+            parameter.declaration(),
+            quote! {
+                fj::Parameter::integer("offset")
+                    .with_min((-5) as f64)
+                    .with_max((<i32>::MAX) as f64)
+            },
+        );
+    }
+
+    #[test]
+    fn float_range() {
+        let parameter = parse(parse_quote! {
+            /// The radius
+            ///
+            /// More details.
+            #[param(default = 1.5, max = 10.)]
+            radius: f64
+        });
+        assert_eq!(parameter.kind, Kind::Float);
+        assert_tokens(
+            parameter.declaration(),
+            quote! {
+                fj::Parameter::float("radius")
+                    .with_default((1.5) as f64)
+                    .with_max((10.) as f64)
+                    .with_description("The radius")
+            },
+        );
+    }
+
+    #[test]
+    fn self_argument() {
+        let mut input: ItemFn = parse_quote! { fn model(&self) {} };
+        let error = from_input(input.sig.inputs.first_mut().unwrap());
+        assert_eq!(error, "A model function can't take `self`");
+    }
+
+    #[test]
+    fn unsupported_type() {
+        for mut input in [
+            parse_quote! { name: String },
+            parse_quote! { size: Option<f64> },
+            parse_quote! { flag: bool },
+        ] {
+            assert_eq!(
+                from_input(&mut input),
+                "Unsupported type for model parameter; expected an integer or \
+                floating-point type",
+            );
+        }
+    }
+
+    #[test]
+    fn pattern_argument() {
+        let mut input = parse_quote! { (a, b): (f64, f64) };
+        assert_eq!(
+            from_input(&mut input),
+            "Expected a plain identifier for a model parameter",
+        );
+    }
+
+    #[test]
+    fn duplicate_key() {
+        let mut input = parse_quote! {
+            #[param(default = 1)]
+            #[param(min = 0, default = 2)]
+            count: u32
+        };
+        assert_eq!(
+            from_input(&mut input),
+            "`default` is specified more than once",
+        );
+
+        let mut input = parse_quote! {
+            #[param(max = 1, max = 2)]
+            count: u32
+        };
+        assert_eq!(from_input(&mut input), "`max` is specified more than once");
+    }
+
+    #[test]
+    fn unknown_key() {
+        let mut input = parse_quote! {
+            #[param(step = 1)]
+            count: u32
+        };
+        assert_eq!(
+            from_input(&mut input),
+            "Unknown argument; expected `default`, `min`, or `max`",
+        );
+    }
+
+    fn parse(mut input: FnArg) -> Parameter {
+        let parameter = Parameter::from_input(&mut input).unwrap();
+
+        // The attributes are removed from the argument.
+        if let FnArg::Typed(input) = input {
+            assert!(input.attrs.is_empty());
+        }
+
+        parameter
+    }
+
+    /// Parse a parameter, expecting it to fail, and return the error message
+    fn from_input(input: &mut FnArg) -> String {
+        match Parameter::from_input(input) {
+            Ok(parameter) => {
+                panic!("Expected error for parameter `{}`", parameter.ident)
+            }
+            Err(err) => err.to_string(),
+        }
+    }
+
+    fn assert_tokens(
+        actual: proc_macro2::TokenStream,
+        expected: proc_macro2::TokenStream,
+    ) {
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+}
@@ -3,12 +3,18 @@
 //! Provides the functionality to create a window and perform basic viewing
 //! with programmed models.
 
-use std::time::Instant;
+use std::{
+    panic::{self, AssertUnwindSafe},
+    sync::mpsc,
+    thread,
+    time::Instant,
+};
 
-use fj_host::Watcher;
-use fj_operations::shape_processor::ShapeProcessor;
+use fj_host::{LoadedModel, Watcher, WatcherEvent};
+use fj_operations::shape_processor::{self, ProcessedShape, ShapeProcessor};
 use futures::executor::block_on;
-use tracing::{trace, warn};
+use thiserror::Error;
+use tracing::{error, info, trace, warn};
 use winit::{
     event::{Event, WindowEvent},
     event_loop::{ControlFlow, EventLoop},
@@ -39,6 +45,8 @@ pub fn run(
     let mut shape = None;
     let mut camera = None;
 
+    let processed = process_in_background(shape_processor);
+
     event_loop.run(move |event, _, control_flow| {
         trace!("Handling event: {:?}", event);
 
@@ -46,8 +54,44 @@ pub fn run(
 
         let now = Instant::now();
 
-        if let Some(new_shape) = watcher.receive() {
-            let new_shape = shape_processor.process(&new_shape);
+        while let Some(event) = watcher.receive() {
+            match event {
+                WatcherEvent::Building => info!("Building model"),
+                WatcherEvent::Evaluating => info!("Evaluating model"),
+                WatcherEvent::Ready(loaded) => {
+                    for diagnostic in &loaded.diagnostics {
+                        eprint!("{}", diagnostic.render());
+                    }
+
+                    // Can't fail. The processing thread recovers from panics,
+                    // and only stops once the receiving end has been dropped.
+                    let _ = processed.models.send(loaded);
+                }
+                WatcherEvent::Failed(err) => {
+                    // It would be better to display an error in the UI, where
+                    // the user can actually see it. Issue:
+                    // https://github.com/hannobraun/fornjot/issues/30
+                    match err {
+                        fj_host::Error::Compile { diagnostics } => {
+                            for diagnostic in &diagnostics {
+                                eprint!("{}", diagnostic.render());
+                            }
+                            error!("Error compiling model");
+                        }
+                        err => error!("{}", err),
+                    }
+                }
+            }
+        }
+
+        while let Ok(result) = processed.shapes.try_recv() {
+            let new_shape = match result {
+                Ok(new_shape) => new_shape,
+                Err(err) => {
+                    error!("{}", err);
+                    continue;
+                }
+            };
             renderer.update_geometry(
                 (&new_shape.mesh).into(),
                 (&new_shape.debug_info).into(),
@@ -155,3 +199,57 @@ pub fn run(
         }
     });
 }
+
+/// Channels to and from the thread that processes models into shapes
+struct Processing {
+    models: mpsc::Sender<LoadedModel>,
+    shapes: mpsc::Receiver<Result<ProcessedShape, ProcessingError>>,
+}
+
+/// An error that occurred while processing a model in the background
+#[derive(Error, Debug)]
+enum ProcessingError {
+    #[error("Error processing model: {0}")]
+    Shape(#[from] shape_processor::Error),
+
+    #[error("Processing model panicked")]
+    Panic,
+}
+
+/// Process models on a separate thread
+///
+/// Processing a complex model can take a while, and the window should stay
+/// responsive in the meantime. If several models arrive while another is being
+/// processed, only the latest one is processed next.
+///
+/// A panic while processing a model is reported like any other error, and
+/// doesn't stop the thread from processing the models that arrive later.
+fn process_in_background(shape_processor: ShapeProcessor) -> Processing {
+    let (models_tx, models_rx) = mpsc::channel::<LoadedModel>();
+    let (shapes_tx, shapes_rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        for loaded in models_rx.iter() {
+            let loaded = models_rx.try_iter().last().unwrap_or(loaded);
+            let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                shape_processor.process(&loaded.shape, &loaded.settings)
+            }));
+            let result = match result {
+                Ok(result) => result.map_err(Into::into),
+                // The panic message has already been printed by the panic
+                // hook.
+                Err(_) => Err(ProcessingError::Panic),
+            };
+
+            // The receiver is gone, if the window has been closed.
+            if shapes_tx.send(result).is_err() {
+                break;
+            }
+        }
+    });
+
+    Processing {
+        models: models_tx,
+        shapes: shapes_rx,
+    }
+}
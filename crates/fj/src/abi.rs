@@ -0,0 +1,106 @@
+//! The interface between models and the host application
+//!
+//! The items in this module are used by the code that [`crate::model`]
+//! generates. Models shouldn't need to use them directly.
+
+#[cfg(target_arch = "wasm32")]
+pub mod wasm;
+
+use std::{any::Any, collections::HashMap, fmt, panic, str::FromStr};
+
+use crate::{raw_vec::RawVec, Shape};
+
+/// The version of the interface between models and the host application
+///
+/// Models export this, and the host refuses to load models that were built
+/// against a different version. This must be increased whenever the layout of
+/// any of the types that cross that interface changes, or the signature of any
+/// of the functions that models export.
+pub const VERSION: u32 = 1;
+
+/// The result of calling a model
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    any(feature = "serde", target_arch = "wasm32"),
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[repr(C)]
+pub enum ModelResult {
+    /// The model returned a shape
+    Ok(Shape),
+
+    /// The model panicked
+    Panic(PanicMessage),
+}
+
+/// The message of a panic that occurred in a model
+#[derive(Clone)]
+#[cfg_attr(
+    any(feature = "serde", target_arch = "wasm32"),
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[repr(C)]
+pub struct PanicMessage(RawVec<u8>);
+
+impl PanicMessage {
+    fn new(message: &str) -> Self {
+        Self(RawVec::from_vec(message.as_bytes().to_vec()))
+    }
+}
+
+impl fmt::Debug for PanicMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.to_string())
+    }
+}
+
+impl fmt::Display for PanicMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", String::from_utf8_lossy(&self.0.to_vec()))
+    }
+}
+
+/// Call a model function, catching any panic
+///
+/// A panic must not unwind across the FFI boundary into the host application.
+/// This catches it in the model instead, and reports it as part of the result.
+pub fn catch_panic(model: impl FnOnce() -> Shape) -> ModelResult {
+    // The model is not called again after a panic, so there's no chance of
+    // observing any broken invariants.
+    match panic::catch_unwind(panic::AssertUnwindSafe(model)) {
+        Ok(shape) => ModelResult::Ok(shape),
+        Err(payload) => ModelResult::Panic(PanicMessage::new(&panic_message(
+            payload.as_ref(),
+        ))),
+    }
+}
+
+/// Parse the argument for a model parameter
+///
+/// # Panics
+///
+/// Panics, if the argument is missing or can't be parsed. The host validates
+/// the arguments before calling the model, so this only happens, if the model
+/// declares its parameters incorrectly.
+pub fn argument<T: FromStr>(args: &HashMap<String, String>, name: &str) -> T {
+    let value = args
+        .get(name)
+        .unwrap_or_else(|| panic!("Missing argument for parameter `{name}`"));
+
+    value.parse().unwrap_or_else(|_| {
+        panic!("Invalid argument `{value}` for parameter `{name}`")
+    })
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    // Panics created by `panic!` carry either a `&str` or a `String`. If it's
+    // neither, there's not much we can say.
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        return (*message).to_owned();
+    }
+    if let Some(message) = payload.downcast_ref::<String>() {
+        return message.clone();
+    }
+
+    "Model panicked with a non-string payload".to_owned()
+}
@@ -0,0 +1,71 @@
+//! Support for models that are compiled to WebAssembly
+//!
+//! A WebAssembly model can't pass Rust types to the host directly. Instead, the
+//! host writes the serialized arguments into a buffer in the model's memory,
+//! and the model returns its result as another serialized buffer. Buffers are
+//! returned as a `u64`, with the pointer in the upper and the length in the
+//! lower 32 bits.
+//!
+//! Whoever receives a buffer owns it, and must free it using [`free`].
+
+use std::{collections::HashMap, ptr};
+
+use serde::Serialize;
+
+use super::{ModelResult, PanicMessage};
+
+/// Allocate a buffer of the given length
+///
+/// The host uses this to allocate the buffer it writes the arguments into.
+pub fn alloc(len: usize) -> *mut u8 {
+    Box::into_raw(vec![0; len].into_boxed_slice()) as *mut u8
+}
+
+/// Free a buffer
+///
+/// # Safety
+///
+/// `ptr` and `len` must describe a buffer that was allocated by [`alloc`], or
+/// returned by [`encode`], and that hasn't been freed yet.
+pub unsafe fn free(ptr: *mut u8, len: usize) {
+    drop(Box::from_raw(ptr::slice_from_raw_parts_mut(ptr, len)));
+}
+
+/// Call the model function with serialized arguments
+///
+/// Returns the serialized [`ModelResult`].
+///
+/// # Safety
+///
+/// `ptr` and `len` must describe a buffer that was allocated by [`alloc`].
+/// Ownership of that buffer is passed to this function.
+pub unsafe fn model(
+    ptr: *mut u8,
+    len: usize,
+    model: impl FnOnce(&HashMap<String, String>) -> ModelResult,
+) -> u64 {
+    let arguments = Box::from_raw(ptr::slice_from_raw_parts_mut(ptr, len));
+
+    let result = match bincode::deserialize(&arguments) {
+        Ok(arguments) => model(&arguments),
+        Err(err) => ModelResult::Panic(PanicMessage::new(&format!(
+            "Failed to decode arguments: {err}"
+        ))),
+    };
+
+    encode(&result)
+}
+
+/// Serialize a value into a new buffer
+pub fn encode(value: &impl Serialize) -> u64 {
+    // Can't panic. Serializing into a `Vec` only fails, if a `Serialize`
+    // implementation reports an error, and none of ours do.
+    let buffer = bincode::serialize(value)
+        .expect("Failed to serialize value")
+        .into_boxed_slice();
+
+    let len = buffer.len() as u64;
+    let ptr = Box::into_raw(buffer) as *mut u8 as u64;
+
+    (ptr << 32) | len
+}
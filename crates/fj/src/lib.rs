@@ -13,20 +13,102 @@
 //! need the Fornjot application. Please refer to the [Fornjot repository] for
 //! usage examples.
 //!
+//! With the `serde` feature enabled, shapes and settings implement `Serialize`
+//! and `Deserialize`. Shapes can then be converted to and from JSON, using
+//! `Shape::to_json` and `Shape::from_json`, and to and from a compact binary
+//! format, using `Shape::to_bytes` and `Shape::from_bytes`. This is useful for
+//! caching shapes, sending them between processes, or comparing a model's
+//! output against a known-good version.
+//!
 //! [Fornjot]: https://www.fornjot.app/
 //! [Fornjot repository]: https://github.com/hannobraun/Fornjot
 
 #![deny(missing_docs)]
 
+pub mod abi;
 pub mod syntax;
 
+mod parameters;
+mod raw_vec;
+mod settings;
 mod shape_2d;
 mod shape_3d;
 
-pub use self::{shape_2d::*, shape_3d::*};
+#[cfg(feature = "serde")]
+mod serialization;
+
+pub use self::{parameters::*, settings::*, shape_2d::*, shape_3d::*};
+
+/// Define the entry point of a model
+///
+/// Models are dynamic libraries that are loaded by the host application. This
+/// attribute turns a function into the entry point that the host calls.
+///
+/// Each argument of the function is a parameter of the model. Parameters can be
+/// integers or floating-point numbers, and are configured using the `#[param]`
+/// attribute. A parameter's doc comment becomes its description:
+///
+/// ``` rust
+/// #[fj::model]
+/// pub fn model(
+///     /// Number of sides of the polygon
+///     #[param(default = 5, min = 3)]
+///     num_sides: u32,
+///
+///     /// Radius of the circle that the corners of the polygon are on
+///     #[param(default = 1.0, min = 0.0)]
+///     radius: f64,
+/// ) -> fj::Shape {
+///     let points = (0..num_sides)
+///         .map(|i| {
+///             let angle = std::f64::consts::TAU / num_sides as f64 * i as f64;
+///             let (sin, cos) = angle.sin_cos();
+///             [cos * radius, sin * radius]
+///         })
+///         .collect();
+///
+///     fj::Sketch::from_points(points).into()
+/// }
+/// ```
+///
+/// `#[param]` accepts the following arguments, all of which are optional:
+///
+/// - `default`: The value that is used, if the parameter isn't passed. If
+///   there's no default, the parameter is required.
+/// - `min`: The smallest value that the parameter accepts. For integer types,
+///   defaults to the smallest value of the type.
+/// - `max`: The largest value that the parameter accepts. For integer types,
+///   defaults to the largest value of the type.
+///
+/// The host validates the arguments passed to the model against these
+/// declarations, before calling it. See [`ParameterSchema`].
+///
+/// Alternatively, the function can take a single `&HashMap<String, String>`,
+/// to parse the arguments itself. No parameters are declared in that case.
+///
+/// In any case, the function keeps working as a normal Rust function. In
+/// addition, exported functions are generated that call it, and that tell the
+/// host which version of the model interface the model was built for (see
+/// [`abi::VERSION`]). Any panic in the model function is caught, as it would
+/// otherwise unwind into the host application and crash it. The host reports
+/// the panic message as an error instead.
+pub use fj_proc::model;
+
+/// Mark the function that provides a model's settings
+///
+/// Apply this to the `settings` function that is described in [`Settings`].
+/// The function itself is left as it is. Models that are compiled to
+/// WebAssembly can't return settings through the C ABI, so an additional
+/// exported function is generated for them, that passes the settings to the
+/// host in serialized form.
+pub use fj_proc::settings;
 
 /// A shape
 #[derive(Clone, Debug)]
+#[cfg_attr(
+    any(feature = "serde", target_arch = "wasm32"),
+    derive(serde::Serialize, serde::Deserialize)
+)]
 #[repr(C)]
 pub enum Shape {
     /// A 2D shape
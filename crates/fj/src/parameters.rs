@@ -0,0 +1,223 @@
+use std::fmt;
+
+use crate::raw_vec::RawVec;
+
+/// Declares the parameters that a model accepts
+///
+/// Usually, this is generated from the arguments of the model function by
+/// [`crate::model`]. Models can also declare their parameters manually, by
+/// exporting a function named `parameters`:
+///
+/// ``` rust
+/// #[no_mangle]
+/// pub extern "C" fn parameters() -> fj::ParameterSchema {
+///     fj::ParameterSchema::new()
+///         .with(
+///             fj::Parameter::integer("num_points")
+///                 .with_default(5.)
+///                 .with_min(3.)
+///                 .with_description("Number of points of the star"),
+///         )
+///         .with(fj::Parameter::float("height").with_default(1.))
+/// }
+/// ```
+///
+/// The host validates the parameters that are passed to the model against these
+/// declarations, before calling the model. Defaults are filled in for any
+/// parameters that weren't passed, so the model can rely on all declared
+/// parameters being present and valid.
+///
+/// Models that don't export this function get whatever parameters are passed
+/// to them, without any validation.
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    any(feature = "serde", target_arch = "wasm32"),
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[repr(C)]
+pub struct ParameterSchema {
+    parameters: RawVec<Parameter>,
+}
+
+impl ParameterSchema {
+    /// Construct an empty schema
+    pub fn new() -> Self {
+        Self {
+            parameters: RawVec::from_vec(Vec::new()),
+        }
+    }
+
+    /// Add a parameter to the schema
+    pub fn with(self, parameter: Parameter) -> Self {
+        let mut parameters = self.parameters.to_vec();
+        parameters.push(parameter);
+
+        Self {
+            parameters: RawVec::from_vec(parameters),
+        }
+    }
+
+    /// Access the parameters in the schema
+    pub fn parameters(&self) -> Vec<Parameter> {
+        self.parameters.to_vec()
+    }
+}
+
+impl Default for ParameterSchema {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A parameter that a model accepts
+///
+/// See [`ParameterSchema`].
+#[derive(Clone)]
+#[cfg_attr(
+    any(feature = "serde", target_arch = "wasm32"),
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[repr(C)]
+pub struct Parameter {
+    name: RawVec<u8>,
+    kind: ParameterKind,
+    description: RawVec<u8>,
+
+    // NaN means that there's no default value, infinity means that there's no
+    // bound. Not `Option`s, as this needs to be FFI-safe.
+    default: f64,
+    min: f64,
+    max: f64,
+}
+
+impl Parameter {
+    /// Construct a parameter of the given kind
+    ///
+    /// The parameter has no default value, and is therefore required. Use
+    /// [`Parameter::with_default`] to make it optional.
+    pub fn new(name: &str, kind: ParameterKind) -> Self {
+        Self {
+            name: RawVec::from_vec(name.as_bytes().to_vec()),
+            kind,
+            description: RawVec::from_vec(Vec::new()),
+            default: f64::NAN,
+            min: f64::NEG_INFINITY,
+            max: f64::INFINITY,
+        }
+    }
+
+    /// Construct an integer parameter
+    pub fn integer(name: &str) -> Self {
+        Self::new(name, ParameterKind::Integer)
+    }
+
+    /// Construct a floating-point parameter
+    pub fn float(name: &str) -> Self {
+        Self::new(name, ParameterKind::Float)
+    }
+
+    /// Set the value that is used, if the parameter isn't passed
+    pub fn with_default(mut self, default: f64) -> Self {
+        self.default = default;
+        self
+    }
+
+    /// Set the smallest value that the parameter accepts
+    pub fn with_min(mut self, min: f64) -> Self {
+        self.min = min;
+        self
+    }
+
+    /// Set the largest value that the parameter accepts
+    pub fn with_max(mut self, max: f64) -> Self {
+        self.max = max;
+        self
+    }
+
+    /// Set a description of the parameter, to be shown to the user
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = RawVec::from_vec(description.as_bytes().to_vec());
+        self
+    }
+
+    /// Access the name of the parameter
+    pub fn name(&self) -> String {
+        String::from_utf8_lossy(&self.name.to_vec()).into_owned()
+    }
+
+    /// Access the kind of the parameter
+    pub fn kind(&self) -> ParameterKind {
+        self.kind
+    }
+
+    /// Access the description of the parameter
+    ///
+    /// Returns an empty string, if no description was set.
+    pub fn description(&self) -> String {
+        String::from_utf8_lossy(&self.description.to_vec()).into_owned()
+    }
+
+    /// Access the default value of the parameter, if any
+    pub fn default(&self) -> Option<f64> {
+        if self.default.is_nan() {
+            return None;
+        }
+
+        Some(self.default)
+    }
+
+    /// Access the smallest value that the parameter accepts, if limited
+    pub fn min(&self) -> Option<f64> {
+        if !self.min.is_finite() {
+            return None;
+        }
+
+        Some(self.min)
+    }
+
+    /// Access the largest value that the parameter accepts, if limited
+    pub fn max(&self) -> Option<f64> {
+        if !self.max.is_finite() {
+            return None;
+        }
+
+        Some(self.max)
+    }
+}
+
+impl fmt::Debug for Parameter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Parameter")
+            .field("name", &self.name())
+            .field("kind", &self.kind)
+            .field("description", &self.description())
+            .field("default", &self.default())
+            .field("min", &self.min())
+            .field("max", &self.max())
+            .finish()
+    }
+}
+
+/// The kind of value that a parameter accepts
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(
+    any(feature = "serde", target_arch = "wasm32"),
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[repr(C)]
+pub enum ParameterKind {
+    /// An integer, like `5` or `-2`
+    Integer,
+
+    /// A floating-point number, like `1.5` or `3`
+    Float,
+}
+
+impl fmt::Display for ParameterKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Integer => write!(f, "an integer"),
+            Self::Float => write!(f, "a number"),
+        }
+    }
+}
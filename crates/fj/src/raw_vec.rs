@@ -0,0 +1,138 @@
+use std::{fmt, mem};
+
+/// The raw parts of a `Vec`
+///
+/// Shapes need to be FFI-safe, meaning they can't store a `Vec` directly. They
+/// need to take this detour.
+#[repr(C)]
+pub struct RawVec<T> {
+    ptr: *mut T,
+    length: usize,
+    capacity: usize,
+}
+
+impl<T: Clone> RawVec<T> {
+    /// Take ownership of the memory of a `Vec`
+    pub fn from_vec(mut vec: Vec<T>) -> Self {
+        // This can be cleaned up, once `Vec::into_raw_parts` is stable.
+        let ptr = vec.as_mut_ptr();
+        let length = vec.len();
+        let capacity = vec.capacity();
+
+        // We're taking ownership of the memory here, so we can't allow `vec` to
+        // deallocate it.
+        mem::forget(vec);
+
+        Self {
+            ptr,
+            length,
+            capacity,
+        }
+    }
+
+    /// Return a copy of the `Vec`
+    pub fn to_vec(&self) -> Vec<T> {
+        // This is sound. All invariants are automatically kept, as the raw
+        // parts come from an original `Vec` that is identical to the new one we
+        // create here, and aren't being modified anywhere.
+        let vec = unsafe {
+            Vec::from_raw_parts(self.ptr, self.length, self.capacity)
+        };
+
+        // Ownership of the pointer in `self` transferred to `vec`. We work
+        // around that, by returning a clone of `vec` (hence not giving
+        // ownership to the caller).
+        let ret = vec.clone();
+
+        // Now we just need to forget that `vec` ever existed, and we keep
+        // ownership of the pointer.
+        mem::forget(vec);
+
+        ret
+    }
+}
+
+impl<T: Clone> Clone for RawVec<T> {
+    fn clone(&self) -> Self {
+        // `to_vec` copies the elements, so the clone owns its own memory.
+        Self::from_vec(self.to_vec())
+    }
+}
+
+impl<T> Drop for RawVec<T> {
+    fn drop(&mut self) {
+        // This is sound, for the same reasons as in `to_vec`. `self` is never
+        // used again, so the rebuilt `Vec` can take ownership of the memory and
+        // free it.
+        let vec = unsafe {
+            Vec::from_raw_parts(self.ptr, self.length, self.capacity)
+        };
+        drop(vec);
+    }
+}
+
+impl<T: Clone + fmt::Debug> fmt::Debug for RawVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.to_vec()).finish()
+    }
+}
+
+// `RawVec` can be `Send`, because it encapsulates the raw pointer it contains,
+// making sure memory ownership rules are observed.
+unsafe impl<T: Send> Send for RawVec<T> {}
+
+// `RawVec` is serialized as the `Vec` it represents. The raw parts are only
+// meaningful within the process that created them.
+#[cfg(any(feature = "serde", target_arch = "wasm32"))]
+impl<T: Clone + serde::Serialize> serde::Serialize for RawVec<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.to_vec().serialize(serializer)
+    }
+}
+
+#[cfg(any(feature = "serde", target_arch = "wasm32"))]
+impl<'de, T> serde::Deserialize<'de> for RawVec<T>
+where
+    T: Clone + serde::Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Vec::deserialize(deserializer).map(Self::from_vec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::RawVec;
+
+    #[test]
+    fn clone_is_deep() {
+        let original = RawVec::from_vec(vec![1, 2, 3]);
+        let clone = original.clone();
+        drop(original);
+
+        assert_eq!(clone.to_vec(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn drop_frees_elements() {
+        let element = Rc::new(());
+
+        let raw_vec = RawVec::from_vec(vec![element.clone(), element.clone()]);
+        let clone = raw_vec.clone();
+        assert_eq!(Rc::strong_count(&element), 5);
+
+        drop(raw_vec);
+        assert_eq!(Rc::strong_count(&element), 3);
+
+        drop(clone);
+        assert_eq!(Rc::strong_count(&element), 1);
+    }
+}
@@ -0,0 +1,104 @@
+use crate::Shape;
+
+/// Serialization of shapes
+///
+/// Shapes can be serialized to JSON, which is human-readable and suitable for
+/// comparing a model's output against a known-good version, or to a compact
+/// binary format, which is suitable for caching shapes or sending them between
+/// processes.
+///
+/// ``` rust
+/// let shape: fj::Shape = fj::Circle::from_radius(1.).into();
+///
+/// let json = shape.to_json()?;
+/// assert_eq!(fj::Shape::from_json(&json)?.to_json()?, json);
+///
+/// let bytes = shape.to_bytes()?;
+/// assert_eq!(fj::Shape::from_bytes(&bytes)?.to_json()?, json);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+impl Shape {
+    /// Serialize the shape to JSON
+    ///
+    /// The JSON is pretty-printed, to make it easy to read and to compare.
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Deserialize a shape from JSON
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Serialize the shape to a compact binary format
+    ///
+    /// The format is not self-describing, and is only guaranteed to be
+    /// readable by the same version of this library.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, bincode::Error> {
+        bincode::serialize(self)
+    }
+
+    /// Deserialize a shape from the binary format
+    ///
+    /// See [`Shape::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, bincode::Error> {
+        bincode::deserialize(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Shape, Shape2d, Shape3d, Sketch, SketchSegment, Spline, Sweep,
+        Transform,
+    };
+
+    #[test]
+    fn round_trip() -> Result<(), Box<dyn std::error::Error>> {
+        let spline = Spline::from_control_points(2, vec![[1., 1.], [2., 0.]])?
+            .with_weights(vec![1., 0.5, 1.])?
+            .with_knots(vec![0., 0., 0., 1., 1., 1.])?;
+        let sketch = Sketch::from_segments(vec![
+            SketchSegment::LineTo([0., 0.]),
+            SketchSegment::SplineTo(spline),
+        ]);
+        let shape: Shape = Transform {
+            shape: Sweep::from_path(sketch.into(), [0., 0., 1.]).into(),
+            axis: [0., 0., 1.],
+            angle: 1.,
+            offset: [1., 2., 3.],
+        }
+        .into();
+
+        let json = shape.to_json()?;
+        assert_eq!(Shape::from_json(&json)?.to_json()?, json);
+
+        let from_bytes = Shape::from_bytes(&shape.to_bytes()?)?;
+        assert_eq!(from_bytes.to_json()?, json);
+
+        let transform = match from_bytes {
+            Shape::Shape3d(Shape3d::Transform(transform)) => transform,
+            shape => panic!("Expected transform, got {:?}", shape),
+        };
+        assert_eq!(transform.offset, [1., 2., 3.]);
+
+        let sweep = match transform.shape {
+            Shape3d::Sweep(sweep) => sweep,
+            shape => panic!("Expected sweep, got {:?}", shape),
+        };
+        let sketch = match sweep.shape() {
+            Shape2d::Sketch(sketch) => sketch,
+            shape => panic!("Expected sketch, got {:?}", shape),
+        };
+        match &sketch.to_segments()[..] {
+            [SketchSegment::LineTo(point), SketchSegment::SplineTo(spline)] => {
+                assert_eq!(*point, [0., 0.]);
+                assert_eq!(spline.to_control_points(), [[1., 1.], [2., 0.]]);
+                assert_eq!(spline.to_weights(), Some(vec![1., 0.5, 1.]));
+            }
+            segments => panic!("Unexpected segments: {:?}", segments),
+        }
+
+        Ok(())
+    }
+}
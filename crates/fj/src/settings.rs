@@ -0,0 +1,125 @@
+/// Settings that apply to a whole model
+///
+/// Models can provide settings by exporting a function named `settings`, next
+/// to their `model` function:
+///
+/// ``` rust
+/// #[fj::settings]
+/// #[no_mangle]
+/// pub extern "C" fn settings() -> fj::Settings {
+///     fj::Settings::new()
+///         .with_units(fj::Units::Inches)
+///         .with_min_distance(0.001)
+/// }
+/// ```
+///
+/// The `#[fj::settings]` attribute is only required for models that are
+/// compiled to WebAssembly, but it doesn't hurt to always use it.
+///
+/// Models that don't export this function get the defaults, as returned by
+/// [`Settings::new`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(
+    any(feature = "serde", target_arch = "wasm32"),
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[repr(C)]
+pub struct Settings {
+    units: Units,
+
+    // Zero means that no minimum distance was set, and the default for the
+    // units should be used. Not an `Option`, as this needs to be FFI-safe.
+    min_distance: f64,
+}
+
+impl Settings {
+    /// Construct the default settings
+    ///
+    /// Uses millimeters, and the default minimum distance for those.
+    pub fn new() -> Self {
+        Self {
+            units: Units::Millimeters,
+            min_distance: 0.,
+        }
+    }
+
+    /// Set the units that the model's coordinates are in
+    pub fn with_units(mut self, units: Units) -> Self {
+        self.units = units;
+        self
+    }
+
+    /// Set the minimum distance between distinct points in the model
+    ///
+    /// Points that are closer to each other than this are considered to be
+    /// the same. The distance is given in the model's units.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `min_distance` is not positive.
+    pub fn with_min_distance(mut self, min_distance: f64) -> Self {
+        assert!(min_distance > 0., "Minimum distance must be positive");
+
+        self.min_distance = min_distance;
+        self
+    }
+
+    /// Access the units that the model's coordinates are in
+    pub fn units(&self) -> Units {
+        self.units
+    }
+
+    /// Access the minimum distance between distinct points in the model
+    ///
+    /// Returns the default for the model's units (see
+    /// [`Units::default_min_distance`]), if none was set.
+    pub fn min_distance(&self) -> f64 {
+        if self.min_distance > 0. {
+            self.min_distance
+        } else {
+            self.units.default_min_distance()
+        }
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The units that a model's coordinates are in
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(
+    any(feature = "serde", target_arch = "wasm32"),
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[repr(C)]
+pub enum Units {
+    /// Millimeters
+    Millimeters,
+
+    /// Inches
+    Inches,
+
+    /// Meters
+    Meters,
+}
+
+impl Units {
+    /// The length of one unit, in millimeters
+    pub fn in_millimeters(&self) -> f64 {
+        match self {
+            Self::Millimeters => 1.,
+            Self::Inches => 25.4,
+            Self::Meters => 1000.,
+        }
+    }
+
+    /// The default minimum distance between distinct points
+    ///
+    /// This is 0.5 µm, converted to these units.
+    pub fn default_min_distance(&self) -> f64 {
+        0.0005 / self.in_millimeters()
+    }
+}
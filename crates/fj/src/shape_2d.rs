@@ -1,9 +1,13 @@
-use std::mem;
+use std::fmt;
 
-use crate::Shape;
+use crate::{raw_vec::RawVec, Shape};
 
 /// A 2-dimensional shape
 #[derive(Clone, Debug)]
+#[cfg_attr(
+    any(feature = "serde", target_arch = "wasm32"),
+    derive(serde::Serialize, serde::Deserialize)
+)]
 #[repr(C)]
 pub enum Shape2d {
     /// A circle
@@ -29,6 +33,10 @@ impl Shape2d {
 
 /// A circle
 #[derive(Clone, Debug)]
+#[cfg_attr(
+    any(feature = "serde", target_arch = "wasm32"),
+    derive(serde::Serialize, serde::Deserialize)
+)]
 #[repr(C)]
 pub struct Circle {
     /// The radius of the circle
@@ -82,6 +90,10 @@ impl From<Circle> for Shape2d {
 
 /// A difference between two shapes
 #[derive(Clone, Debug)]
+#[cfg_attr(
+    any(feature = "serde", target_arch = "wasm32"),
+    derive(serde::Serialize, serde::Deserialize)
+)]
 #[repr(C)]
 pub struct Difference2d {
     shapes: [Shape2d; 2],
@@ -118,64 +130,62 @@ impl From<Difference2d> for Shape2d {
 
 /// A sketch
 ///
-/// Sketches are currently limited to a single cycle of straight lines,
-/// represented by a number of points. For example, if the points a, b, and c
-/// are provided, the edges ab, bc, and ca are assumed.
+/// Sketches are currently limited to a single cycle of segments. Each segment
+/// starts where the previous one ends, and the first segment starts where the
+/// last one ends. For example, if the points a, b, and c are provided via
+/// [`Sketch::from_points`], the edges ab, bc, and ca are assumed.
 ///
 /// Nothing about these edges is checked right now, but algorithms might assume
 /// that the edges are non-overlapping. If you create a `Sketch` with
 /// overlapping edges, you're on your own.
 #[derive(Clone, Debug)]
+#[cfg_attr(
+    any(feature = "serde", target_arch = "wasm32"),
+    derive(serde::Serialize, serde::Deserialize)
+)]
 #[repr(C)]
 pub struct Sketch {
-    // The fields are the raw parts of a `Vec`. `Sketch` needs to be FFI-safe,
-    // meaning it can't store a `Vec` directly. It needs to take this detour.
-    ptr: *mut [f64; 2],
-    length: usize,
-    capacity: usize,
+    segments: RawVec<SketchSegment>,
     // The color of the sketch in RGBA
     color: [u8; 4],
 }
 
 impl Sketch {
     /// Create a sketch from a bunch of points
-    pub fn from_points(mut points: Vec<[f64; 2]>) -> Self {
-        // This can be cleaned up, once `Vec::into_raw_parts` is stable.
-        let ptr = points.as_mut_ptr();
-        let length = points.len();
-        let capacity = points.capacity();
-
-        // We're taking ownership of the memory here, so we can't allow `points`
-        // to deallocate it.
-        mem::forget(points);
+    ///
+    /// The points are connected by straight lines.
+    pub fn from_points(points: Vec<[f64; 2]>) -> Self {
+        Self::from_segments(
+            points.into_iter().map(SketchSegment::LineTo).collect(),
+        )
+    }
 
+    /// Create a sketch from a bunch of segments
+    pub fn from_segments(segments: Vec<SketchSegment>) -> Self {
         Self {
-            ptr,
-            length,
-            capacity,
+            segments: RawVec::from_vec(segments),
             color: [255, 0, 0, 255],
         }
     }
 
+    /// Return the segments of the sketch
+    pub fn to_segments(&self) -> Vec<SketchSegment> {
+        self.segments.to_vec()
+    }
+
     /// Return the points of the sketch
+    ///
+    /// Returns the end point of every segment. For spline segments, all of
+    /// their control points are returned. The sketch is contained within the
+    /// convex hull of the returned points.
     pub fn to_points(&self) -> Vec<[f64; 2]> {
-        // This is sound. All invariants are automatically kept, as the raw
-        // parts come from an original `Vec` that is identical to the new one we
-        // create here, and aren't being modified anywhere.
-        let points = unsafe {
-            Vec::from_raw_parts(self.ptr, self.length, self.capacity)
-        };
-
-        // Ownership of the pointer in `self.raw_parts` transferred to `points`.
-        // We work around that, by returning a clone of `points` (hence not
-        // giving ownership to the caller).
-        let ret = points.clone();
-
-        // Now we just need to forget that `points` ever existed, and we keep
-        // ownership of the pointer.
-        mem::forget(points);
-
-        ret
+        self.to_segments()
+            .into_iter()
+            .flat_map(|segment| match segment {
+                SketchSegment::LineTo(point) => vec![point],
+                SketchSegment::SplineTo(spline) => spline.to_control_points(),
+            })
+            .collect()
     }
 
     /// Set the rendering color of the sketch in RGBA
@@ -207,6 +217,346 @@ impl From<Sketch> for Shape2d {
     }
 }
 
-// `Sketch` can be `Send`, because it encapsulates the raw pointer it contains,
-// making sure memory ownership rules are observed.
-unsafe impl Send for Sketch {}
+/// A segment of a [`Sketch`]
+///
+/// Each segment starts at the end of the previous segment.
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    any(feature = "serde", target_arch = "wasm32"),
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[repr(C)]
+pub enum SketchSegment {
+    /// A straight line to the given point
+    LineTo([f64; 2]),
+
+    /// A spline
+    SplineTo(Spline),
+}
+
+/// A spline segment of a [`Sketch`]
+///
+/// The spline is a NURBS curve. Its first control point is the end of the
+/// previous segment, which is why it is not part of the control points that
+/// are passed to [`Spline::from_control_points`]. The last control point is
+/// the end of the spline.
+///
+/// By default, the spline is non-rational (all weights are `1.`) and uses a
+/// clamped, uniform knot vector, meaning it actually passes through its first
+/// and last control point. Both can be overridden.
+///
+/// The spline is validated when it is built, so every `Spline` describes a
+/// valid curve.
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    any(feature = "serde", target_arch = "wasm32"),
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "SplineFields")
+)]
+#[repr(C)]
+pub struct Spline {
+    degree: usize,
+    control_points: RawVec<[f64; 2]>,
+    weights: RawVec<f64>,
+    knots: RawVec<f64>,
+}
+
+impl Spline {
+    /// Create a spline from its degree and control points
+    ///
+    /// Including the implicit first one, a spline needs at least one control
+    /// point more than its degree, and never less than two. All coordinates of
+    /// the control points must be finite.
+    pub fn from_control_points(
+        degree: usize,
+        control_points: Vec<[f64; 2]>,
+    ) -> Result<Self, SplineError> {
+        let spline = Self {
+            degree,
+            control_points: RawVec::from_vec(control_points),
+            weights: RawVec::from_vec(Vec::new()),
+            knots: RawVec::from_vec(Vec::new()),
+        };
+        spline.validate()?;
+
+        Ok(spline)
+    }
+
+    /// Set the weights of the spline
+    ///
+    /// Must contain one positive, finite weight for each control point,
+    /// including the implicit first one.
+    pub fn with_weights(
+        mut self,
+        weights: Vec<f64>,
+    ) -> Result<Self, SplineError> {
+        self.weights = RawVec::from_vec(weights);
+        self.validate()?;
+
+        Ok(self)
+    }
+
+    /// Set the knot vector of the spline
+    ///
+    /// Must contain one knot for each control point (including the implicit
+    /// first one), plus the degree of the spline, plus one. The knots must be
+    /// finite, must not decrease, and must not all be equal.
+    pub fn with_knots(mut self, knots: Vec<f64>) -> Result<Self, SplineError> {
+        self.knots = RawVec::from_vec(knots);
+        self.validate()?;
+
+        Ok(self)
+    }
+
+    fn validate(&self) -> Result<(), SplineError> {
+        let degree = self.degree;
+        let control_points = self.control_points.to_vec();
+
+        // The implicit first control point is not stored.
+        let num_control_points = control_points.len() + 1;
+
+        let required = degree.max(1) + 1;
+        if num_control_points < required {
+            return Err(SplineError::TooFewControlPoints {
+                degree,
+                required,
+                actual: num_control_points,
+            });
+        }
+        if control_points
+            .iter()
+            .flatten()
+            .any(|value| !value.is_finite())
+        {
+            return Err(SplineError::NonFiniteControlPoint);
+        }
+
+        if let Some(weights) = self.to_weights() {
+            if weights.len() != num_control_points {
+                return Err(SplineError::WrongNumberOfWeights {
+                    expected: num_control_points,
+                    actual: weights.len(),
+                });
+            }
+            if weights
+                .iter()
+                .any(|&weight| !(weight.is_finite() && weight > 0.))
+            {
+                return Err(SplineError::InvalidWeight);
+            }
+        }
+
+        if let Some(knots) = self.to_knots() {
+            let expected = num_control_points + degree + 1;
+            if knots.len() != expected {
+                return Err(SplineError::WrongNumberOfKnots {
+                    expected,
+                    actual: knots.len(),
+                });
+            }
+            if knots.iter().any(|knot| !knot.is_finite()) {
+                return Err(SplineError::NonFiniteKnot);
+            }
+            if knots.windows(2).any(|pair| pair[1] < pair[0]) {
+                return Err(SplineError::DecreasingKnots);
+            }
+            if knots.first() == knots.last() {
+                return Err(SplineError::EmptyDomain);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Access the degree of the spline
+    pub fn degree(&self) -> usize {
+        self.degree
+    }
+
+    /// Return the control points of the spline
+    ///
+    /// The implicit first control point is not included.
+    pub fn to_control_points(&self) -> Vec<[f64; 2]> {
+        self.control_points.to_vec()
+    }
+
+    /// Return the weights of the spline, if they have been set
+    pub fn to_weights(&self) -> Option<Vec<f64>> {
+        let weights = self.weights.to_vec();
+        if weights.is_empty() {
+            return None;
+        }
+
+        Some(weights)
+    }
+
+    /// Return the knot vector of the spline, if it has been set
+    pub fn to_knots(&self) -> Option<Vec<f64>> {
+        let knots = self.knots.to_vec();
+        if knots.is_empty() {
+            return None;
+        }
+
+        Some(knots)
+    }
+}
+
+/// The fields of a [`Spline`], before they have been validated
+///
+/// Deserializing a spline goes through this, so a spline that was serialized
+/// by someone else can't skip validation.
+#[cfg(any(feature = "serde", target_arch = "wasm32"))]
+#[derive(serde::Deserialize)]
+struct SplineFields {
+    degree: usize,
+    control_points: RawVec<[f64; 2]>,
+    weights: RawVec<f64>,
+    knots: RawVec<f64>,
+}
+
+#[cfg(any(feature = "serde", target_arch = "wasm32"))]
+impl TryFrom<SplineFields> for Spline {
+    type Error = SplineError;
+
+    fn try_from(fields: SplineFields) -> Result<Self, Self::Error> {
+        let spline = Self {
+            degree: fields.degree,
+            control_points: fields.control_points,
+            weights: fields.weights,
+            knots: fields.knots,
+        };
+        spline.validate()?;
+
+        Ok(spline)
+    }
+}
+
+/// An error that occurs, if a [`Spline`] is built from invalid data
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SplineError {
+    /// The spline doesn't have enough control points for its degree
+    ///
+    /// The numbers include the implicit first control point.
+    TooFewControlPoints {
+        /// The degree of the spline
+        degree: usize,
+
+        /// The number of control points that are required
+        required: usize,
+
+        /// The number of control points that were provided
+        actual: usize,
+    },
+
+    /// The number of weights doesn't match the number of control points
+    WrongNumberOfWeights {
+        /// The number of weights that is required
+        expected: usize,
+
+        /// The number of weights that were provided
+        actual: usize,
+    },
+
+    /// A control point has a coordinate that is not finite
+    NonFiniteControlPoint,
+
+    /// A weight is not a positive, finite number
+    InvalidWeight,
+
+    /// The number of knots doesn't match the number of control points
+    WrongNumberOfKnots {
+        /// The number of knots that is required
+        expected: usize,
+
+        /// The number of knots that were provided
+        actual: usize,
+    },
+
+    /// A knot is not finite
+    NonFiniteKnot,
+
+    /// The knot vector decreases somewhere
+    DecreasingKnots,
+
+    /// All knots are equal, leaving the spline without a parameter domain
+    EmptyDomain,
+}
+
+impl fmt::Display for SplineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooFewControlPoints {
+                degree,
+                required,
+                actual,
+            } => write!(
+                f,
+                "Spline of degree {degree} needs at least {required} control \
+                points, including the implicit first one; got {actual}"
+            ),
+            Self::WrongNumberOfWeights { expected, actual } => write!(
+                f,
+                "Spline needs one weight per control point ({expected}); got \
+                {actual}"
+            ),
+            Self::NonFiniteControlPoint => {
+                write!(f, "Control points of spline must be finite")
+            }
+            Self::InvalidWeight => {
+                write!(f, "Weights of spline must be positive and finite")
+            }
+            Self::WrongNumberOfKnots { expected, actual } => write!(
+                f,
+                "Spline needs {expected} knots for its control points and \
+                degree; got {actual}"
+            ),
+            Self::NonFiniteKnot => {
+                write!(f, "Knots of spline must be finite")
+            }
+            Self::DecreasingKnots => {
+                write!(f, "Knots of spline must not decrease")
+            }
+            Self::EmptyDomain => {
+                write!(f, "Knots of spline must not all be equal")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SplineError {}
+
+#[cfg(test)]
+mod tests {
+    use super::{Spline, SplineError};
+
+    fn spline() -> Spline {
+        Spline::from_control_points(1, vec![[1., 0.]]).unwrap()
+    }
+
+    #[test]
+    fn spline_rejects_non_finite_values() {
+        assert_eq!(
+            Spline::from_control_points(1, vec![[f64::NAN, 0.]]).unwrap_err(),
+            SplineError::NonFiniteControlPoint,
+        );
+        assert_eq!(
+            spline().with_weights(vec![1., f64::INFINITY]).unwrap_err(),
+            SplineError::InvalidWeight,
+        );
+        assert_eq!(
+            spline()
+                .with_knots(vec![0., 0., 1., f64::INFINITY])
+                .unwrap_err(),
+            SplineError::NonFiniteKnot,
+        );
+    }
+
+    #[test]
+    fn spline_rejects_empty_domain() {
+        assert_eq!(
+            spline().with_knots(vec![1., 1., 1., 1.]).unwrap_err(),
+            SplineError::EmptyDomain,
+        );
+        assert!(spline().with_knots(vec![0., 0., 1., 1.]).is_ok());
+    }
+}
@@ -2,6 +2,10 @@ use crate::{Shape, Shape2d};
 
 /// A 3-dimensional shape
 #[derive(Clone, Debug)]
+#[cfg_attr(
+    any(feature = "serde", target_arch = "wasm32"),
+    derive(serde::Serialize, serde::Deserialize)
+)]
 #[repr(C)]
 pub enum Shape3d {
     /// A group of two 3-dimensional shapes
@@ -29,6 +33,10 @@ impl From<Shape3d> for Shape {
 ///
 /// Whether the shapes in the group touch or overlap is not currently checked.
 #[derive(Clone, Debug)]
+#[cfg_attr(
+    any(feature = "serde", target_arch = "wasm32"),
+    derive(serde::Serialize, serde::Deserialize)
+)]
 #[repr(C)]
 pub struct Group {
     /// The first of the shapes
@@ -60,6 +68,10 @@ impl From<Group> for Shape3d {
 /// See issue:
 /// <https://github.com/hannobraun/Fornjot/issues/101>
 #[derive(Clone, Debug)]
+#[cfg_attr(
+    any(feature = "serde", target_arch = "wasm32"),
+    derive(serde::Serialize, serde::Deserialize)
+)]
 #[repr(C)]
 pub struct Transform {
     /// The shape being transformed
@@ -89,6 +101,10 @@ impl From<Transform> for Shape3d {
 
 /// A sweep of a 2-dimensional shape along straight path
 #[derive(Clone, Debug)]
+#[cfg_attr(
+    any(feature = "serde", target_arch = "wasm32"),
+    derive(serde::Serialize, serde::Deserialize)
+)]
 #[repr(C)]
 pub struct Sweep {
     /// The 2-dimensional shape being swept
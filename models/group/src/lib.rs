@@ -1,9 +1,7 @@
-use std::collections::HashMap;
-
 use fj::syntax::*;
 
-#[no_mangle]
-pub extern "C" fn model(_: &HashMap<String, String>) -> fj::Shape {
+#[fj::model]
+pub fn model() -> fj::Shape {
     #[rustfmt::skip]
     let vertices = vec![
         [-0.5, -0.5],
@@ -1,25 +1,19 @@
-use std::collections::HashMap;
-
 use fj::syntax::*;
 
-#[no_mangle]
-pub extern "C" fn model(args: &HashMap<String, String>) -> fj::Shape {
-    let outer = args
-        .get("outer")
-        .unwrap_or(&"1.0".to_owned())
-        .parse()
-        .unwrap();
-    let inner = args
-        .get("inner")
-        .unwrap_or(&"0.5".to_owned())
-        .parse()
-        .unwrap();
-    let height: f64 = args
-        .get("height")
-        .unwrap_or(&"1.0".to_owned())
-        .parse()
-        .unwrap();
+#[fj::model]
+pub fn model(
+    /// Outer radius of the spacer
+    #[param(default = 1.0, min = 0.0)]
+    outer: f64,
+
+    /// Inner radius of the spacer
+    #[param(default = 0.5, min = 0.0)]
+    inner: f64,
 
+    /// Height of the spacer
+    #[param(default = 1.0, min = 0.0)]
+    height: f64,
+) -> fj::Shape {
     let outer_edge =
         fj::Circle::from_radius(outer).with_color([0, 0, 255, 255]);
     let inner_edge = fj::Circle::from_radius(inner);